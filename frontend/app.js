@@ -20,18 +20,6 @@ const STAGE_CLASS = {
     '리뷰': 'review',
 };
 
-// Tasks 탭: 단계별 due offset (due_date로부터의 일 수, 음수 = 앞서야 함)
-// 리뷰: due-1일, 검증: 리뷰-1일(=due-2일), 개발: 검증-2일(=due-4일),
-// CCB: 개발-1일(=due-5일), 사양확인: CCB-1일(=due-6일)
-const STAGE_DUE_OFFSETS = {
-    '사양확인': -6,
-    'CCB': -5,
-    '개발': -4,
-    '검증': -2,
-    '리뷰': -1,
-    'Done': 0,
-};
-
 // ═══════════════════════════════════════════════════════════════
 // 상태 (State)
 // ═══════════════════════════════════════════════════════════════
@@ -42,6 +30,9 @@ let tasks = [];                 // Task[]
 let selectedTaskId = null;
 let selectedBacklogKeys = new Set();
 let existingTaskJiraKeys = new Set(); // kanban에 이미 추가된 jira key set
+let currentUser = null;         // 로그인한 사용자 (GET /api/auth/me)
+let boards = [];                // Board[] + 내 역할 (GET /api/boards)
+let currentBoard = localStorage.getItem('board') || 'default';
 
 // ═══════════════════════════════════════════════════════════════
 // 테마
@@ -60,6 +51,193 @@ function initTheme() {
     };
 }
 
+// ═══════════════════════════════════════════════════════════════
+// 로그인 (세션 cookie)
+// ═══════════════════════════════════════════════════════════════
+
+// 세션이 만료되어 /api가 401을 돌려주면 로그인 창을 띄운다
+const rawFetch = window.fetch.bind(window);
+window.fetch = async (...args) => {
+    const resp = await rawFetch(...args);
+    if (resp.status === 401 && !String(args[0]).startsWith('/api/auth/login')) {
+        showLogin();
+    }
+    return resp;
+};
+
+function showLogin() {
+    currentUser = null;
+    if (eventSource) eventSource.close();
+    document.getElementById('headerUser').textContent = '';
+    document.getElementById('logoutBtn').style.display = 'none';
+    document.getElementById('tokensBtn').style.display = 'none';
+    document.getElementById('loginModalOverlay').classList.add('open');
+    document.getElementById('loginUsername').focus();
+}
+
+// board 역할: viewer < member < admin
+const BOARD_ROLE_LEVEL = { viewer: 0, member: 1, admin: 2 };
+function hasBoardRole(role, boardKey = currentBoard) {
+    const board = boards.find(b => b.key === boardKey);
+    return (BOARD_ROLE_LEVEL[board?.role] ?? 0) >= BOARD_ROLE_LEVEL[role];
+}
+
+// 선택한 board의 API 경로 (/api/boards/:board/...)
+function boardApi(path) {
+    return `/api/boards/${encodeURIComponent(currentBoard)}${path}`;
+}
+
+async function loadBoards() {
+    boards = await (await fetch('/api/boards')).json();
+    if (!boards.some(b => b.key === currentBoard)) currentBoard = boards[0]?.key || 'default';
+    const select = document.getElementById('boardSelect');
+    select.innerHTML = boards.map(b => `<option value="${b.key}">${b.name}</option>`).join('');
+    select.value = currentBoard;
+    const board = boards.find(b => b.key === currentBoard);
+    document.getElementById('backlogProject').placeholder = `Project Key (기본: ${board?.jira_project || '-'})`;
+}
+
+async function switchBoard(key) {
+    currentBoard = key;
+    localStorage.setItem('board', key);
+    await loadBoards();
+    backlogTickets = [];
+    selectedBacklogKeys.clear();
+    subscribeEvents();
+    switchTab(currentTab);
+}
+
+// ═══════════════════════════════════════════════════════════════
+// 실시간 변경 알림 (SSE, GET /api/events)
+// ═══════════════════════════════════════════════════════════════
+
+let eventSource = null;
+let refreshTimer = null;
+
+// 끊기면 EventSource가 Last-Event-ID를 붙여 알아서 다시 연결한다
+function subscribeEvents() {
+    if (eventSource) eventSource.close();
+    eventSource = new EventSource(`/api/events?board=${encodeURIComponent(currentBoard)}`);
+    eventSource.onmessage = () => scheduleRefresh();
+}
+
+// 여러 이벤트가 몰려도 한 번만 다시 읽는다
+function scheduleRefresh() {
+    clearTimeout(refreshTimer);
+    refreshTimer = setTimeout(async () => {
+        if (currentTab !== 'kanban' && currentTab !== 'tasks') return;
+        await fetchTasks();
+        if (currentTab === 'kanban') renderKanban();
+        if (currentTab === 'tasks') renderTasksTable();
+    }, 300);
+}
+
+function setCurrentUser(user) {
+    currentUser = user;
+    document.getElementById('headerUser').textContent = user.display_name || user.username;
+    document.getElementById('logoutBtn').style.display = '';
+    document.getElementById('tokensBtn').style.display = '';
+}
+
+async function submitLogin(event) {
+    event.preventDefault();
+    const errorEl = document.getElementById('loginError');
+    const resp = await fetch('/api/auth/login', {
+        method: 'POST',
+        headers: { 'Content-Type': 'application/json' },
+        body: JSON.stringify({
+            username: document.getElementById('loginUsername').value.trim(),
+            password: document.getElementById('loginPassword').value,
+        }),
+    });
+    if (!resp.ok) {
+        errorEl.textContent = (await resp.json()).message;
+        errorEl.style.display = '';
+        return;
+    }
+    errorEl.style.display = 'none';
+    document.getElementById('loginPassword').value = '';
+    document.getElementById('loginModalOverlay').classList.remove('open');
+    await initAuth();
+    fetchTasks();
+}
+
+async function logout() {
+    await fetch('/api/auth/logout', { method: 'POST' });
+    showLogin();
+}
+
+// ── 개인 API 토큰 (스크립트/CI용) ──
+
+async function openTokensModal() {
+    document.getElementById('newTokenValue').style.display = 'none';
+    document.getElementById('tokensModalOverlay').classList.add('open');
+    await renderTokens();
+}
+
+function closeTokensModal() {
+    document.getElementById('tokensModalOverlay').classList.remove('open');
+}
+
+async function renderTokens() {
+    const tokens = await (await fetch('/api/tokens')).json();
+    const fmt = (d) => d ? new Date(d).toLocaleString('ko-KR') : '-';
+    document.getElementById('tokensList').innerHTML = tokens.length === 0
+        ? `<p style="color:var(--text-secondary); font-size:0.85rem;">발급된 토큰 없음</p>`
+        : `<table style="width:100%; font-size:0.8rem;">
+            <tr><th>이름</th><th>토큰</th><th>Scope</th><th>마지막 사용</th><th>만료</th><th></th></tr>
+            ${tokens.map(t => `
+                <tr style="${t.revoked_at ? 'opacity:0.5;' : ''}">
+                    <td>${t.name}</td>
+                    <td><code>${t.token_prefix}…</code></td>
+                    <td>${t.scopes}</td>
+                    <td>${fmt(t.last_used_at)}</td>
+                    <td>${fmt(t.expires_at)}</td>
+                    <td>${t.revoked_at ? '폐기됨' : `<a href="#" onclick="revokeToken(${t.id}); return false;" style="color:var(--accent-red);">폐기</a>`}</td>
+                </tr>`).join('')}
+           </table>`;
+}
+
+async function submitNewToken() {
+    const name = document.getElementById('newTokenName').value.trim();
+    const days = document.getElementById('newTokenDays').value;
+    if (!name) return;
+    const resp = await fetch('/api/tokens', {
+        method: 'POST',
+        headers: { 'Content-Type': 'application/json' },
+        body: JSON.stringify({
+            name,
+            scopes: [document.getElementById('newTokenScope').value],
+            expires_in_days: days ? Number(days) : null,
+        }),
+    });
+    const body = await resp.json();
+    if (!resp.ok) {
+        alert(body.message);
+        return;
+    }
+    const el = document.getElementById('newTokenValue');
+    el.innerHTML = `새 토큰 (지금만 볼 수 있습니다): <code>${body.token}</code>`;
+    el.style.display = '';
+    document.getElementById('newTokenName').value = '';
+    await renderTokens();
+}
+
+async function revokeToken(id) {
+    if (!confirm('이 토큰을 폐기하시겠습니까?')) return;
+    await fetch(`/api/tokens/${id}`, { method: 'DELETE' });
+    await renderTokens();
+}
+
+async function initAuth() {
+    const resp = await fetch('/api/auth/me');
+    if (!resp.ok) return false;
+    setCurrentUser(await resp.json());
+    await loadBoards();
+    subscribeEvents();
+    return true;
+}
+
 // ═══════════════════════════════════════════════════════════════
 // 탭 전환
 // ═══════════════════════════════════════════════════════════════
@@ -87,7 +265,7 @@ function switchTab(tab) {
 // ═══════════════════════════════════════════════════════════════
 
 async function queryBacklog() {
-    const project = document.getElementById('backlogProject').value.trim() || 'VPC';
+    const project = document.getElementById('backlogProject').value.trim() || null; // 없으면 board 기본 프로젝트
     const user = document.getElementById('backlogUser').value.trim() || null;
     const jql = document.getElementById('backlogJql').value.trim() || null;
 
@@ -95,7 +273,7 @@ async function queryBacklog() {
     container.innerHTML = `<div class="empty-state"><div class="empty-icon">⏳</div><p>조회 중...</p></div>`;
 
     try {
-        const resp = await fetch('/api/backlog/query', {
+        const resp = await fetch(boardApi('/backlog/query'), {
             method: 'POST',
             headers: { 'Content-Type': 'application/json' },
             body: JSON.stringify({ project, user, start_date: null, end_date: null, query_string: jql }),
@@ -247,12 +425,13 @@ async function addSelectedToKanban() {
     const keys = Array.from(selectedBacklogKeys);
 
     try {
-        const resp = await fetch('/api/tasks/from-backlog', {
+        const resp = await fetch(boardApi('/tasks/from-backlog'), {
             method: 'POST',
             headers: { 'Content-Type': 'application/json' },
-            body: JSON.stringify({ ticket_keys: keys, include_subtasks: includeSubtasks }),
+            body: JSON.stringify({ ticket_keys: keys, include_subtasks: includeSubtasks, include_links: true }),
         });
         const created = await resp.json();
+        if (!resp.ok) throw new Error(created.message);
         alert(`${created.length}개의 Task가 Kanban에 추가되었습니다.`);
 
         // 상태 갱신
@@ -271,7 +450,7 @@ async function addSelectedToKanban() {
 
 async function fetchTasks() {
     try {
-        const resp = await fetch('/api/tasks');
+        const resp = await fetch(boardApi('/tasks'));
         tasks = await resp.json();
     } catch (err) {
         console.error('Failed to fetch tasks:', err);
@@ -415,15 +594,72 @@ async function moveTaskStatus(taskId, newStatus) {
 }
 
 async function moveTaskToStatusStage(taskId, newStatus, stage) {
-    await fetch(`/api/tasks/${taskId}/status`, {
-        method: 'POST',
-        headers: { 'Content-Type': 'application/json' },
-        body: JSON.stringify({ status: newStatus, stage }),
-    });
+    await postTaskStatus(taskId, newStatus, stage);
     await fetchTasks();
     renderKanban();
 }
 
+// 읽어 둔 task 버전 (다른 사람이 먼저 바꿨으면 서버가 409 version_conflict)
+function ifMatch(taskId) {
+    const task = tasks.find(t => t.id === taskId);
+    return task ? { 'If-Match': `"${task.version}"` } : {};
+}
+
+// 저장 후 응답의 ETag로 캐시한 버전 갱신 (이어서 다른 요청을 보낼 때 필요)
+function rememberVersion(taskId, resp) {
+    const task = tasks.find(t => t.id === taskId);
+    const version = Number(resp.headers.get('ETag')?.replaceAll('"', ''));
+    if (task && version) task.version = version;
+}
+
+async function handleVersionConflict(err) {
+    alert(`다른 사용자가 먼저 이 Task를 수정했습니다. 최신 내용을 다시 불러옵니다.\n(${err.message})`);
+    await fetchTasks();
+    if (currentTab === 'kanban') renderKanban();
+    if (currentTab === 'tasks') renderTasksTable();
+}
+
+/**
+ * 상태/단계 변경 요청.
+ * WIP 한도 초과나 blocker가 남아 있으면 사유를 받아 override로,
+ * 전이 규칙이 comment를 요구하면 comment를 받아 재시도
+ */
+async function postTaskStatus(taskId, status, stage) {
+    const extra = {};
+    for (;;) {
+        const resp = await fetch(`/api/tasks/${taskId}/status`, {
+            method: 'POST',
+            headers: { 'Content-Type': 'application/json', ...ifMatch(taskId) },
+            body: JSON.stringify({ status, stage, ...extra }),
+        });
+        if (resp.ok) {
+            rememberVersion(taskId, resp);
+            return true;
+        }
+
+        const err = await resp.json().catch(() => null);
+        if (err?.error === 'version_conflict') {
+            await handleVersionConflict(err);
+            return false;
+        } else if ((err?.error === 'wip_limit_exceeded' || err?.error === 'blocked') && !extra.override) {
+            const msg = err.error === 'blocked'
+                ? `선행 task(${err.blockers.map(b => b.jira_ticket_key || '#' + b.id).join(', ')})가 끝나지 않았습니다.`
+                : `${err.scope} WIP 한도(${err.wip_limit}) 초과입니다.`;
+            const reason = prompt(`${msg} 그래도 이동하려면 사유를 입력하세요.`);
+            if (!reason) return false;
+            Object.assign(extra, { override: true, reason });
+        } else if (err?.error === 'transition_requirements_missing'
+            && err.missing?.length === 1 && err.missing[0] === 'comment' && !extra.comment) {
+            const comment = prompt(`${err.rule.from_state} → ${err.rule.to_state} 이동 사유(comment)를 입력하세요.`);
+            if (!comment) return false;
+            extra.comment = comment;
+        } else {
+            alert(err?.message || '상태 변경에 실패했습니다.');
+            return false;
+        }
+    }
+}
+
 // ═══════════════════════════════════════════════════════════════
 // ── TASKS 탭 (일정표)
 // ═══════════════════════════════════════════════════════════════
@@ -470,38 +706,6 @@ function flattenTree(nodes, depth = 0, parentStart = null, parentDue = null) {
     return result;
 }
 
-/**
- * 이상적 진행도 계산 (0~100)
- * due_date가 null이면 진행도 0 (무한)
- */
-function calcIdealProgress(startStr, dueStr) {
-    if (!dueStr) return 0;
-    const now = Date.now();
-    const start = new Date(startStr).getTime();
-    const due = new Date(dueStr).getTime();
-    if (due <= start) return 100;
-    return Math.min(100, Math.max(0, Math.round((now - start) / (due - start) * 100)));
-}
-
-/**
- * 실제 진행도 계산 (단계 기반)
- * 각 단계의 이상적 완료 시점을 due_date 기준으로 계산하여,
- * 현재 단계가 몇 % 위치에 있는지 반환
- */
-function calcActualProgress(status, stage, startStr, dueStr) {
-    if (status === 'Done') return 100;
-    if (status === 'Pending') return 0;
-    if (!stage) return 5;
-
-    const stageOrder = STAGES; // 사양확인, CCB, 개발, 검증, 리뷰
-    const idx = stageOrder.indexOf(stage); // 0~4
-    if (idx < 0) return 5;
-
-    // 실제 진행도: 현재 단계가 시작했으므로 해당 단계의 시작 비율 반환
-    // 5단계를 균등하게 나누면 각 단계 = 20%
-    return Math.round((idx / stageOrder.length) * 100) + 5; // 5% 최소값
-}
-
 function getStageColorClass(status, stage) {
     if (status === 'Done') return 'stage-color-done';
     if (status === 'Pending') return 'stage-color-pending';
@@ -530,8 +734,9 @@ function renderTasksTable() {
         if (depthClass) tr.classList.add(depthClass);
         if (row.status === 'Done') tr.classList.add('task-row-done');
 
-        const idealPct = calcIdealProgress(row.effectiveStart, row.effectiveDue);
-        const actualPct = calcActualProgress(row.status, row.stage, row.effectiveStart, row.effectiveDue);
+        // 진행도는 서버에서 계산 (ideal_progress / actual_progress / is_late)
+        const idealPct = row.ideal_progress;
+        const actualPct = row.actual_progress;
         const colorClass = getStageColorClass(row.status, row.stage);
 
         const statusChipClass = row.status === 'Pending' ? 'pending'
@@ -539,6 +744,11 @@ function renderTasksTable() {
         const statusLabel = row.status === 'Pending' ? '예정'
             : row.status === 'InProgress' ? (row.stage || '진행중') : '완료';
 
+        // 단계별 계획 완료일은 서버에서 영업일 기준으로 계산 (stage_deadlines)
+        const stageDeadline = (row.stage_deadlines || []).find(d => d.stage === row.stage);
+        const statusTitle = row.status === 'InProgress' && stageDeadline
+            ? `${row.stage} 완료 목표: ${stageDeadline.planned_date}${row.stage_overdue ? ' (지남)' : ''}` : '';
+
         // 들여쓰기 표현 (depth)
         const indent = row.depth > 0
             ? `<span style="color:var(--text-secondary); margin-right:4px;">${'└'.padStart(row.depth, '·')}</span>`
@@ -564,7 +774,7 @@ function renderTasksTable() {
                 </div>
             </td>
             <td style="font-size:0.82rem;">${row.assignee || '<span style="color:var(--text-secondary);">–</span>'}</td>
-            <td><span class="status-chip ${statusChipClass}">${statusLabel}</span></td>
+            <td><span class="status-chip ${statusChipClass}" title="${statusTitle}">${statusLabel}${row.stage_overdue ? ' ⚠' : ''}</span></td>
             <td class="date-cell">${startDisplay}</td>
             <td class="date-cell">${dueDisplay}</td>
             <td>
@@ -574,6 +784,7 @@ function renderTasksTable() {
                 </div>
                 <div class="progress-label">
                     이상 ${idealPct}% · 실제 ${actualPct}%
+                    ${row.is_late ? ' · <span style="color:var(--accent-red);">지연</span>' : ''}
                     ${!row.effectiveDue ? ' · <span style="color:var(--text-secondary);">기한 없음</span>' : ''}
                 </div>
             </td>
@@ -585,6 +796,44 @@ function renderTasksTable() {
     });
 }
 
+// 일정표를 CSV / Excel 파일로 내려받기 (서버에서 트리 순서로 들여쓰기)
+function exportTasks(format) {
+    window.location.href = boardApi(`/tasks/export?format=${format}`);
+}
+
+// CSV 가져오기: 먼저 dry-run으로 검사 결과를 보여 주고 확인하면 추가
+async function importTasks(input) {
+    const file = input.files[0];
+    input.value = '';
+    if (!file) return;
+    const csv = await file.text();
+    const post = (dryRun) => fetch(boardApi(`/tasks/import?dry_run=${dryRun}`), {
+        method: 'POST',
+        headers: { 'Content-Type': 'text/csv' },
+        body: csv,
+    });
+
+    const preview = await (await post(true)).json();
+    if (preview.error) {
+        alert(preview.message);
+        return;
+    }
+    if (preview.errors.length) {
+        alert('가져올 수 없습니다:\n' + preview.errors.map(e => `${e.line}행: ${e.message}`).join('\n'));
+        return;
+    }
+    if (!confirm(`${preview.created}개 Task를 추가합니다. (이미 있는 Jira 티켓 ${preview.skipped}개는 건너뜀)`)) return;
+
+    const resp = await post(false);
+    const result = await resp.json();
+    if (!resp.ok) {
+        alert(result.message);
+        return;
+    }
+    await fetchTasks();
+    renderTasksTable();
+}
+
 // ═══════════════════════════════════════════════════════════════
 // ── Task Modal (상세 / 수정)
 // ═══════════════════════════════════════════════════════════════
@@ -619,6 +868,7 @@ async function openTaskModal(taskId) {
             </div>
             <button class="modal-close-btn" onclick="closeTaskModal()">✕</button>
         </div>
+        ${task.description_html ? `<div class="markdown-body" style="margin-bottom:1rem;">${task.description_html}</div>` : ''}
 
         <div class="form-grid">
             <div class="form-group span-2">
@@ -672,7 +922,12 @@ async function openTaskModal(taskId) {
         <div style="display:flex; gap:0.75rem; margin-top:1.25rem; flex-wrap:wrap;">
             <button onclick="saveTaskDetail(${taskId})">저장</button>
             <button onclick="closeTaskModal()" class="btn-secondary">취소</button>
-            <button onclick="deleteTask(${taskId})" class="btn-danger" style="margin-left:auto;">삭제</button>
+            ${hasBoardRole('member') && boards.some(b => b.key !== currentBoard && hasBoardRole('member', b.key)) ? `
+                <select onchange="moveTaskToBoard(${taskId}, this.value)" style="width:auto;">
+                    <option value="">다른 board로 이동...</option>
+                    ${boards.filter(b => b.key !== currentBoard && hasBoardRole('member', b.key)).map(b => `<option value="${b.key}">${b.name}</option>`).join('')}
+                </select>` : ''}
+            ${hasBoardRole('admin') ? `<button onclick="deleteTask(${taskId})" class="btn-danger" style="margin-left:auto;">삭제</button>` : ''}
         </div>
 
         <div class="comment-section">
@@ -680,21 +935,32 @@ async function openTaskModal(taskId) {
             <div class="comment-list">
                 ${comments.length === 0
             ? `<p style="color:var(--text-secondary); font-size:0.85rem;">코멘트 없음</p>`
-            : comments.map(c => `
+            : comments.map(c => c.deleted_at ? `
+                        <div class="comment-item">
+                            <div class="comment-body" style="color:var(--text-secondary); font-style:italic;">삭제된 코멘트입니다</div>
+                        </div>
+                    ` : `
                         <div class="comment-item">
                             <div class="comment-meta">
                                 <span>${c.author}</span>
-                                <span>${new Date(c.created_at).toLocaleString('ko-KR')}</span>
+                                <span>${new Date(c.created_at).toLocaleString('ko-KR')}${c.edited_at ? ' (수정됨)' : ''}</span>
                             </div>
-                            <div class="comment-body">${c.content}</div>
+                            <div class="comment-body markdown-body">${c.content_html}</div>
                             ${c.attachments ? `<div style="margin-top:0.4rem; font-size:0.78rem;"><a href="${c.attachments}" target="_blank" style="color:var(--accent-blue);">📎 첨부</a></div>` : ''}
+                            ${(c.files || []).map(f => `<div style="margin-top:0.4rem; font-size:0.78rem;"><a href="/api/attachments/${f.id}" target="_blank" style="color:var(--accent-blue);">📎 ${f.file_name}</a> <span style="color:var(--text-secondary);">(${Math.ceil(f.size / 1024)} KB)</span></div>`).join('')}
+                            ${c.author === currentUser?.username || hasBoardRole('admin') ? `
+                                <div style="margin-top:0.4rem; font-size:0.75rem; display:flex; gap:0.5rem;">
+                                    <a href="#" onclick="editComment(${taskId}, ${c.id}); return false;" style="color:var(--text-secondary);">수정</a>
+                                    <a href="#" onclick="deleteComment(${taskId}, ${c.id}); return false;" style="color:var(--text-secondary);">삭제</a>
+                                </div>` : ''}
                         </div>
                     `).join('')
         }
             </div>
-            <div class="comment-input-area">
+            <div class="comment-input-area" style="${hasBoardRole('member') ? '' : 'display:none;'}">
                 <textarea id="commentInput" placeholder="코멘트를 입력하세요..."></textarea>
                 <input type="text" id="commentAttachment" placeholder="첨부 URL (선택)">
+                <input type="file" id="commentFiles" multiple>
                 <button onclick="submitComment(${taskId})">코멘트 추가</button>
             </div>
         </div>
@@ -728,26 +994,20 @@ async function saveTaskDetail(taskId) {
     const task = tasks.find(t => t.id === taskId);
 
     // 상태 변경이 있으면 먼저 처리
+    let moved = true;
     if (pendingStatus && pendingStatus !== task?.status) {
         const stage = pendingStatus === 'InProgress'
             ? (document.getElementById('modalStage')?.value || '사양확인')
             : null;
-        await fetch(`/api/tasks/${taskId}/status`, {
-            method: 'POST',
-            headers: { 'Content-Type': 'application/json' },
-            body: JSON.stringify({ status: pendingStatus, stage }),
-        });
+        moved = await postTaskStatus(taskId, pendingStatus, stage);
     } else if (task?.status === 'InProgress') {
         // 상태는 그대로인데 stage만 변경된 경우
         const newStage = document.getElementById('modalStage')?.value;
         if (newStage && newStage !== task.stage) {
-            await fetch(`/api/tasks/${taskId}/status`, {
-                method: 'POST',
-                headers: { 'Content-Type': 'application/json' },
-                body: JSON.stringify({ status: 'InProgress', stage: newStage }),
-            });
+            moved = await postTaskStatus(taskId, 'InProgress', newStage);
         }
     }
+    if (!moved) return;
 
     // 메타데이터 업데이트
     const payload = {
@@ -759,12 +1019,42 @@ async function saveTaskDetail(taskId) {
         due_date: document.getElementById('modalDueDate')?.value || null,
     };
 
-    await fetch(`/api/tasks/${taskId}`, {
+    const resp = await fetch(`/api/tasks/${taskId}`, {
         method: 'PUT',
-        headers: { 'Content-Type': 'application/json' },
+        headers: { 'Content-Type': 'application/json', ...ifMatch(taskId) },
         body: JSON.stringify(payload),
     });
+    if (!resp.ok) {
+        const err = await resp.json().catch(() => null);
+        if (err?.error === 'version_conflict') {
+            closeTaskModal();
+            await handleVersionConflict(err);
+        } else {
+            alert(err?.message || '저장에 실패했습니다.');
+        }
+        return;
+    }
+
+    await fetchTasks();
+    closeTaskModal();
+    if (currentTab === 'kanban') renderKanban();
+    if (currentTab === 'tasks') renderTasksTable();
+}
 
+// 하위 task도 함께 이동
+async function moveTaskToBoard(taskId, boardKey) {
+    if (!boardKey) return;
+    const board = boards.find(b => b.key === boardKey);
+    if (!confirm(`이 Task와 하위 Task를 모두 ${board.name} board로 옮기시겠습니까?`)) return;
+    const resp = await fetch(`/api/tasks/${taskId}/board`, {
+        method: 'POST',
+        headers: { 'Content-Type': 'application/json' },
+        body: JSON.stringify({ board: boardKey }),
+    });
+    if (!resp.ok) {
+        alert((await resp.json()).message);
+        return;
+    }
     await fetchTasks();
     closeTaskModal();
     if (currentTab === 'kanban') renderKanban();
@@ -773,7 +1063,11 @@ async function saveTaskDetail(taskId) {
 
 async function deleteTask(taskId) {
     if (!confirm('이 Task를 삭제하시겠습니까? 삭제된 Task는 복구할 수 없습니다.')) return;
-    await fetch(`/api/tasks/${taskId}`, { method: 'DELETE' });
+    const resp = await fetch(`/api/tasks/${taskId}`, { method: 'DELETE' });
+    if (!resp.ok) {
+        alert((await resp.json()).message);
+        return;
+    }
     await fetchTasks();
     closeTaskModal();
     if (currentTab === 'kanban') renderKanban();
@@ -783,16 +1077,54 @@ async function deleteTask(taskId) {
 async function submitComment(taskId) {
     const content = document.getElementById('commentInput').value.trim();
     const attachments = document.getElementById('commentAttachment').value.trim();
+    const files = document.getElementById('commentFiles').files;
     if (!content) return;
 
-    await fetch(`/api/tasks/${taskId}/comments`, {
+    // 파일을 먼저 올리고 받은 id를 comment에 연결
+    let attachmentIds = [];
+    if (files.length > 0) {
+        const form = new FormData();
+        for (const f of files) form.append('file', f);
+        const upResp = await fetch(`/api/tasks/${taskId}/attachments`, { method: 'POST', body: form });
+        if (!upResp.ok) {
+            alert((await upResp.json()).message);
+            return;
+        }
+        attachmentIds = (await upResp.json()).map(a => a.id);
+    }
+
+    const resp = await fetch(`/api/tasks/${taskId}/comments`, {
         method: 'POST',
         headers: { 'Content-Type': 'application/json' },
-        body: JSON.stringify({ author: 'Team Member', content, attachments: attachments || null }),
+        body: JSON.stringify({ content, attachments: attachments || null, attachment_ids: attachmentIds }),
     });
+    if (!resp.ok) alert((await resp.json()).message);
     openTaskModal(taskId); // 모달 새로고침
 }
 
+async function editComment(taskId, commentId) {
+    const current = (await (await fetch(`/api/tasks/${taskId}/comments`)).json()).find(c => c.id === commentId);
+    const content = prompt('코멘트 수정', current?.content ?? '');
+    if (!content || !content.trim()) return;
+
+    const resp = await fetch(`/api/tasks/${taskId}/comments/${commentId}`, {
+        method: 'PUT',
+        headers: { 'Content-Type': 'application/json' },
+        body: JSON.stringify({ content }),
+    });
+    if (!resp.ok) alert((await resp.json()).message);
+    openTaskModal(taskId);
+}
+
+async function deleteComment(taskId, commentId) {
+    if (!confirm('코멘트를 삭제하시겠습니까?')) return;
+    const resp = await fetch(`/api/tasks/${taskId}/comments/${commentId}`, {
+        method: 'DELETE',
+    });
+    if (!resp.ok) alert((await resp.json()).message);
+    openTaskModal(taskId);
+}
+
 function closeTaskModal() {
     document.getElementById('taskModalOverlay').classList.remove('open');
     selectedTaskId = null;
@@ -827,11 +1159,15 @@ async function submitNewTask() {
         jira_url: null,
     };
 
-    await fetch('/api/tasks', {
+    const resp = await fetch(boardApi('/tasks'), {
         method: 'POST',
         headers: { 'Content-Type': 'application/json' },
         body: JSON.stringify(payload),
     });
+    if (!resp.ok) {
+        alert((await resp.json()).message);
+        return;
+    }
 
     closeNewTaskModal();
     await fetchTasks();
@@ -864,5 +1200,13 @@ document.getElementById('newTaskModalOverlay').onclick = (e) => {
 
 // 초기 실행
 initTheme();
+
+// description/comment 안의 #123, VPC-101 링크 → 해당 task 모달 열기
+document.addEventListener('click', (e) => {
+    const ref = e.target.closest('a.task-ref');
+    if (!ref) return;
+    e.preventDefault();
+    openTaskModal(Number(ref.dataset.taskId));
+});
 // 최초에는 kanban 탭 데이터를 미리 로드 (탭 전환 시 바로 보이게)
-fetchTasks();
+initAuth().then(ok => { if (ok) fetchTasks(); });
@@ -0,0 +1,425 @@
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+
+use chrono::{Days, NaiveDate, Utc};
+use sqlx::{FromRow, SqlitePool};
+
+use crate::error::ApiError;
+use crate::models::{ProjectSchedule, ProjectedChange, ScheduleAnalysis, TaskSchedule, WhatIfResult};
+use crate::schedule;
+
+// ─────────────────────────────────────────────
+// 일정 분석: blocks 링크와 parent/child 관계 위에서 critical path와 float 계산
+//
+// - 예상 시작 = max(계획 시작, 막고 있는 task들의 예상 완료)
+// - 예상 완료 = 예상 시작 + 계획 기간 (끝나지 않은 task는 오늘 이전일 수 없음),
+//   상위 task는 하위 task의 예상 완료보다 빠를 수 없음
+// - 최종 허용 완료 = min(프로젝트 due, 상위 task due, 후속 task의 최종 허용 시작)
+// - float = 최종 허용 완료 - 예상 완료. 0 이하면 조금만 밀려도 상위/프로젝트 일정이 밀림
+// ─────────────────────────────────────────────
+
+#[derive(FromRow, Clone)]
+struct TaskRow {
+    id: i64,
+    title: String,
+    status: String,
+    parent_task_id: Option<i64>,
+    project_key: Option<String>,
+}
+
+struct Node {
+    row: TaskRow,
+    start: NaiveDate,
+    due: Option<NaiveDate>,
+}
+
+#[derive(Default, Clone, Copy)]
+struct Computed {
+    projected_start: Option<NaiveDate>,
+    projected_finish: Option<NaiveDate>,
+    latest_finish: Option<NaiveDate>,
+}
+
+struct Graph {
+    nodes: BTreeMap<i64, Node>,
+    blockers: HashMap<i64, Vec<i64>>,   // target → sources
+    successors: HashMap<i64, Vec<i64>>, // source → targets
+    children: HashMap<i64, Vec<i64>>,
+}
+
+async fn load_graph(db: &SqlitePool, board_id: i64, project_key: Option<&str>) -> Result<Graph, sqlx::Error> {
+    let rows = sqlx::query_as::<_, TaskRow>(
+        "SELECT id, title, status, parent_task_id, project_key FROM tasks
+         WHERE board_id = ?1 AND (?2 IS NULL OR project_key = ?2)"
+    )
+    .bind(board_id)
+    .bind(project_key)
+    .fetch_all(db)
+    .await?;
+    let dates = schedule::effective_schedule(db, board_id, None).await?;
+
+    let nodes: BTreeMap<i64, Node> = rows
+        .into_iter()
+        .filter_map(|row| {
+            let &(start, due) = dates.get(&row.id)?;
+            Some((row.id, Node { row, start, due }))
+        })
+        .collect();
+
+    let edges: Vec<(i64, i64)> = sqlx::query_as(
+        "SELECT l.source_task_id, l.target_task_id FROM task_links l
+           JOIN tasks t ON t.id = l.source_task_id
+          WHERE l.link_type = 'blocks' AND t.board_id = ?"
+    )
+    .bind(board_id)
+    .fetch_all(db)
+    .await?;
+
+    let mut graph = Graph {
+        blockers: HashMap::new(),
+        successors: HashMap::new(),
+        children: HashMap::new(),
+        nodes,
+    };
+    for (source, target) in edges {
+        if graph.nodes.contains_key(&source) && graph.nodes.contains_key(&target) {
+            graph.blockers.entry(target).or_default().push(source);
+            graph.successors.entry(source).or_default().push(target);
+        }
+    }
+    for node in graph.nodes.values() {
+        if let Some(pid) = node.row.parent_task_id.filter(|p| graph.nodes.contains_key(p)) {
+            graph.children.entry(pid).or_default().push(node.row.id);
+        }
+    }
+    Ok(graph)
+}
+
+fn days_between(from: NaiveDate, to: NaiveDate) -> i64 {
+    (to - from).num_days()
+}
+
+fn add_days(date: NaiveDate, days: i64) -> NaiveDate {
+    if days >= 0 {
+        date + Days::new(days as u64)
+    } else {
+        date - Days::new(days.unsigned_abs())
+    }
+}
+
+/// blocker → task, child → parent 순서의 위상 정렬.
+/// (parent가 자기 child를 막는 식의 사이클에 걸린 task는 skipped로 돌려준다)
+fn topo_order(graph: &Graph) -> (Vec<i64>, Vec<i64>) {
+    let mut indegree: HashMap<i64, usize> = graph.nodes.keys().map(|&id| (id, 0)).collect();
+    let mut out: HashMap<i64, Vec<i64>> = HashMap::new();
+    for (&target, sources) in &graph.blockers {
+        for &s in sources {
+            out.entry(s).or_default().push(target);
+            *indegree.get_mut(&target).unwrap() += 1;
+        }
+    }
+    for (&parent, kids) in &graph.children {
+        for &c in kids {
+            out.entry(c).or_default().push(parent);
+            *indegree.get_mut(&parent).unwrap() += 1;
+        }
+    }
+
+    let mut queue: VecDeque<i64> = graph
+        .nodes
+        .keys()
+        .copied()
+        .filter(|id| indegree[id] == 0)
+        .collect();
+    let mut order = Vec::with_capacity(graph.nodes.len());
+    while let Some(id) = queue.pop_front() {
+        order.push(id);
+        for &next in out.get(&id).map(Vec::as_slice).unwrap_or_default() {
+            let d = indegree.get_mut(&next).unwrap();
+            *d -= 1;
+            if *d == 0 {
+                queue.push_back(next);
+            }
+        }
+    }
+    let visited: HashSet<i64> = order.iter().copied().collect();
+    let skipped = graph
+        .nodes
+        .keys()
+        .copied()
+        .filter(|id| !visited.contains(id))
+        .collect();
+    (order, skipped)
+}
+
+fn project_dues(graph: &Graph) -> ProjectDues {
+    let mut dues = ProjectDues::new();
+    for node in graph.nodes.values() {
+        let entry = dues.entry(node.row.project_key.clone()).or_default();
+        *entry = (*entry).max(node.due);
+    }
+    dues
+}
+
+type ProjectDues = BTreeMap<Option<String>, Option<NaiveDate>>;
+
+fn compute(graph: &Graph, order: &[i64], dues: &ProjectDues, today: NaiveDate) -> HashMap<i64, Computed> {
+    let mut result: HashMap<i64, Computed> = HashMap::new();
+    let open = |id: &i64| graph.nodes.get(id).is_some_and(|n| n.row.status != "Done");
+
+    // forward pass: 예상 시작/완료
+    for id in order {
+        let node = &graph.nodes[id];
+        if node.row.status == "Done" {
+            result.insert(*id, Computed::default());
+            continue;
+        }
+        let start = graph
+            .blockers
+            .get(id)
+            .into_iter()
+            .flatten()
+            .filter(|b| open(b))
+            .filter_map(|b| result.get(b).and_then(|c| c.projected_finish))
+            .fold(node.start, NaiveDate::max);
+        let duration = node.due.map(|d| days_between(node.start, d).max(0)).unwrap_or(0);
+        let finish = graph
+            .children
+            .get(id)
+            .into_iter()
+            .flatten()
+            .filter_map(|c| result.get(c).and_then(|c| c.projected_finish))
+            .fold(add_days(start, duration).max(today), NaiveDate::max);
+        result.insert(
+            *id,
+            Computed { projected_start: Some(start), projected_finish: Some(finish), latest_finish: None },
+        );
+    }
+
+    // backward pass: 최종 허용 완료
+    for id in order.iter().rev() {
+        let node = &graph.nodes[id];
+        if node.row.status == "Done" {
+            continue;
+        }
+        let mut latest = dues.get(&node.row.project_key).copied().flatten();
+        let mut tighten = |d: Option<NaiveDate>| {
+            if let Some(d) = d {
+                latest = Some(latest.map_or(d, |l| l.min(d)));
+            }
+        };
+
+        if let Some(pid) = node.row.parent_task_id
+            && let Some(parent) = graph.nodes.get(&pid).filter(|p| p.row.status != "Done")
+        {
+            tighten(parent.due);
+            tighten(result.get(&pid).and_then(|c| c.latest_finish));
+        }
+        for s in graph.successors.get(id).into_iter().flatten().filter(|s| open(s)) {
+            let Some(c) = result.get(s) else { continue };
+            if let (Some(lf), Some(ps), Some(pf)) = (c.latest_finish, c.projected_start, c.projected_finish) {
+                tighten(Some(add_days(lf, -days_between(ps, pf))));
+            }
+        }
+
+        if let Some(c) = result.get_mut(id) {
+            c.latest_finish = latest;
+        }
+    }
+
+    result
+}
+
+fn project_schedules(
+    graph: &Graph,
+    computed: &HashMap<i64, Computed>,
+    dues: &ProjectDues,
+) -> Vec<ProjectSchedule> {
+    dues.clone()
+        .into_iter()
+        .map(|(project_key, due_date)| {
+            let projected_finish = graph
+                .nodes
+                .values()
+                .filter(|n| n.row.project_key == project_key)
+                .filter_map(|n| computed.get(&n.row.id).and_then(|c| c.projected_finish))
+                .max();
+            ProjectSchedule {
+                is_late: matches!((due_date, projected_finish), (Some(d), Some(f)) if f > d),
+                project_key,
+                due_date,
+                projected_finish,
+            }
+        })
+        .collect()
+}
+
+/// 가장 늦게 끝나는 critical task에서 시작해, 그 task의 시작(막고 있는 task)이나
+/// 완료(하위 task)를 정한 critical 선행 task를 거슬러 올라간 사슬. 앞에서부터 순서대로.
+fn critical_path(graph: &Graph, tasks: &[TaskSchedule]) -> Vec<i64> {
+    let critical: HashMap<i64, &TaskSchedule> = tasks.iter().filter(|t| t.critical).map(|t| (t.task_id, t)).collect();
+    let latest = |ids: &mut dyn Iterator<Item = i64>| {
+        ids.filter_map(|id| critical.get(&id))
+            .max_by_key(|t| (t.projected_finish, std::cmp::Reverse(t.task_id)))
+            .map(|t| t.task_id)
+    };
+
+    let mut path = Vec::new();
+    let mut seen = HashSet::new();
+    let mut current = latest(&mut critical.keys().copied());
+    while let Some(id) = current.filter(|id| seen.insert(*id)) {
+        path.push(id);
+        let blockers = graph.blockers.get(&id).into_iter().flatten();
+        let children = graph.children.get(&id).into_iter().flatten();
+        current = latest(&mut blockers.chain(children).copied());
+    }
+    path.reverse();
+    path
+}
+
+/// board 하나의 일정 분석. what_if = (task_id, 새 due_date)이면 그 변경을 적용한 결과와
+/// 변경 전 대비 차이를 돌려준다. what-if task가 분석 범위(board, project_key)에 없으면 400.
+/// DB에는 아무것도 쓰지 않는다.
+pub async fn analyze(
+    db: &SqlitePool,
+    board_id: i64,
+    project_key: Option<&str>,
+    what_if: Option<(i64, NaiveDate)>,
+) -> Result<ScheduleAnalysis, ApiError> {
+    let mut graph = load_graph(db, board_id, project_key).await?;
+    if let Some((task_id, _)) = what_if
+        && !graph.nodes.contains_key(&task_id)
+    {
+        return Err(ApiError::bad_request(format!(
+            "task {} is not part of the analysis (project {})",
+            task_id,
+            project_key.unwrap_or("any")
+        )));
+    }
+    let today = Utc::now().date_naive();
+    let (order, skipped) = topo_order(&graph);
+    // what-if에서도 프로젝트 due는 변경 전 기준으로 본다
+    let dues = project_dues(&graph);
+
+    let mut baseline = None;
+    if let Some((task_id, due)) = what_if {
+        let before = compute(&graph, &order, &dues, today);
+        let projects_before = project_schedules(&graph, &before, &dues);
+        baseline = Some((before, projects_before));
+        // due가 없는 하위 task는 상위 task의 due를 따르므로 실효 일정을 다시 계산한다
+        let dates = schedule::effective_schedule(db, board_id, Some((task_id, due))).await?;
+        for (id, node) in graph.nodes.iter_mut() {
+            if let Some(&(start, due)) = dates.get(id) {
+                node.start = start;
+                node.due = due;
+            }
+        }
+    }
+
+    let computed = compute(&graph, &order, &dues, today);
+
+    let tasks: Vec<TaskSchedule> = order
+        .iter()
+        .map(|id| {
+            let node = &graph.nodes[id];
+            let c = computed.get(id).copied().unwrap_or_default();
+            let parent_due = node.row.parent_task_id.and_then(|p| graph.nodes.get(&p)).and_then(|p| p.due);
+            let float_days = match (c.latest_finish, c.projected_finish) {
+                (Some(lf), Some(pf)) => Some(days_between(pf, lf)),
+                _ => None,
+            };
+            TaskSchedule {
+                task_id: *id,
+                title: node.row.title.clone(),
+                status: node.row.status.clone(),
+                parent_task_id: node.row.parent_task_id,
+                project_key: node.row.project_key.clone(),
+                planned_start: node.start,
+                planned_finish: node.due,
+                projected_start: c.projected_start,
+                projected_finish: c.projected_finish,
+                latest_finish: c.latest_finish,
+                float_days,
+                critical: float_days.is_some_and(|f| f <= 0),
+                delays_parent: matches!((c.projected_finish, parent_due), (Some(f), Some(d)) if f > d),
+            }
+        })
+        .collect();
+
+    let critical_path = critical_path(&graph, &tasks);
+
+    let projects = project_schedules(&graph, &computed, &dues);
+
+    let what_if = match (what_if, baseline) {
+        (Some((task_id, due_date)), Some((before, projects_before))) => {
+            let changed = order
+                .iter()
+                .filter_map(|id| {
+                    let b = before.get(id).and_then(|c| c.projected_finish);
+                    let a = computed.get(id).and_then(|c| c.projected_finish);
+                    (a != b).then_some(ProjectedChange {
+                        task_id: *id,
+                        projected_finish_before: b,
+                        projected_finish_after: a,
+                    })
+                })
+                .collect();
+            Some(WhatIfResult { task_id, due_date, projects_before, projects_after: projects.clone(), changed })
+        }
+        _ => None,
+    };
+
+    Ok(ScheduleAnalysis { tasks, critical_path, projects, skipped, what_if })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support;
+
+    fn date(s: &str) -> NaiveDate {
+        s.parse().unwrap()
+    }
+
+    async fn board(statements: &[&str]) -> SqlitePool {
+        let pool = test_support::memory_db().await;
+        for statement in statements {
+            sqlx::query(statement).execute(&pool).await.unwrap();
+        }
+        pool
+    }
+
+    #[tokio::test]
+    async fn what_if_moves_children_that_inherit_the_due() {
+        let pool = board(&[
+            "INSERT INTO tasks (title, board_id, start_date, due_date) VALUES ('parent', 1, '2030-01-01', '2030-01-10')",
+            "INSERT INTO tasks (title, board_id, parent_task_id, start_date) VALUES ('child', 1, 1, '2030-01-01')",
+        ])
+        .await;
+        let analysis = analyze(&pool, 1, None, Some((1, date("2030-01-20")))).await.unwrap();
+
+        let child = analysis.tasks.iter().find(|t| t.task_id == 2).unwrap();
+        assert_eq!(child.planned_finish, Some(date("2030-01-20")));
+        assert_eq!(child.projected_finish, Some(date("2030-01-20")));
+        let what_if = analysis.what_if.unwrap();
+        let changed = what_if.changed.iter().find(|c| c.task_id == 2).expect("child should change");
+        assert_eq!(changed.projected_finish_before, Some(date("2030-01-10")));
+        assert_eq!(changed.projected_finish_after, Some(date("2030-01-20")));
+        assert_eq!(what_if.projects_after[0].projected_finish, Some(date("2030-01-20")));
+    }
+
+    #[tokio::test]
+    async fn critical_path_follows_predecessors() {
+        // a blocks b, 둘 다 프로젝트 due(01-10)를 넘긴다. c는 float 0이지만 사슬과 상관없다.
+        let pool = board(&[
+            "INSERT INTO tasks (title, board_id, start_date, due_date) VALUES ('a', 1, '2030-01-01', '2030-01-05')",
+            "INSERT INTO tasks (title, board_id, start_date, due_date) VALUES ('b', 1, '2030-01-01', '2030-01-10')",
+            "INSERT INTO tasks (title, board_id, start_date, due_date) VALUES ('c', 1, '2030-01-01', '2030-01-10')",
+            "INSERT INTO task_links (source_task_id, target_task_id, link_type) VALUES (1, 2, 'blocks')",
+        ])
+        .await;
+        let analysis = analyze(&pool, 1, None, None).await.unwrap();
+
+        assert!(analysis.tasks.iter().all(|t| t.critical));
+        assert_eq!(analysis.critical_path, vec![1, 2]);
+    }
+}
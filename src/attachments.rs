@@ -0,0 +1,153 @@
+use std::path::PathBuf;
+
+use sha2::{Digest, Sha256};
+use sqlx::SqliteConnection;
+
+use crate::boards;
+use crate::error::ApiError;
+use crate::models::{Attachment, Board};
+
+// ─────────────────────────────────────────────
+// comment 첨부 파일: 내용의 sha256을 이름으로 로컬 디스크에 저장
+// (같은 파일을 여러 번 올려도 한 번만 저장됨)
+// ─────────────────────────────────────────────
+
+pub const UPLOAD_DIR: &str = "uploads";
+pub const MAX_ATTACHMENT_BYTES: usize = 10 * 1024 * 1024;
+
+pub const ALLOWED_CONTENT_TYPES: [&str; 12] = [
+    "image/png",
+    "image/jpeg",
+    "image/gif",
+    "image/webp",
+    "application/pdf",
+    "text/plain",
+    "text/csv",
+    "application/zip",
+    "application/json",
+    "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+    "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+    "application/vnd.openxmlformats-officedocument.presentationml.presentation",
+];
+
+/// 첨부가 달린 task의 board
+pub async fn board_of_attachment(conn: &mut SqliteConnection, id: i64) -> Result<Board, ApiError> {
+    let task_id: Option<(i64,)> = sqlx::query_as("SELECT task_id FROM attachments WHERE id = ?")
+        .bind(id)
+        .fetch_optional(&mut *conn)
+        .await?;
+    match task_id {
+        Some((task_id,)) => boards::board_of_task(conn, task_id).await,
+        None => Err(ApiError::not_found(format!("attachment {} not found", id))),
+    }
+}
+
+/// uploads/ab/abcdef... (앞 2글자로 디렉터리를 나눔)
+pub fn blob_path(sha256: &str) -> PathBuf {
+    PathBuf::from(UPLOAD_DIR).join(&sha256[..2]).join(sha256)
+}
+
+/// 크기/타입을 검사하고 디스크에 저장한 뒤 sha256을 돌려준다
+pub async fn store(data: &[u8], content_type: &str) -> Result<String, ApiError> {
+    if data.is_empty() {
+        return Err(ApiError::bad_request("attachment is empty"));
+    }
+    if data.len() > MAX_ATTACHMENT_BYTES {
+        return Err(ApiError::new(
+            axum::http::StatusCode::PAYLOAD_TOO_LARGE,
+            "attachment_too_large",
+            format!("attachments are limited to {} bytes", MAX_ATTACHMENT_BYTES),
+        )
+        .with("size", data.len()));
+    }
+    // "text/plain; charset=utf-8" 같은 parameter는 무시하고 비교
+    let essence = content_type.split(';').next().unwrap_or_default().trim().to_ascii_lowercase();
+    if !ALLOWED_CONTENT_TYPES.contains(&essence.as_str()) {
+        return Err(ApiError::new(
+            axum::http::StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            "attachment_type_not_allowed",
+            format!("content type {} is not allowed", content_type),
+        )
+        .with("allowed", ALLOWED_CONTENT_TYPES));
+    }
+
+    let sha256 = hex::encode(Sha256::digest(data));
+    let path = blob_path(&sha256);
+    if tokio::fs::try_exists(&path).await.unwrap_or(false) {
+        return Ok(sha256);
+    }
+
+    let io_error = |e: std::io::Error| {
+        eprintln!("Attachment write error: {}", e);
+        ApiError::new(axum::http::StatusCode::INTERNAL_SERVER_ERROR, "storage_error", e.to_string())
+    };
+    if let Some(dir) = path.parent() {
+        tokio::fs::create_dir_all(dir).await.map_err(io_error)?;
+    }
+    // 중간에 실패해도 깨진 파일이 남지 않도록 임시 파일에 쓰고 rename
+    let tmp = path.with_extension(format!("{}.tmp", uuid::Uuid::new_v4()));
+    tokio::fs::write(&tmp, data).await.map_err(io_error)?;
+    tokio::fs::rename(&tmp, &path).await.map_err(io_error)?;
+    Ok(sha256)
+}
+
+pub async fn load_for_comments(
+    conn: &mut SqliteConnection,
+    task_id: i64,
+) -> Result<Vec<Attachment>, sqlx::Error> {
+    sqlx::query_as::<_, Attachment>(
+        "SELECT * FROM attachments WHERE task_id = ? AND comment_id IS NOT NULL ORDER BY id"
+    )
+    .bind(task_id)
+    .fetch_all(&mut *conn)
+    .await
+}
+
+/// comment를 지우면 첨부도 지운다 (파일은 같은 내용의 다른 첨부가 쓸 수 있으므로 디스크에 남긴다)
+pub async fn delete_for_comment(conn: &mut SqliteConnection, comment_id: i64) -> Result<(), sqlx::Error> {
+    sqlx::query("DELETE FROM attachments WHERE comment_id = ?")
+        .bind(comment_id)
+        .execute(&mut *conn)
+        .await?;
+    Ok(())
+}
+
+/// 업로드해 둔 첨부를 comment에 연결. 같은 task에 올린, 아직 연결되지 않은 첨부만 가능.
+pub async fn link_to_comment(
+    conn: &mut SqliteConnection,
+    task_id: i64,
+    comment_id: i64,
+    ids: &[i64],
+) -> Result<(), ApiError> {
+    for &id in ids {
+        let linked = sqlx::query(
+            "UPDATE attachments SET comment_id = ? WHERE id = ? AND task_id = ? AND comment_id IS NULL"
+        )
+        .bind(comment_id)
+        .bind(id)
+        .bind(task_id)
+        .execute(&mut *conn)
+        .await?
+        .rows_affected();
+        if linked == 0 {
+            return Err(ApiError::bad_request(format!(
+                "attachment {} does not exist on task {} or is already attached to a comment",
+                id, task_id
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Content-Disposition용 파일 이름 (RFC 5987, 한글 파일명 대응)
+pub fn content_disposition(file_name: &str, content_type: &str) -> String {
+    let encoded: String = file_name
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'.' | b'-' | b'_' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect();
+    let kind = if content_type.starts_with("image/") { "inline" } else { "attachment" };
+    format!("{}; filename*=UTF-8''{}", kind, encoded)
+}
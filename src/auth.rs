@@ -0,0 +1,503 @@
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+use axum::{
+    async_trait,
+    extract::{FromRef, FromRequestParts, RawPathParams, Request, State},
+    http::{header, request::Parts, HeaderMap, Method},
+    middleware::Next,
+    response::Response,
+};
+use chrono::{DateTime, Duration, Utc};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::{FromRow, SqliteConnection, SqlitePool};
+
+use crate::{attachments, boards, views};
+use crate::error::ApiError;
+use crate::models::{ApiToken, Board, User};
+
+// ─────────────────────────────────────────────
+// 로그인 / 세션
+//
+// - 비밀번호는 argon2 해시로 저장
+// - 로그인하면 임의 토큰을 발급하고 DB에는 sha256만 저장
+// - 토큰은 session cookie 또는 "Authorization: Bearer <token>"으로 받는다
+// ─────────────────────────────────────────────
+
+pub const SESSION_COOKIE: &str = "kanban_session";
+pub const SESSION_TTL_DAYS: i64 = 14;
+pub const ADMIN_ROLE: &str = "admin";
+pub const MEMBER_ROLE: &str = "member";
+pub const USER_ROLES: [&str; 2] = [ADMIN_ROLE, MEMBER_ROLE];
+pub const MIN_PASSWORD_LEN: usize = 8;
+/// last_used_at은 이 간격(분)보다 오래됐을 때만 다시 쓴다 (요청마다 write하지 않도록)
+const LAST_USED_INTERVAL_MINUTES: i64 = 5;
+
+/// 요청을 보낸 사용자 (require_auth가 request extension에 넣어 둔다)
+#[derive(Debug, Clone, FromRow)]
+pub struct CurrentUser {
+    pub id: i64,
+    pub username: String,
+    pub role: String,
+    #[sqlx(skip)]
+    pub scopes: Option<Vec<String>>, // API 토큰으로 들어온 경우의 scope (로그인 세션이면 None)
+}
+
+impl CurrentUser {
+    pub fn is_admin(&self) -> bool {
+        self.role == ADMIN_ROLE
+    }
+
+    pub fn require_admin(&self) -> Result<(), ApiError> {
+        self.require_scope(SCOPE_ADMIN)?;
+        if self.is_admin() {
+            Ok(())
+        } else {
+            Err(ApiError::forbidden("this action requires the admin role"))
+        }
+    }
+
+    /// admin은 모든 scope를, write:tasks는 read를 포함한다
+    pub fn has_scope(&self, scope: &str) -> bool {
+        let Some(scopes) = &self.scopes else { return true };
+        let granted = |s: &str| scopes.iter().any(|g| g == s);
+        granted(scope) || granted(SCOPE_ADMIN) || (scope == SCOPE_READ && granted(SCOPE_WRITE_TASKS))
+    }
+
+    pub fn require_scope(&self, scope: &str) -> Result<(), ApiError> {
+        if self.has_scope(scope) {
+            Ok(())
+        } else {
+            Err(ApiError::forbidden(format!("this API token lacks the {} scope", scope))
+                .with("required_scope", scope))
+        }
+    }
+
+    /// 토큰 관리, 비밀번호 변경 등은 로그인 세션으로만
+    pub fn require_session(&self) -> Result<(), ApiError> {
+        if self.scopes.is_none() {
+            Ok(())
+        } else {
+            Err(ApiError::forbidden("this action is not available with an API token"))
+        }
+    }
+}
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for CurrentUser {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        parts
+            .extensions
+            .get::<CurrentUser>()
+            .cloned()
+            .ok_or_else(|| ApiError::unauthorized("login required"))
+    }
+}
+
+// ─────────────────────────────────────────────
+// board별 역할: viewer(읽기) < member(task 편집, comment) < admin(삭제, workflow/설정)
+//
+// - users.role이 admin이면 모든 board의 admin
+// - board_members에 없는 사용자는 기본 board에서만 viewer, 다른 board에는 접근할 수 없다
+// ─────────────────────────────────────────────
+
+pub const DEFAULT_BOARD: &str = "default";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BoardRole {
+    Viewer = 0,
+    Member = 1,
+    Admin = 2,
+}
+
+pub const BOARD_ROLES: [BoardRole; 3] = [BoardRole::Viewer, BoardRole::Member, BoardRole::Admin];
+
+impl BoardRole {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            BoardRole::Viewer => "viewer",
+            BoardRole::Member => "member",
+            BoardRole::Admin => "admin",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        BOARD_ROLES.into_iter().find(|r| r.as_str() == s)
+    }
+}
+
+/// board에서의 역할. 접근할 수 없는 board면 None.
+pub async fn board_role(
+    conn: &mut SqliteConnection,
+    user: &CurrentUser,
+    board: &str,
+) -> Result<Option<BoardRole>, sqlx::Error> {
+    if user.is_admin() {
+        return Ok(Some(BoardRole::Admin));
+    }
+    let role: Option<(String,)> = sqlx::query_as("SELECT role FROM board_members WHERE board = ? AND user_id = ?")
+        .bind(board)
+        .bind(user.id)
+        .fetch_optional(&mut *conn)
+        .await?;
+    Ok(match role.and_then(|(r,)| BoardRole::parse(&r)) {
+        Some(role) => Some(role),
+        None if board == DEFAULT_BOARD => Some(BoardRole::Viewer),
+        None => None,
+    })
+}
+
+/// 사용자가 볼 수 있는 board key 목록 (admin이면 None = 전부)
+pub async fn viewable_boards(conn: &mut SqliteConnection, user: &CurrentUser) -> Result<Option<Vec<String>>, sqlx::Error> {
+    if user.is_admin() {
+        return Ok(None);
+    }
+    let rows: Vec<(String,)> = sqlx::query_as("SELECT board FROM board_members WHERE user_id = ?")
+        .bind(user.id)
+        .fetch_all(&mut *conn)
+        .await?;
+    let mut keys: Vec<String> = rows.into_iter().map(|(key,)| key).collect();
+    if !keys.iter().any(|k| k == DEFAULT_BOARD) {
+        keys.push(DEFAULT_BOARD.to_string());
+    }
+    Ok(Some(keys))
+}
+
+/// board에서 MIN 이상의 역할을 요구하는 extractor. 모자라면 403.
+///
+/// board는 경로의 :board, /api/tasks/:id 경로면 그 task의 board, /api/views/:id면 그 view의 board,
+/// /api/attachments/:id면 첨부된 task의 board, 모두 없으면 기본 board.
+pub struct BoardAccess<const MIN: u8> {
+    pub user: CurrentUser,
+    pub role: BoardRole,
+    pub board: Board,
+}
+
+pub type BoardViewer = BoardAccess<{ BoardRole::Viewer as u8 }>;
+pub type BoardMember = BoardAccess<{ BoardRole::Member as u8 }>;
+pub type BoardAdmin = BoardAccess<{ BoardRole::Admin as u8 }>;
+
+#[async_trait]
+impl<S, const MIN: u8> FromRequestParts<S> for BoardAccess<MIN>
+where
+    SqlitePool: FromRef<S>,
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let user = CurrentUser::from_request_parts(parts, state).await?;
+        user.require_scope(match MIN {
+            0 => SCOPE_READ,
+            1 => SCOPE_WRITE_TASKS,
+            _ => SCOPE_ADMIN,
+        })?;
+
+        let params = RawPathParams::from_request_parts(parts, state)
+            .await
+            .map_err(|e| ApiError::bad_request(e.body_text()))?;
+        let param = |name: &str| params.iter().find(|(k, _)| *k == name).map(|(_, v)| v.to_string());
+        let db = SqlitePool::from_ref(state);
+        let mut conn = db.acquire().await?;
+        let board = match (param("board"), param("id")) {
+            (Some(key), _) => boards::fetch_board(&mut conn, &key).await?,
+            (None, Some(id)) if parts.uri.path().starts_with("/api/tasks/") => {
+                let id = id.parse().map_err(|_| ApiError::bad_request(format!("invalid task id: {}", id)))?;
+                boards::board_of_task(&mut conn, id).await?
+            }
+            (None, Some(id)) if parts.uri.path().starts_with("/api/views/") => {
+                let id = id.parse().map_err(|_| ApiError::bad_request(format!("invalid view id: {}", id)))?;
+                views::board_of_view(&mut conn, id).await?
+            }
+            (None, Some(id)) if parts.uri.path().starts_with("/api/attachments/") => {
+                let id = id.parse().map_err(|_| ApiError::bad_request(format!("invalid attachment id: {}", id)))?;
+                attachments::board_of_attachment(&mut conn, id).await?
+            }
+            _ => boards::fetch_board(&mut conn, DEFAULT_BOARD).await?,
+        };
+
+        let Some(role) = board_role(&mut conn, &user, &board.key).await? else {
+            return Err(ApiError::forbidden(format!("you are not a member of board {}", board.key))
+                .with("board", &board.key));
+        };
+        if (role as u8) < MIN {
+            let required = BOARD_ROLES[MIN as usize];
+            return Err(ApiError::forbidden(format!(
+                "this action requires the {} role on board {}",
+                required.as_str(),
+                board.key
+            ))
+            .with("board", &board.key)
+            .with("role", role)
+            .with("required", required));
+        }
+        Ok(Self { user, role, board })
+    }
+}
+
+pub fn hash_password(password: &str) -> Result<String, ApiError> {
+    if password.chars().count() < MIN_PASSWORD_LEN {
+        return Err(ApiError::bad_request(format!(
+            "password must be at least {} characters",
+            MIN_PASSWORD_LEN
+        )));
+    }
+    let salt = SaltString::generate(&mut rand::rngs::OsRng);
+    Argon2::default()
+        .hash_password(password.as_bytes(), &salt)
+        .map(|h| h.to_string())
+        .map_err(|e| {
+            eprintln!("Password hash error: {}", e);
+            ApiError::new(axum::http::StatusCode::INTERNAL_SERVER_ERROR, "password_hash_error", e.to_string())
+        })
+}
+
+pub fn verify_password(password: &str, hash: &str) -> bool {
+    PasswordHash::new(hash)
+        .is_ok_and(|parsed| Argon2::default().verify_password(password.as_bytes(), &parsed).is_ok())
+}
+
+/// 256bit 임의 토큰 (hex)
+pub fn new_token() -> String {
+    let mut bytes = [0u8; 32];
+    rand::rngs::OsRng.fill_bytes(&mut bytes);
+    hex::encode(bytes)
+}
+
+pub fn token_hash(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+/// 계정 역할 이름 검사 (board 역할은 BoardRole)
+pub fn validate_user_role(role: &str) -> Result<(), ApiError> {
+    if USER_ROLES.contains(&role) {
+        Ok(())
+    } else {
+        Err(ApiError::bad_request(format!("unknown role {}; expected one of {}", role, USER_ROLES.join(", "))))
+    }
+}
+
+/// 세션을 만들고 (토큰, 만료 시각)을 돌려준다
+pub async fn create_session(
+    conn: &mut SqliteConnection,
+    user_id: i64,
+) -> Result<(String, DateTime<Utc>), sqlx::Error> {
+    let token = new_token();
+    let expires_at = Utc::now() + Duration::days(SESSION_TTL_DAYS);
+    sqlx::query("INSERT INTO sessions (token_hash, user_id, expires_at) VALUES (?, ?, ?)")
+        .bind(token_hash(&token))
+        .bind(user_id)
+        .bind(expires_at)
+        .execute(&mut *conn)
+        .await?;
+    Ok((token, expires_at))
+}
+
+pub fn session_cookie(token: &str, expires_at: DateTime<Utc>) -> String {
+    let max_age = (expires_at - Utc::now()).num_seconds().max(0);
+    format!("{}={}; Path=/; HttpOnly; Secure; SameSite=Lax; Max-Age={}", SESSION_COOKIE, token, max_age)
+}
+
+pub fn clear_session_cookie() -> String {
+    format!("{}=; Path=/; HttpOnly; Secure; SameSite=Lax; Max-Age=0", SESSION_COOKIE)
+}
+
+/// Authorization: Bearer 우선, 없으면 session cookie
+pub fn token_from_headers(headers: &HeaderMap) -> Option<String> {
+    if let Some(bearer) = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+    {
+        return Some(bearer.trim().to_string());
+    }
+    headers
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(';'))
+        .filter_map(|pair| pair.trim().split_once('='))
+        .find(|(name, _)| *name == SESSION_COOKIE)
+        .map(|(_, value)| value.to_string())
+}
+
+async fn user_for_token(db: &SqlitePool, token: &str) -> Result<Option<CurrentUser>, sqlx::Error> {
+    if token.starts_with(API_TOKEN_PREFIX) {
+        return user_for_api_token(db, token).await;
+    }
+    let hash = token_hash(token);
+    let user = sqlx::query_as::<_, CurrentUser>(
+        "SELECT u.id, u.username, u.role FROM sessions s
+           JOIN users u ON u.id = s.user_id
+          WHERE s.token_hash = ? AND s.expires_at > ? AND u.disabled = 0"
+    )
+    .bind(&hash)
+    .bind(Utc::now())
+    .fetch_optional(db)
+    .await?;
+    if user.is_some() {
+        let now = Utc::now();
+        sqlx::query(
+            "UPDATE sessions SET last_used_at = ?
+              WHERE token_hash = ? AND (last_used_at IS NULL OR last_used_at < ?)"
+        )
+        .bind(now)
+        .bind(&hash)
+        .bind(now - Duration::minutes(LAST_USED_INTERVAL_MINUTES))
+        .execute(db)
+        .await?;
+    }
+    Ok(user)
+}
+
+/// /api 아래 모든 route에 거는 middleware. 로그인하지 않았으면 401.
+pub async fn require_auth(
+    State(db): State<SqlitePool>,
+    mut req: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    let token = token_from_headers(req.headers()).ok_or_else(|| ApiError::unauthorized("login required"))?;
+    let user = user_for_token(&db, &token)
+        .await?
+        .ok_or_else(|| ApiError::unauthorized("session is invalid or expired"))?;
+    // API 토큰: 조회는 read, 그 밖의 요청은 최소 write:tasks (admin 작업은 extractor에서 다시 검사)
+    let method = req.method();
+    user.require_scope(if method == Method::GET || method == Method::HEAD { SCOPE_READ } else { SCOPE_WRITE_TASKS })?;
+    req.extensions_mut().insert(user);
+    Ok(next.run(req).await)
+}
+
+pub async fn delete_session(conn: &mut SqliteConnection, token: &str) -> Result<(), sqlx::Error> {
+    sqlx::query("DELETE FROM sessions WHERE token_hash = ?")
+        .bind(token_hash(token))
+        .execute(&mut *conn)
+        .await?;
+    Ok(())
+}
+
+pub async fn fetch_user(conn: &mut SqliteConnection, id: i64) -> Result<User, ApiError> {
+    sqlx::query_as::<_, User>("SELECT * FROM users WHERE id = ?")
+        .bind(id)
+        .fetch_optional(&mut *conn)
+        .await?
+        .ok_or_else(|| ApiError::not_found(format!("user {} not found", id)))
+}
+
+/// 사용자가 하나도 없으면 admin 계정을 만든다.
+/// 비밀번호는 KANBAN_ADMIN_PASSWORD, 없으면 임의로 만들어 한 번만 출력한다.
+pub async fn ensure_admin_user(db: &SqlitePool) -> Result<(), ApiError> {
+    let (count,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM users").fetch_one(db).await?;
+    if count > 0 {
+        return Ok(());
+    }
+    let (password, generated) = match std::env::var("KANBAN_ADMIN_PASSWORD") {
+        Ok(p) => (p, false),
+        Err(_) => (new_token()[..16].to_string(), true),
+    };
+    sqlx::query("INSERT INTO users (username, display_name, password_hash, role) VALUES ('admin', 'Administrator', ?, ?)")
+        .bind(hash_password(&password)?)
+        .bind(ADMIN_ROLE)
+        .execute(db)
+        .await?;
+    if generated {
+        println!("Created user 'admin' with password {} (change it after logging in)", password);
+    } else {
+        println!("Created user 'admin' with the password from KANBAN_ADMIN_PASSWORD");
+    }
+    Ok(())
+}
+
+// ─────────────────────────────────────────────
+// 개인 API 토큰 (스크립트, CI용)
+//
+// - "kbp_" + 임의 hex, DB에는 sha256만 저장 (발급 시 한 번만 보여줌)
+// - scope: read, write:tasks, admin
+// - 권한은 토큰 주인의 board 역할을 넘지 않는다
+// ─────────────────────────────────────────────
+
+pub const API_TOKEN_PREFIX: &str = "kbp_";
+pub const SCOPE_READ: &str = "read";
+pub const SCOPE_WRITE_TASKS: &str = "write:tasks";
+pub const SCOPE_ADMIN: &str = "admin";
+pub const SCOPES: [&str; 3] = [SCOPE_READ, SCOPE_WRITE_TASKS, SCOPE_ADMIN];
+
+/// scope 목록 검증 (중복 제거, 쉼표 구분 문자열로)
+pub fn normalize_scopes(scopes: &[String]) -> Result<String, ApiError> {
+    if scopes.is_empty() {
+        return Err(ApiError::bad_request(format!("at least one scope is required: {:?}", SCOPES)));
+    }
+    let mut result: Vec<&str> = Vec::new();
+    for scope in scopes {
+        let Some(known) = SCOPES.iter().find(|s| **s == scope.trim()) else {
+            return Err(ApiError::bad_request(format!("unknown scope {}; expected one of {:?}", scope, SCOPES)));
+        };
+        if !result.contains(known) {
+            result.push(known);
+        }
+    }
+    Ok(result.join(","))
+}
+
+/// 토큰을 발급하고 평문 토큰과 저장된 행을 돌려준다
+pub async fn create_api_token(
+    conn: &mut SqliteConnection,
+    user_id: i64,
+    name: &str,
+    scopes: &str,
+    expires_at: Option<DateTime<Utc>>,
+) -> Result<(String, ApiToken), sqlx::Error> {
+    let token = format!("{}{}", API_TOKEN_PREFIX, new_token());
+    let row = sqlx::query_as::<_, ApiToken>(
+        "INSERT INTO api_tokens (user_id, name, token_hash, token_prefix, scopes, expires_at)
+         VALUES (?, ?, ?, ?, ?, ?) RETURNING *"
+    )
+    .bind(user_id)
+    .bind(name)
+    .bind(token_hash(&token))
+    .bind(&token[..API_TOKEN_PREFIX.len() + 6])
+    .bind(scopes)
+    .bind(expires_at)
+    .fetch_one(&mut *conn)
+    .await?;
+    Ok((token, row))
+}
+
+async fn user_for_api_token(db: &SqlitePool, token: &str) -> Result<Option<CurrentUser>, sqlx::Error> {
+    #[derive(FromRow)]
+    struct TokenUser {
+        token_id: i64,
+        scopes: String,
+        #[sqlx(flatten)]
+        user: CurrentUser,
+    }
+
+    let now = Utc::now();
+    let found = sqlx::query_as::<_, TokenUser>(
+        "SELECT t.id AS token_id, t.scopes, u.id, u.username, u.role FROM api_tokens t
+           JOIN users u ON u.id = t.user_id
+          WHERE t.token_hash = ? AND t.revoked_at IS NULL
+            AND (t.expires_at IS NULL OR t.expires_at > ?) AND u.disabled = 0"
+    )
+    .bind(token_hash(token))
+    .bind(now)
+    .fetch_optional(db)
+    .await?;
+    let Some(found) = found else { return Ok(None) };
+
+    sqlx::query(
+        "UPDATE api_tokens SET last_used_at = ?
+          WHERE id = ? AND (last_used_at IS NULL OR last_used_at < ?)"
+    )
+    .bind(now)
+    .bind(found.token_id)
+    .bind(now - Duration::minutes(LAST_USED_INTERVAL_MINUTES))
+    .execute(db)
+    .await?;
+    let mut user = found.user;
+    user.scopes = Some(found.scopes.split(',').map(str::to_string).collect());
+    Ok(Some(user))
+}
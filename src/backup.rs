@@ -0,0 +1,732 @@
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqliteConnection, SqlitePool};
+
+use crate::auth::BoardRole;
+use crate::error::ApiError;
+use crate::models::Board;
+use crate::{boards, schedule, workflow};
+
+// ─────────────────────────────────────────────
+// Board 백업/복원: board 하나를 버전이 붙은 JSON archive로 내보내고 다시 넣는다
+//
+// - task, comment(수정 이력, mention), 첨부 파일 메타데이터, history, 단계 전이 기록,
+//   board 안의 task link, WIP 한도, board 역할, 저장된 view, 삭제된 task 기록
+// - 첨부 파일 내용은 sha256으로 디스크에 있으므로 메타데이터만 (없는 blob은 경고)
+// - 복원은 새 board key로만 하고 모든 id를 새로 매긴다 (기존 DB에 넣어도 겹치지 않음)
+// - 사용자는 username으로 찾는다. 없는 사용자의 board 역할/view, 알 수 없는 역할은 건너뛰고 경고.
+// - 본문/history detail/삭제 기록 안의 task id (#123 등)는 바꾸지 않는다
+// ─────────────────────────────────────────────
+
+pub const ARCHIVE_FORMAT: &str = "vpc-kanban/board";
+pub const ARCHIVE_VERSION: u32 = 1;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BoardArchive {
+    pub format: String,
+    pub version: u32,
+    pub exported_at: DateTime<Utc>,
+    pub board: ArchivedBoard,
+    pub wip_limits: Vec<ArchivedWipLimit>,
+    pub members: Vec<ArchivedMember>,
+    pub tasks: Vec<ArchivedTask>,
+    pub comments: Vec<ArchivedComment>,
+    pub comment_revisions: Vec<ArchivedCommentRevision>,
+    pub comment_mentions: Vec<ArchivedMention>,
+    pub attachments: Vec<ArchivedAttachment>,
+    pub history: Vec<ArchivedHistory>,
+    pub transitions: Vec<ArchivedTransition>,
+    pub links: Vec<ArchivedLink>,
+    pub views: Vec<ArchivedView>,
+    #[serde(default)]
+    pub deletions: Vec<ArchivedDeletion>,   // 이 필드가 생기기 전 archive에는 없다
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct ArchivedBoard {
+    pub key: String,
+    pub name: String,
+    pub jira_project: Option<String>,
+    pub workflow: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct ArchivedWipLimit {
+    pub scope: String,
+    pub wip_limit: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct ArchivedMember {
+    pub username: String,
+    pub role: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct ArchivedTask {
+    pub id: i64,
+    pub jira_ticket_key: Option<String>,
+    pub title: String,
+    pub description: Option<String>,
+    pub status: String,
+    pub stage: Option<String>,
+    pub assignee: Option<String>,
+    pub project_key: Option<String>,
+    pub parent_task_id: Option<i64>,
+    pub alias: Option<String>,
+    pub tags: Option<String>,
+    pub keywords: Option<String>,
+    pub start_date: Option<NaiveDate>,
+    pub due_date: Option<NaiveDate>,
+    pub jira_url: Option<String>,
+    pub sort_order: i64,
+    pub version: i64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct ArchivedComment {
+    pub id: i64,
+    pub task_id: i64,
+    pub author: String,
+    pub content: String,
+    pub attachments: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub edited_at: Option<DateTime<Utc>>,
+    pub deleted_at: Option<DateTime<Utc>>,
+    pub deleted_by: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct ArchivedCommentRevision {
+    pub comment_id: i64,
+    pub content: String,
+    pub attachments: Option<String>,
+    pub edited_by: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct ArchivedMention {
+    pub comment_id: i64,
+    pub task_id: i64,
+    pub username: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct ArchivedAttachment {
+    pub task_id: i64,
+    pub comment_id: Option<i64>,
+    pub sha256: String,
+    pub file_name: String,
+    pub content_type: String,
+    pub size: i64,
+    pub uploaded_by: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct ArchivedHistory {
+    pub task_id: i64,
+    pub action: String,
+    pub detail: Option<String>,
+    pub actor: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct ArchivedTransition {
+    pub task_id: i64,
+    pub from_status: Option<String>,
+    pub from_stage: Option<String>,
+    pub to_status: String,
+    pub to_stage: Option<String>,
+    pub actor: Option<String>,
+    pub entered_at: DateTime<Utc>,
+    pub exited_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct ArchivedLink {
+    pub source_task_id: i64,
+    pub target_task_id: i64,
+    pub link_type: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct ArchivedView {
+    pub owner: String,               // username
+    pub name: String,
+    pub shared: bool,
+    pub query: String,               // TaskListQuery JSON 그대로
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct ArchivedDeletion {
+    pub task_id: i64,                // 지워진 task의 옛 id (이미 없는 task라 바꾸지 않는다)
+    pub title: String,
+    pub action: String,
+    pub detail: Option<String>,
+    pub actor: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// 복원 결과: 넣은 개수, 건너뛴 것, 옛 task id → 새 task id
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RestoreReport {
+    pub board: Board,
+    pub counts: BTreeMap<String, usize>,
+    pub warnings: Vec<String>,
+    pub task_ids: BTreeMap<i64, i64>,
+}
+
+// board의 task에 딸린 행
+const BOARD_TASKS: &str = "(SELECT id FROM tasks WHERE board_id = ?)";
+
+/// board 전체를 archive로. 한 transaction 안에서 읽어 중간에 바뀐 내용이 섞이지 않게 한다.
+pub async fn export_board(conn: &mut SqliteConnection, board: &Board) -> Result<BoardArchive, sqlx::Error> {
+    async fn rows<T>(conn: &mut SqliteConnection, sql: &str, board_id: i64) -> Result<Vec<T>, sqlx::Error>
+    where
+        T: for<'r> FromRow<'r, sqlx::sqlite::SqliteRow> + Send + Unpin,
+    {
+        sqlx::query_as::<_, T>(sql).bind(board_id).fetch_all(&mut *conn).await
+    }
+
+    let id = board.id;
+    Ok(BoardArchive {
+        format: ARCHIVE_FORMAT.to_string(),
+        version: ARCHIVE_VERSION,
+        exported_at: Utc::now(),
+        board: ArchivedBoard {
+            key: board.key.clone(),
+            name: board.name.clone(),
+            jira_project: board.jira_project.clone(),
+            workflow: board.workflow.clone(),
+            created_at: board.created_at,
+        },
+        wip_limits: rows(conn, "SELECT scope, wip_limit FROM wip_limits WHERE board_id = ? ORDER BY scope", id).await?,
+        members: sqlx::query_as(
+            "SELECT u.username, m.role FROM board_members m JOIN users u ON u.id = m.user_id
+             WHERE m.board = ? ORDER BY u.username"
+        )
+        .bind(&board.key)
+        .fetch_all(&mut *conn)
+        .await?,
+        tasks: rows(conn, "SELECT * FROM tasks WHERE board_id = ? ORDER BY id", id).await?,
+        comments: rows(conn, &format!("SELECT * FROM task_comments WHERE task_id IN {} ORDER BY id", BOARD_TASKS), id).await?,
+        comment_revisions: rows(
+            conn,
+            &format!(
+                "SELECT r.* FROM task_comment_revisions r JOIN task_comments c ON c.id = r.comment_id
+                 WHERE c.task_id IN {} ORDER BY r.id",
+                BOARD_TASKS
+            ),
+            id,
+        )
+        .await?,
+        comment_mentions: rows(conn, &format!("SELECT * FROM comment_mentions WHERE task_id IN {} ORDER BY id", BOARD_TASKS), id).await?,
+        attachments: rows(conn, &format!("SELECT * FROM attachments WHERE task_id IN {} ORDER BY id", BOARD_TASKS), id).await?,
+        history: rows(conn, &format!("SELECT * FROM task_history WHERE task_id IN {} ORDER BY id", BOARD_TASKS), id).await?,
+        transitions: rows(conn, &format!("SELECT * FROM task_stage_transitions WHERE task_id IN {} ORDER BY id", BOARD_TASKS), id).await?,
+        // 다른 board의 task와 이어진 link는 복원할 곳이 없으므로 양쪽 다 이 board인 것만
+        links: sqlx::query_as(&format!(
+            "SELECT * FROM task_links WHERE source_task_id IN {} AND target_task_id IN {} ORDER BY id",
+            BOARD_TASKS, BOARD_TASKS
+        ))
+        .bind(id)
+        .bind(id)
+        .fetch_all(&mut *conn)
+        .await?,
+        views: rows(
+            conn,
+            "SELECT u.username AS owner, v.name, v.shared, v.query, v.created_at, v.updated_at
+             FROM saved_views v JOIN users u ON u.id = v.owner_id WHERE v.board_id = ? ORDER BY v.id",
+            id,
+        )
+        .await?,
+        deletions: rows(conn, "SELECT * FROM task_deletions WHERE board_id = ? ORDER BY id", id).await?,
+    })
+}
+
+/// archive를 새 board로 넣는다. key가 없으면 archive의 board key (이미 있으면 409).
+/// 호출한 쪽의 transaction 안에서 실행해 실패하면 아무것도 남지 않게 한다.
+pub async fn restore_board(
+    conn: &mut SqliteConnection,
+    archive: &BoardArchive,
+    key: Option<&str>,
+) -> Result<RestoreReport, ApiError> {
+    if archive.format != ARCHIVE_FORMAT {
+        return Err(ApiError::bad_request(format!("not a board archive (format {:?})", archive.format)));
+    }
+    if archive.version > ARCHIVE_VERSION {
+        return Err(ApiError::bad_request(format!(
+            "archive version {} is newer than this server supports ({})",
+            archive.version, ARCHIVE_VERSION
+        )));
+    }
+    let key = key.unwrap_or(&archive.board.key);
+    boards::validate_key(key)?;
+    let exists: Option<(i64,)> = sqlx::query_as("SELECT id FROM boards WHERE key = ?")
+        .bind(key)
+        .fetch_optional(&mut *conn)
+        .await?;
+    if exists.is_some() {
+        return Err(ApiError::conflict(
+            "duplicate_board",
+            format!("board {} already exists; restore into a new board key", key),
+        ));
+    }
+
+    let mut warnings = Vec::new();
+    let mut counts = BTreeMap::new();
+    let workflow = if boards::validate_workflow(conn, &archive.board.workflow).await.is_ok() {
+        archive.board.workflow.as_str()
+    } else {
+        warnings.push(format!(
+            "workflow {} does not exist here; using {}",
+            archive.board.workflow,
+            workflow::DEFAULT_WORKFLOW
+        ));
+        workflow::DEFAULT_WORKFLOW
+    };
+    let board = sqlx::query_as::<_, Board>(
+        "INSERT INTO boards (key, name, jira_project, workflow, created_at) VALUES (?, ?, ?, ?, ?) RETURNING *"
+    )
+    .bind(key)
+    .bind(&archive.board.name)
+    .bind(&archive.board.jira_project)
+    .bind(workflow)
+    .bind(archive.board.created_at)
+    .fetch_one(&mut *conn)
+    .await?;
+
+    for limit in &archive.wip_limits {
+        sqlx::query("INSERT INTO wip_limits (board_id, scope, wip_limit) VALUES (?, ?, ?)")
+            .bind(board.id)
+            .bind(&limit.scope)
+            .bind(limit.wip_limit)
+            .execute(&mut *conn)
+            .await?;
+    }
+    counts.insert("wip_limits".to_string(), archive.wip_limits.len());
+
+    let users: HashMap<String, i64> = sqlx::query_as::<_, (String, i64)>("SELECT username, id FROM users")
+        .fetch_all(&mut *conn)
+        .await?
+        .into_iter()
+        .collect();
+    let mut members = 0;
+    for member in &archive.members {
+        let Some(user_id) = users.get(&member.username) else {
+            warnings.push(format!("user {} does not exist; skipped the {} role", member.username, member.role));
+            continue;
+        };
+        let Some(role) = BoardRole::parse(&member.role) else {
+            warnings.push(format!("unknown board role {} for {}; skipped", member.role, member.username));
+            continue;
+        };
+        sqlx::query("INSERT INTO board_members (board, user_id, role) VALUES (?, ?, ?)")
+            .bind(&board.key)
+            .bind(user_id)
+            .bind(role.as_str())
+            .execute(&mut *conn)
+            .await?;
+        members += 1;
+    }
+    counts.insert("members".to_string(), members);
+
+    // task: 먼저 parent 없이 넣고 id가 다 정해진 뒤 parent를 잇는다
+    let mut task_ids: BTreeMap<i64, i64> = BTreeMap::new();
+    for task in &archive.tasks {
+        let new_id = sqlx::query(
+            "INSERT INTO tasks (board_id, jira_ticket_key, title, description, status, stage, assignee, project_key,
+                                alias, tags, keywords, start_date, due_date, jira_url, sort_order, version,
+                                created_at, updated_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"
+        )
+        .bind(board.id)
+        .bind(&task.jira_ticket_key)
+        .bind(&task.title)
+        .bind(&task.description)
+        .bind(&task.status)
+        .bind(&task.stage)
+        .bind(&task.assignee)
+        .bind(&task.project_key)
+        .bind(&task.alias)
+        .bind(&task.tags)
+        .bind(&task.keywords)
+        .bind(task.start_date.map(|d| d.to_string()))
+        .bind(task.due_date.map(|d| d.to_string()))
+        .bind(&task.jira_url)
+        .bind(task.sort_order)
+        .bind(task.version)
+        .bind(task.created_at)
+        .bind(task.updated_at)
+        .execute(&mut *conn)
+        .await?
+        .last_insert_rowid();
+        task_ids.insert(task.id, new_id);
+    }
+    let task_id = |old: i64| {
+        task_ids
+            .get(&old)
+            .copied()
+            .ok_or_else(|| ApiError::bad_request(format!("archive refers to task {} which it does not contain", old)))
+    };
+    for task in &archive.tasks {
+        if let Some(parent) = task.parent_task_id {
+            sqlx::query("UPDATE tasks SET parent_task_id = ? WHERE id = ?")
+                .bind(task_id(parent)?)
+                .bind(task_id(task.id)?)
+                .execute(&mut *conn)
+                .await?;
+        }
+    }
+    for &new_id in task_ids.values() {
+        schedule::refresh_stage_deadlines(conn, new_id).await?;
+    }
+    counts.insert("tasks".to_string(), task_ids.len());
+
+    let mut comment_ids: HashMap<i64, i64> = HashMap::new();
+    for c in &archive.comments {
+        let new_id = sqlx::query(
+            "INSERT INTO task_comments (task_id, author, content, attachments, created_at, edited_at, deleted_at, deleted_by)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?)"
+        )
+        .bind(task_id(c.task_id)?)
+        .bind(&c.author)
+        .bind(&c.content)
+        .bind(&c.attachments)
+        .bind(c.created_at)
+        .bind(c.edited_at)
+        .bind(c.deleted_at)
+        .bind(&c.deleted_by)
+        .execute(&mut *conn)
+        .await?
+        .last_insert_rowid();
+        comment_ids.insert(c.id, new_id);
+    }
+    counts.insert("comments".to_string(), comment_ids.len());
+    let comment_id = |old: i64| {
+        comment_ids
+            .get(&old)
+            .copied()
+            .ok_or_else(|| ApiError::bad_request(format!("archive refers to comment {} which it does not contain", old)))
+    };
+
+    for r in &archive.comment_revisions {
+        sqlx::query(
+            "INSERT INTO task_comment_revisions (comment_id, content, attachments, edited_by, created_at)
+             VALUES (?, ?, ?, ?, ?)"
+        )
+        .bind(comment_id(r.comment_id)?)
+        .bind(&r.content)
+        .bind(&r.attachments)
+        .bind(&r.edited_by)
+        .bind(r.created_at)
+        .execute(&mut *conn)
+        .await?;
+    }
+    counts.insert("comment_revisions".to_string(), archive.comment_revisions.len());
+
+    for m in &archive.comment_mentions {
+        sqlx::query("INSERT INTO comment_mentions (comment_id, task_id, username) VALUES (?, ?, ?)")
+            .bind(comment_id(m.comment_id)?)
+            .bind(task_id(m.task_id)?)
+            .bind(&m.username)
+            .execute(&mut *conn)
+            .await?;
+    }
+    counts.insert("comment_mentions".to_string(), archive.comment_mentions.len());
+
+    for a in &archive.attachments {
+        sqlx::query(
+            "INSERT INTO attachments (task_id, comment_id, sha256, file_name, content_type, size, uploaded_by, created_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?)"
+        )
+        .bind(task_id(a.task_id)?)
+        .bind(a.comment_id.map(comment_id).transpose()?)
+        .bind(&a.sha256)
+        .bind(&a.file_name)
+        .bind(&a.content_type)
+        .bind(a.size)
+        .bind(&a.uploaded_by)
+        .bind(a.created_at)
+        .execute(&mut *conn)
+        .await?;
+        if !crate::attachments::blob_path(&a.sha256).exists() {
+            warnings.push(format!("attachment {} ({}) has no stored file here", a.file_name, a.sha256));
+        }
+    }
+    counts.insert("attachments".to_string(), archive.attachments.len());
+
+    for h in &archive.history {
+        sqlx::query("INSERT INTO task_history (task_id, action, detail, actor, created_at) VALUES (?, ?, ?, ?, ?)")
+            .bind(task_id(h.task_id)?)
+            .bind(&h.action)
+            .bind(&h.detail)
+            .bind(&h.actor)
+            .bind(h.created_at)
+            .execute(&mut *conn)
+            .await?;
+    }
+    counts.insert("history".to_string(), archive.history.len());
+
+    for t in &archive.transitions {
+        sqlx::query(
+            "INSERT INTO task_stage_transitions (task_id, from_status, from_stage, to_status, to_stage, actor, entered_at, exited_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?)"
+        )
+        .bind(task_id(t.task_id)?)
+        .bind(&t.from_status)
+        .bind(&t.from_stage)
+        .bind(&t.to_status)
+        .bind(&t.to_stage)
+        .bind(&t.actor)
+        .bind(t.entered_at)
+        .bind(t.exited_at)
+        .execute(&mut *conn)
+        .await?;
+    }
+    counts.insert("transitions".to_string(), archive.transitions.len());
+
+    for l in &archive.links {
+        sqlx::query("INSERT INTO task_links (source_task_id, target_task_id, link_type, created_at) VALUES (?, ?, ?, ?)")
+            .bind(task_id(l.source_task_id)?)
+            .bind(task_id(l.target_task_id)?)
+            .bind(&l.link_type)
+            .bind(l.created_at)
+            .execute(&mut *conn)
+            .await?;
+    }
+    counts.insert("links".to_string(), archive.links.len());
+
+    let mut views = 0;
+    for v in &archive.views {
+        let Some(owner_id) = users.get(&v.owner) else {
+            warnings.push(format!("user {} does not exist; skipped the view {}", v.owner, v.name));
+            continue;
+        };
+        sqlx::query(
+            "INSERT INTO saved_views (board_id, owner_id, name, shared, query, created_at, updated_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?)"
+        )
+        .bind(board.id)
+        .bind(owner_id)
+        .bind(&v.name)
+        .bind(v.shared)
+        .bind(&v.query)
+        .bind(v.created_at)
+        .bind(v.updated_at)
+        .execute(&mut *conn)
+        .await?;
+        views += 1;
+    }
+    counts.insert("views".to_string(), views);
+
+    for d in &archive.deletions {
+        sqlx::query(
+            "INSERT INTO task_deletions (task_id, board_id, title, action, detail, actor, created_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?)"
+        )
+        .bind(d.task_id)
+        .bind(board.id)
+        .bind(&d.title)
+        .bind(&d.action)
+        .bind(&d.detail)
+        .bind(&d.actor)
+        .bind(d.created_at)
+        .execute(&mut *conn)
+        .await?;
+    }
+    counts.insert("deletions".to_string(), archive.deletions.len());
+
+    Ok(RestoreReport { board, counts, warnings, task_ids })
+}
+
+/// 명령행: `vpc-kanban backup <board> [file]`, `vpc-kanban restore <file> [board]`
+/// (file이 없으면 board-<key>.json)
+pub async fn run_cli(pool: &SqlitePool, args: &[String]) -> Result<(), String> {
+    match args {
+        [cmd, key, rest @ ..] if cmd == "backup" && rest.len() <= 1 => {
+            let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+            let board = boards::fetch_board(&mut tx, key).await.map_err(error_message)?;
+            let archive = export_board(&mut tx, &board).await.map_err(|e| e.to_string())?;
+            tx.commit().await.map_err(|e| e.to_string())?;
+
+            let path = rest.first().cloned().unwrap_or_else(|| format!("board-{}.json", key));
+            let json = serde_json::to_vec_pretty(&archive).map_err(|e| e.to_string())?;
+            std::fs::write(&path, json).map_err(|e| format!("{}: {}", path, e))?;
+            println!("Backed up board {} ({} tasks) to {}", key, archive.tasks.len(), path);
+            Ok(())
+        }
+        [cmd, path, rest @ ..] if cmd == "restore" && rest.len() <= 1 => {
+            let data = std::fs::read(path).map_err(|e| format!("{}: {}", path, e))?;
+            let archive: BoardArchive = serde_json::from_slice(&data).map_err(|e| format!("{}: {}", path, e))?;
+
+            let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+            let report = restore_board(&mut tx, &archive, rest.first().map(String::as_str))
+                .await
+                .map_err(error_message)?;
+            tx.commit().await.map_err(|e| e.to_string())?;
+
+            println!("Restored board {} from {}", report.board.key, path);
+            for (what, count) in &report.counts {
+                println!("  {}: {}", what, count);
+            }
+            for warning in &report.warnings {
+                println!("  warning: {}", warning);
+            }
+            Ok(())
+        }
+        _ => Err("usage: vpc-kanban backup <board> [file] | vpc-kanban restore <file> [board]".to_string()),
+    }
+}
+
+fn error_message(e: ApiError) -> String {
+    e.body
+        .get("message")
+        .and_then(|m| m.as_str())
+        .unwrap_or("unknown error")
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::Value;
+
+    use super::*;
+    use crate::test_support;
+
+    /// board 하나에 archive의 모든 collection이 적어도 한 행씩 있게 채운다
+    async fn seed(conn: &mut SqliteConnection) {
+        test_support::add_user(conn, "kim", crate::auth::MEMBER_ROLE).await;
+        for statement in [
+            "INSERT INTO boards (key, name, jira_project, created_at) VALUES ('src', 'Source', 'SRC', '2026-01-02 03:04:05')",
+            "INSERT INTO board_members (board, user_id, role) VALUES ('src', 1, 'member')",
+            "INSERT INTO wip_limits (board_id, scope, wip_limit) VALUES (2, 'InProgress', 3)",
+            "INSERT INTO tasks (board_id, title, status, stage, created_at, updated_at)
+             VALUES (2, 'parent', 'InProgress', '개발', '2026-01-03 00:00:00', '2026-01-04 00:00:00')",
+            "INSERT INTO tasks (board_id, title, parent_task_id, assignee, tags, due_date, sort_order, version)
+             VALUES (2, 'child', 1, 'kim', 'net', '2026-03-02', 1, 4)",
+            "INSERT INTO tasks (board_id, title, jira_ticket_key, jira_url)
+             VALUES (2, 'other', 'SRC-1', 'https://jira.example.com/browse/SRC-1')",
+            "INSERT INTO task_comments (task_id, author, content, created_at, edited_at) VALUES (2, 'kim', 'hi @kim', '2026-01-05 00:00:00', '2026-01-06 00:00:00')",
+            "INSERT INTO task_comments (task_id, author, content, deleted_at, deleted_by) VALUES (1, 'kim', 'gone', '2026-01-07 00:00:00', 'kim')",
+            "INSERT INTO task_comment_revisions (comment_id, content, edited_by) VALUES (1, 'hi', 'kim')",
+            "INSERT INTO comment_mentions (comment_id, task_id, username) VALUES (1, 2, 'kim')",
+            "INSERT INTO attachments (task_id, comment_id, sha256, file_name, content_type, size, uploaded_by)
+             VALUES (2, 1, 'abc', 'a.txt', 'text/plain', 3, 'kim')",
+            "INSERT INTO attachments (task_id, sha256, file_name, content_type, size) VALUES (3, 'def', 'b.png', 'image/png', 5)",
+            "INSERT INTO task_history (task_id, action, detail, actor) VALUES (1, 'wip_override', '{}', 'kim')",
+            "INSERT INTO task_stage_transitions (task_id, from_status, to_status, to_stage, actor, exited_at)
+             VALUES (1, 'Pending', 'InProgress', '개발', 'kim', '2026-01-08 00:00:00')",
+            "INSERT INTO task_links (source_task_id, target_task_id, link_type) VALUES (3, 2, 'blocks')",
+            "INSERT INTO saved_views (board_id, owner_id, name, shared, query) VALUES (2, 1, 'mine', 1, '{\"assignee\":\"kim\"}')",
+            "INSERT INTO task_deletions (task_id, board_id, title, action, detail, actor) VALUES (9, 2, 'old', 'delete', '{}', 'kim')",
+        ] {
+            sqlx::query(statement).execute(&mut *conn).await.unwrap();
+        }
+    }
+
+    async fn export(conn: &mut SqliteConnection, key: &str) -> BoardArchive {
+        let board = boards::fetch_board(conn, key).await.unwrap();
+        export_board(conn, &board).await.unwrap()
+    }
+
+    /// collection을 JSON으로 바꾸고 fields의 id를 ids로 옮긴다
+    fn remap<T: Serialize>(rows: &[T], fields: &[(&str, &BTreeMap<i64, i64>)]) -> Vec<Value> {
+        rows.iter()
+            .map(|row| {
+                let mut value = serde_json::to_value(row).unwrap();
+                for (field, ids) in fields {
+                    if let Some(old) = value[*field].as_i64() {
+                        value[*field] = ids[&old].into();
+                    }
+                }
+                value
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn export_restore_export_round_trips() {
+        let pool = test_support::memory_db().await;
+        let mut conn = pool.acquire().await.unwrap();
+        seed(&mut conn).await;
+
+        let original = export(&mut conn, "src").await;
+        let report = restore_board(&mut conn, &original, Some("copy")).await.unwrap();
+        assert!(report.warnings.iter().all(|w| w.contains("has no stored file")), "{:?}", report.warnings);
+        let restored = export(&mut conn, "copy").await;
+
+        let tasks = &report.task_ids;
+        assert_eq!(tasks.len(), 3);
+        assert!(tasks.iter().all(|(old, new)| old != new), "restore should assign new task ids");
+        // comment id는 report에 없으므로 id 순서대로 짝짓는다
+        let comments: BTreeMap<i64, i64> =
+            original.comments.iter().zip(&restored.comments).map(|(o, r)| (o.id, r.id)).collect();
+
+        assert_eq!(restored.board.key, "copy");
+        let mut board = serde_json::to_value(&original.board).unwrap();
+        board["key"] = "copy".into();
+        assert_eq!(board, serde_json::to_value(&restored.board).unwrap());
+        assert_eq!(remap(&original.wip_limits, &[]), remap(&restored.wip_limits, &[]));
+        assert_eq!(remap(&original.members, &[]), remap(&restored.members, &[]));
+        assert_eq!(remap(&original.tasks, &[("id", tasks), ("parent_task_id", tasks)]), remap(&restored.tasks, &[]));
+        assert_eq!(remap(&original.comments, &[("id", &comments), ("task_id", tasks)]), remap(&restored.comments, &[]));
+        assert_eq!(
+            remap(&original.comment_revisions, &[("comment_id", &comments)]),
+            remap(&restored.comment_revisions, &[])
+        );
+        assert_eq!(
+            remap(&original.comment_mentions, &[("comment_id", &comments), ("task_id", tasks)]),
+            remap(&restored.comment_mentions, &[])
+        );
+        assert_eq!(
+            remap(&original.attachments, &[("comment_id", &comments), ("task_id", tasks)]),
+            remap(&restored.attachments, &[])
+        );
+        assert_eq!(remap(&original.history, &[("task_id", tasks)]), remap(&restored.history, &[]));
+        assert_eq!(remap(&original.transitions, &[("task_id", tasks)]), remap(&restored.transitions, &[]));
+        assert_eq!(
+            remap(&original.links, &[("source_task_id", tasks), ("target_task_id", tasks)]),
+            remap(&restored.links, &[])
+        );
+        assert_eq!(remap(&original.views, &[]), remap(&restored.views, &[]));
+        assert_eq!(remap(&original.deletions, &[]), remap(&restored.deletions, &[]));
+
+        // 모든 collection이 실제로 비교되었는지
+        let archive = serde_json::to_value(&original).unwrap();
+        for (name, rows) in archive.as_object().unwrap() {
+            if let Some(rows) = rows.as_array() {
+                assert!(!rows.is_empty(), "seed has no {}", name);
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn restore_skips_unknown_member_roles() {
+        let pool = test_support::memory_db().await;
+        let mut conn = pool.acquire().await.unwrap();
+        seed(&mut conn).await;
+
+        let mut archive = serde_json::to_value(export(&mut conn, "src").await).unwrap();
+        archive["members"][0]["role"] = "owner".into();
+        // deletions가 없던 archive도 읽는다
+        archive.as_object_mut().unwrap().remove("deletions");
+        let archive: BoardArchive = serde_json::from_value(archive).unwrap();
+        let report = restore_board(&mut conn, &archive, Some("copy")).await.unwrap();
+
+        assert_eq!(report.counts["members"], 0);
+        assert!(report.warnings.iter().any(|w| w.contains("unknown board role owner")), "{:?}", report.warnings);
+        assert!(export(&mut conn, "copy").await.members.is_empty());
+    }
+}
@@ -0,0 +1,139 @@
+use chrono::Utc;
+use sqlx::SqliteConnection;
+
+use crate::error::ApiError;
+use crate::models::{Board, TaskLink};
+
+// ─────────────────────────────────────────────
+// Board: 팀별 kanban. task는 board_id로 한 board에 속하고,
+// 상위/하위 task는 항상 같은 board에 있다.
+// ─────────────────────────────────────────────
+
+pub async fn fetch_board(conn: &mut SqliteConnection, key: &str) -> Result<Board, ApiError> {
+    sqlx::query_as::<_, Board>("SELECT * FROM boards WHERE key = ?")
+        .bind(key)
+        .fetch_optional(&mut *conn)
+        .await?
+        .ok_or_else(|| ApiError::not_found(format!("board {} not found", key)))
+}
+
+pub async fn board_of_task(conn: &mut SqliteConnection, task_id: i64) -> Result<Board, ApiError> {
+    sqlx::query_as::<_, Board>(
+        "SELECT boards.* FROM boards JOIN tasks ON tasks.board_id = boards.id WHERE tasks.id = ?"
+    )
+    .bind(task_id)
+    .fetch_optional(&mut *conn)
+    .await?
+    .ok_or_else(|| ApiError::not_found(format!("task {} not found", task_id)))
+}
+
+/// URL에 그대로 쓰므로 영문 소문자/숫자/'-'/'_'만
+pub fn validate_key(key: &str) -> Result<(), ApiError> {
+    let valid = !key.is_empty()
+        && key.len() <= 32
+        && key.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || matches!(c, '-' | '_'));
+    if valid {
+        Ok(())
+    } else {
+        Err(ApiError::bad_request(
+            "board key must be 1-32 characters of lowercase letters, digits, '-' or '_'",
+        ))
+    }
+}
+
+pub async fn validate_workflow(conn: &mut SqliteConnection, workflow: &str) -> Result<(), ApiError> {
+    let exists: Option<(String,)> = sqlx::query_as("SELECT name FROM workflows WHERE name = ?")
+        .bind(workflow)
+        .fetch_optional(&mut *conn)
+        .await?;
+    match exists {
+        Some(_) => Ok(()),
+        None => Err(ApiError::bad_request(format!("workflow {} does not exist", workflow))),
+    }
+}
+
+/// 상위 task로 지정하려는 task가 같은 board에 있는지 확인
+pub async fn check_parent_board(
+    conn: &mut SqliteConnection,
+    board: &Board,
+    parent: i64,
+) -> Result<(), ApiError> {
+    let parent_board = board_of_task(conn, parent).await?;
+    if parent_board.id != board.id {
+        return Err(ApiError::bad_request(format!(
+            "parent task {} is on board {}, not {}",
+            parent, parent_board.key, board.key
+        ))
+        .with("parent_board", parent_board.key));
+    }
+    Ok(())
+}
+
+/// board를 옮긴 결과
+pub struct MovedTasks {
+    pub tasks: Vec<i64>,             // root가 첫 번째
+    pub dropped_links: Vec<TaskLink>, // 원래 board에 남은 task와 이어져 있어 지운 link
+}
+
+/// task와 그 subtree 전체를 다른 board로 옮긴다.
+/// 원래 board의 상위 task에서는 떼어 내고 새 board의 최상위 맨 뒤에 둔다.
+/// link는 한 board 안에서만 이으므로 원래 board에 남은 task와의 link는 지운다.
+pub async fn move_to_board(
+    conn: &mut SqliteConnection,
+    id: i64,
+    target: &Board,
+) -> Result<MovedTasks, ApiError> {
+    let ids: Vec<i64> = sqlx::query_as::<_, (i64,)>(
+        "WITH RECURSIVE subtree(id) AS (
+            SELECT ?1
+            UNION
+            SELECT t.id FROM tasks t JOIN subtree ON t.parent_task_id = subtree.id
+         )
+         SELECT id FROM subtree"
+    )
+    .bind(id)
+    .fetch_all(&mut *conn)
+    .await?
+    .into_iter()
+    .map(|(id,)| id)
+    .collect();
+
+    let (next_order,): (i64,) = sqlx::query_as(
+        "SELECT COALESCE(MAX(sort_order) + 1, 0) FROM tasks WHERE board_id = ? AND parent_task_id IS NULL"
+    )
+    .bind(target.id)
+    .fetch_one(&mut *conn)
+    .await?;
+    sqlx::query("UPDATE tasks SET parent_task_id = NULL, sort_order = ? WHERE id = ?")
+        .bind(next_order)
+        .bind(id)
+        .execute(&mut *conn)
+        .await?;
+
+    let now = Utc::now();
+    for task_id in &ids {
+        sqlx::query("UPDATE tasks SET board_id = ?, updated_at = ?, version = version + 1 WHERE id = ?")
+            .bind(target.id)
+            .bind(now)
+            .bind(task_id)
+            .execute(&mut *conn)
+            .await?;
+    }
+
+    let mut dropped_links = Vec::new();
+    for task_id in &ids {
+        dropped_links.extend(
+            sqlx::query_as::<_, TaskLink>(
+                "DELETE FROM task_links
+                 WHERE (source_task_id = ?1 OR target_task_id = ?1)
+                   AND (SELECT board_id FROM tasks WHERE id = source_task_id)
+                       != (SELECT board_id FROM tasks WHERE id = target_task_id)
+                 RETURNING *"
+            )
+            .bind(task_id)
+            .fetch_all(&mut *conn)
+            .await?,
+        );
+    }
+    Ok(MovedTasks { tasks: ids, dropped_links })
+}
@@ -1,17 +1,32 @@
-use sqlx::{sqlite::SqlitePoolOptions, SqlitePool};
+use std::str::FromStr;
+use std::time::Duration;
+
+use sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions};
+use sqlx::SqlitePool;
+
+// 쓰기가 겹칠 때 SQLITE_BUSY로 실패하기 전에 기다리는 시간
+const BUSY_TIMEOUT: Duration = Duration::from_secs(10);
 
 pub async fn init_db() -> Result<SqlitePool, sqlx::Error> {
     let database_url = "sqlite:kanban.db";
 
-    if !std::path::Path::new("kanban.db").exists() {
-        std::fs::File::create("kanban.db").unwrap();
-    }
+    // WAL: 읽기와 쓰기가 서로 막지 않는다 (snapshot의 VACUUM INTO 중에도 쓰기 가능)
+    let options = SqliteConnectOptions::from_str(database_url)?
+        .create_if_missing(true)
+        .journal_mode(SqliteJournalMode::Wal)
+        .busy_timeout(BUSY_TIMEOUT);
 
     let pool = SqlitePoolOptions::new()
         .max_connections(5)
-        .connect(database_url)
+        .connect_with(options)
         .await?;
 
+    create_schema(&pool).await?;
+    Ok(pool)
+}
+
+/// 테이블 생성과 migration (이미 있으면 건너뜀)
+pub async fn create_schema(pool: &SqlitePool) -> Result<(), sqlx::Error> {
     // Tasks 테이블: kanban에 추가된 일감. 영구 저장.
     sqlx::query(
         "CREATE TABLE IF NOT EXISTS tasks (
@@ -30,13 +45,46 @@ pub async fn init_db() -> Result<SqlitePool, sqlx::Error> {
             start_date      TEXT,
             due_date        TEXT,
             jira_url        TEXT,
+            sort_order      INTEGER NOT NULL DEFAULT 0,
             created_at      DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
             updated_at      DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
         )"
     )
-    .execute(&pool)
+    .execute(pool)
     .await?;
 
+    // 형제 task 사이의 순서 (POST /api/tasks/:id/move)
+    add_column_if_missing(pool, "tasks", "sort_order", "INTEGER NOT NULL DEFAULT 0").await?;
+
+    // Board: 팀별 kanban. key는 URL에 쓰는 이름 (/api/boards/:board/...)
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS boards (
+            id           INTEGER PRIMARY KEY AUTOINCREMENT,
+            key          TEXT NOT NULL UNIQUE,
+            name         TEXT NOT NULL,
+            jira_project TEXT,
+            workflow     TEXT NOT NULL DEFAULT 'default',
+            created_at   DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+        )"
+    )
+    .execute(pool)
+    .await?;
+    sqlx::query("INSERT OR IGNORE INTO boards (key, name, jira_project) VALUES ('default', 'VPC', 'VPC')")
+        .execute(pool)
+        .await?;
+
+    // board가 생기기 전의 task는 모두 기본 board로
+    add_column_if_missing(pool, "tasks", "board_id", "INTEGER REFERENCES boards(id)").await?;
+    sqlx::query("UPDATE tasks SET board_id = (SELECT id FROM boards WHERE key = 'default') WHERE board_id IS NULL")
+        .execute(pool)
+        .await?;
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_tasks_board ON tasks (board_id, parent_task_id)")
+        .execute(pool)
+        .await?;
+
+    // 낙관적 동시성 제어: task를 바꿀 때마다 +1, PUT/status는 If-Match로 확인
+    add_column_if_missing(pool, "tasks", "version", "INTEGER NOT NULL DEFAULT 1").await?;
+
     // Task comments
     sqlx::query(
         "CREATE TABLE IF NOT EXISTS task_comments (
@@ -48,8 +96,351 @@ pub async fn init_db() -> Result<SqlitePool, sqlx::Error> {
             created_at  DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
         )"
     )
-    .execute(&pool)
+    .execute(pool)
     .await?;
 
-    Ok(pool)
+    // Task 단계 전이 기록 (update_task_status에서 채움)
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS task_stage_transitions (
+            id          INTEGER PRIMARY KEY AUTOINCREMENT,
+            task_id     INTEGER NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+            from_status TEXT,
+            from_stage  TEXT,
+            to_status   TEXT NOT NULL,
+            to_stage    TEXT,
+            actor       TEXT,
+            entered_at  DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            exited_at   DATETIME
+        )"
+    )
+    .execute(pool)
+    .await?;
+
+    sqlx::query(
+        "CREATE INDEX IF NOT EXISTS idx_task_stage_transitions_task
+         ON task_stage_transitions (task_id, id)"
+    )
+    .execute(pool)
+    .await?;
+
+    // 단계별 계획 완료일 (due_date, 휴일이 바뀌면 다시 계산)
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS task_stage_deadlines (
+            task_id      INTEGER NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+            stage        TEXT NOT NULL,
+            planned_date TEXT NOT NULL,
+            PRIMARY KEY (task_id, stage)
+        )"
+    )
+    .execute(pool)
+    .await?;
+
+    // 휴일 (주말은 항상 제외)
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS holidays (
+            date        TEXT PRIMARY KEY,
+            name        TEXT
+        )"
+    )
+    .execute(pool)
+    .await?;
+
+    // 상위 task roll-up 규칙 (기본값: 모두 켜짐)
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS rollup_rules (
+            rule        TEXT PRIMARY KEY,
+            enabled     INTEGER NOT NULL DEFAULT 1
+        )"
+    )
+    .execute(pool)
+    .await?;
+
+    for rule in crate::rollup::RULES {
+        sqlx::query("INSERT OR IGNORE INTO rollup_rules (rule, enabled) VALUES (?, 1)")
+            .bind(rule)
+            .execute(pool)
+            .await?;
+    }
+
+    // Task 간 의존 관계
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS task_links (
+            id              INTEGER PRIMARY KEY AUTOINCREMENT,
+            source_task_id  INTEGER NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+            target_task_id  INTEGER NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+            link_type       TEXT NOT NULL,
+            created_at      DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            UNIQUE (source_task_id, target_task_id, link_type)
+        )"
+    )
+    .execute(pool)
+    .await?;
+
+    // 수정/삭제된 comment (삭제는 tombstone으로 남김)
+    add_column_if_missing(pool, "task_comments", "edited_at", "DATETIME").await?;
+    add_column_if_missing(pool, "task_comments", "deleted_at", "DATETIME").await?;
+    add_column_if_missing(pool, "task_comments", "deleted_by", "TEXT").await?;
+
+    // comment 수정 이력: 바뀌기 전 내용
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS task_comment_revisions (
+            id          INTEGER PRIMARY KEY AUTOINCREMENT,
+            comment_id  INTEGER NOT NULL REFERENCES task_comments(id) ON DELETE CASCADE,
+            content     TEXT NOT NULL,
+            attachments TEXT,
+            edited_by   TEXT,
+            created_at  DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+        )"
+    )
+    .execute(pool)
+    .await?;
+
+    // comment 안의 @user mention (comment 작성/수정 시 다시 저장)
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS comment_mentions (
+            id         INTEGER PRIMARY KEY AUTOINCREMENT,
+            comment_id INTEGER NOT NULL REFERENCES task_comments(id) ON DELETE CASCADE,
+            task_id    INTEGER NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+            username   TEXT NOT NULL,
+            UNIQUE (comment_id, username)
+        )"
+    )
+    .execute(pool)
+    .await?;
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_comment_mentions_user ON comment_mentions (username)")
+        .execute(pool)
+        .await?;
+
+    // comment 첨부 파일 (내용은 디스크에, 여기는 메타데이터만)
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS attachments (
+            id           INTEGER PRIMARY KEY AUTOINCREMENT,
+            task_id      INTEGER NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+            comment_id   INTEGER REFERENCES task_comments(id) ON DELETE CASCADE,
+            sha256       TEXT NOT NULL,
+            file_name    TEXT NOT NULL,
+            content_type TEXT NOT NULL,
+            size         INTEGER NOT NULL,
+            uploaded_by  TEXT,
+            created_at   DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+        )"
+    )
+    .execute(pool)
+    .await?;
+
+    // 로그인 계정
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS users (
+            id            INTEGER PRIMARY KEY AUTOINCREMENT,
+            username      TEXT NOT NULL UNIQUE,
+            display_name  TEXT,
+            password_hash TEXT NOT NULL,
+            role          TEXT NOT NULL DEFAULT 'member',
+            disabled      BOOLEAN NOT NULL DEFAULT 0,
+            created_at    DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+        )"
+    )
+    .execute(pool)
+    .await?;
+
+    // 로그인 세션 (토큰은 sha256만 저장)
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS sessions (
+            token_hash   TEXT PRIMARY KEY,
+            user_id      INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+            created_at   DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            expires_at   DATETIME NOT NULL,
+            last_used_at DATETIME
+        )"
+    )
+    .execute(pool)
+    .await?;
+
+    // 개인 API 토큰 (토큰은 sha256만 저장)
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS api_tokens (
+            id           INTEGER PRIMARY KEY AUTOINCREMENT,
+            user_id      INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+            name         TEXT NOT NULL,
+            token_hash   TEXT NOT NULL UNIQUE,
+            token_prefix TEXT NOT NULL,
+            scopes       TEXT NOT NULL,
+            created_at   DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            expires_at   DATETIME,
+            last_used_at DATETIME,
+            revoked_at   DATETIME
+        )"
+    )
+    .execute(pool)
+    .await?;
+
+    // board별 역할 (없으면 viewer)
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS board_members (
+            board   TEXT NOT NULL,
+            user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+            role    TEXT NOT NULL CHECK (role IN ('viewer', 'member', 'admin')),
+            PRIMARY KEY (board, user_id)
+        )"
+    )
+    .execute(pool)
+    .await?;
+
+    // WIP 한도 (board별): scope = "InProgress" 또는 stage 이름
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS wip_limits (
+            board_id    INTEGER NOT NULL REFERENCES boards(id) ON DELETE CASCADE,
+            scope       TEXT NOT NULL,
+            wip_limit   INTEGER NOT NULL CHECK (wip_limit >= 0),
+            PRIMARY KEY (board_id, scope)
+        )"
+    )
+    .execute(pool)
+    .await?;
+
+    // 저장된 view: query는 TaskListQuery JSON
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS saved_views (
+            id          INTEGER PRIMARY KEY AUTOINCREMENT,
+            board_id    INTEGER NOT NULL REFERENCES boards(id) ON DELETE CASCADE,
+            owner_id    INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+            name        TEXT NOT NULL,
+            shared      INTEGER NOT NULL DEFAULT 0,
+            query       TEXT NOT NULL DEFAULT '{}',
+            created_at  DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            updated_at  DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+        )"
+    )
+    .execute(pool)
+    .await?;
+
+    // Task history: WIP override 등 기록
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS task_history (
+            id          INTEGER PRIMARY KEY AUTOINCREMENT,
+            task_id     INTEGER NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+            action      TEXT NOT NULL,
+            detail      TEXT,
+            actor       TEXT,
+            created_at  DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+        )"
+    )
+    .execute(pool)
+    .await?;
+
+    // 지운 task 기록: task_history는 task와 같이 지워지므로 task를 FK로 걸지 않고 따로 남긴다
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS task_deletions (
+            id          INTEGER PRIMARY KEY AUTOINCREMENT,
+            task_id     INTEGER NOT NULL,
+            board_id    INTEGER NOT NULL REFERENCES boards(id) ON DELETE CASCADE,
+            title       TEXT NOT NULL,
+            action      TEXT NOT NULL,
+            detail      TEXT,
+            actor       TEXT,
+            created_at  DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+        )"
+    )
+    .execute(pool)
+    .await?;
+
+    // Workflow: 전이 규칙 묶음. 규칙이 하나도 없으면 모든 전이 허용.
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS workflows (
+            name        TEXT PRIMARY KEY,
+            created_at  DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+        )"
+    )
+    .execute(pool)
+    .await?;
+
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS workflow_transitions (
+            id              INTEGER PRIMARY KEY AUTOINCREMENT,
+            workflow        TEXT NOT NULL REFERENCES workflows(name) ON DELETE CASCADE,
+            from_state      TEXT NOT NULL,
+            to_state        TEXT NOT NULL,
+            required_fields TEXT,
+            require_comment INTEGER NOT NULL DEFAULT 0,
+            allowed_roles   TEXT,
+            UNIQUE (workflow, from_state, to_state)
+        )"
+    )
+    .execute(pool)
+    .await?;
+
+    seed_default_workflow(pool).await?;
+
+    Ok(())
+}
+
+/// 예전 DB 파일에 나중에 추가된 컬럼을 붙인다
+async fn add_column_if_missing(
+    pool: &SqlitePool,
+    table: &str,
+    column: &str,
+    decl: &str,
+) -> Result<(), sqlx::Error> {
+    let columns: Vec<(String,)> = sqlx::query_as(&format!("SELECT name FROM pragma_table_info('{}')", table))
+        .fetch_all(pool)
+        .await?;
+    if !columns.iter().any(|(name,)| name == column) {
+        sqlx::query(&format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, decl))
+            .execute(pool)
+            .await?;
+    }
+    Ok(())
+}
+
+/// 단계 순서 workflow: 사양확인 → CCB → 개발 → 검증 → 리뷰 → Done 순서로 진행.
+/// 개발 진입 시 담당자 필수, 이전 단계로 되돌릴 때는 comment 필수.
+/// (from, to, required_fields, require_comment)
+const STAGED_WORKFLOW: &str = "staged";
+const STAGED_RULES: [(&str, &str, Option<&str>, bool); 14] = [
+    ("Pending", "사양확인", None, false),
+    ("사양확인", "CCB", None, false),
+    ("CCB", "개발", Some("assignee"), false),
+    ("개발", "검증", None, false),
+    ("검증", "리뷰", None, false),
+    ("리뷰", "Done", None, false),
+    ("CCB", "사양확인", None, true),
+    ("개발", "CCB", None, true),
+    ("검증", "개발", None, true),
+    ("리뷰", "검증", None, true),
+    ("리뷰", "개발", None, true),
+    ("Done", "리뷰", None, true),
+    ("*", "Pending", None, true),
+    ("InProgress", "사양확인", None, false),
+];
+
+/// 기본 workflow는 규칙 없음 (모든 전이 허용). 단계 순서를 강제하려면 board의 workflow를
+/// "staged"로 바꾼다. 두 workflow 모두 처음 생성될 때만 규칙을 넣으므로 이후 수정한 규칙은 유지된다.
+async fn seed_default_workflow(pool: &SqlitePool) -> Result<(), sqlx::Error> {
+    sqlx::query("INSERT OR IGNORE INTO workflows (name) VALUES ('default')")
+        .execute(pool)
+        .await?;
+
+    let created = sqlx::query("INSERT OR IGNORE INTO workflows (name) VALUES (?)")
+        .bind(STAGED_WORKFLOW)
+        .execute(pool)
+        .await?
+        .rows_affected();
+    if created == 0 {
+        return Ok(());
+    }
+    for (from, to, required_fields, require_comment) in STAGED_RULES {
+        sqlx::query(
+            "INSERT INTO workflow_transitions (workflow, from_state, to_state, required_fields, require_comment)
+             VALUES (?, ?, ?, ?, ?)"
+        )
+        .bind(STAGED_WORKFLOW)
+        .bind(from)
+        .bind(to)
+        .bind(required_fields)
+        .bind(require_comment)
+        .execute(pool)
+        .await?;
+    }
+
+    Ok(())
 }
@@ -0,0 +1,153 @@
+use std::collections::VecDeque;
+use std::convert::Infallible;
+use std::sync::Mutex;
+
+use axum::response::sse::Event;
+use chrono::{DateTime, Utc};
+use futures_util::stream::{self, Stream};
+use serde::Serialize;
+use tokio::sync::broadcast;
+
+// ─────────────────────────────────────────────
+// 실시간 board 변경 알림 (GET /api/events, Server-Sent Events)
+//
+// - 변경한 handler가 commit 후 publish → tokio broadcast로 구독자에게 전달
+// - 최근 RECENT_EVENTS개는 메모리에 두고, 재접속 시 Last-Event-ID 이후를 다시 보낸다
+// - 그 사이 이벤트를 놓쳤으면 (서버 재시작, 버퍼 초과) "resync"를 보내 전체를 다시 읽게 한다
+// ─────────────────────────────────────────────
+
+const RECENT_EVENTS: usize = 1000;
+const CHANNEL_CAPACITY: usize = 256;
+
+pub const TASK_CREATED: &str = "task.created";
+pub const TASK_UPDATED: &str = "task.updated";
+pub const TASK_MOVED: &str = "task.moved";
+pub const TASK_DELETED: &str = "task.deleted";
+pub const COMMENT_ADDED: &str = "comment.added";
+pub const COMMENT_UPDATED: &str = "comment.updated";
+pub const COMMENT_DELETED: &str = "comment.deleted";
+pub const BOARD_UPDATED: &str = "board.updated";
+pub const RESYNC: &str = "resync";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BoardEvent {
+    pub id: u64,
+    pub kind: &'static str,
+    pub board: String,               // board key
+    pub task_id: Option<i64>,
+    pub actor: Option<String>,
+    pub data: serde_json::Value,
+    pub at: DateTime<Utc>,
+}
+
+pub struct EventHub {
+    sender: broadcast::Sender<BoardEvent>,
+    // 최근 이벤트 (id 오름차순). id 발급과 push를 같은 lock 안에서 해 순서를 보장한다.
+    recent: Mutex<(u64, VecDeque<BoardEvent>)>,
+}
+
+impl EventHub {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(CHANNEL_CAPACITY);
+        Self { sender, recent: Mutex::new((0, VecDeque::with_capacity(RECENT_EVENTS))) }
+    }
+
+    pub fn publish(
+        &self,
+        kind: &'static str,
+        board: &str,
+        task_id: Option<i64>,
+        actor: Option<&str>,
+        data: serde_json::Value,
+    ) {
+        let mut recent = self.recent.lock().unwrap();
+        let (last_id, buffer) = &mut *recent;
+        *last_id += 1;
+        let event = BoardEvent {
+            id: *last_id,
+            kind,
+            board: board.to_string(),
+            task_id,
+            actor: actor.map(str::to_string),
+            data,
+            at: Utc::now(),
+        };
+        if buffer.len() == RECENT_EVENTS {
+            buffer.pop_front();
+        }
+        buffer.push_back(event.clone());
+        // 구독자가 없으면 Err지만 무시해도 된다
+        let _ = self.sender.send(event);
+    }
+
+    /// boards(None이면 전체)의 이벤트 stream. last_event_id가 있으면 그 이후부터.
+    pub fn subscribe(
+        &self,
+        boards: Option<Vec<String>>,
+        last_event_id: Option<u64>,
+    ) -> impl Stream<Item = Result<Event, Infallible>> + Send + use<> {
+        // 놓치는 이벤트가 없도록 receiver를 먼저 만들고 버퍼를 읽는다
+        let receiver = self.sender.subscribe();
+        let (replay, resync, last_sent) = {
+            let recent = self.recent.lock().unwrap();
+            let (last_id, buffer) = &*recent;
+            match last_event_id {
+                None => (Vec::new(), false, *last_id),
+                Some(since) => {
+                    let oldest = buffer.front().map_or(*last_id + 1, |e| e.id);
+                    let missed = since > *last_id || since + 1 < oldest;
+                    let replay = buffer.iter().filter(|e| e.id > since).cloned().collect();
+                    (if missed { Vec::new() } else { replay }, missed, *last_id)
+                }
+            }
+        };
+
+        let matches = move |event: &BoardEvent| boards.as_ref().is_none_or(|b| b.contains(&event.board));
+        let mut head: Vec<Event> = Vec::new();
+        if resync {
+            head.push(resync_event(last_sent));
+        }
+        head.extend(replay.iter().filter(|e| matches(e)).map(to_sse));
+
+        let live = stream::unfold((receiver, last_sent), move |(mut receiver, mut last_sent)| {
+            let matches = matches.clone();
+            async move {
+                loop {
+                    match receiver.recv().await {
+                        // 버퍼에서 이미 보낸 이벤트는 건너뜀
+                        Ok(event) if event.id <= last_sent || !matches(&event) => continue,
+                        Ok(event) => {
+                            last_sent = event.id;
+                            return Some((Ok(to_sse(&event)), (receiver, last_sent)));
+                        }
+                        Err(broadcast::error::RecvError::Lagged(_)) => {
+                            return Some((Ok(resync_event(last_sent)), (receiver, last_sent)));
+                        }
+                        Err(broadcast::error::RecvError::Closed) => return None,
+                    }
+                }
+            }
+        });
+        stream::StreamExt::chain(stream::iter(head.into_iter().map(Ok)), live)
+    }
+}
+
+fn to_sse(event: &BoardEvent) -> Event {
+    Event::default()
+        .id(event.id.to_string())
+        .json_data(event)
+        .unwrap_or_else(|_| Event::default().id(event.id.to_string()))
+}
+
+/// 놓친 이벤트가 있으니 전체를 다시 읽으라는 알림 (id는 지금까지의 마지막 id)
+fn resync_event(last_id: u64) -> Event {
+    to_sse(&BoardEvent {
+        id: last_id,
+        kind: RESYNC,
+        board: String::new(),
+        task_id: None,
+        actor: None,
+        data: serde_json::Value::Null,
+        at: Utc::now(),
+    })
+}
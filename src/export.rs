@@ -0,0 +1,208 @@
+use std::collections::{HashMap, HashSet};
+
+use axum::http::StatusCode;
+use chrono::{Datelike, NaiveDate};
+use rust_xlsxwriter::{ExcelDateTime, Format, Workbook};
+
+use crate::error::ApiError;
+use crate::jira;
+use crate::models::Task;
+use crate::workflow::STAGES;
+
+// ─────────────────────────────────────────────
+// Task 표 내보내기 (GET /api/tasks/export?format=csv|xlsx)
+//
+// - 목록 API와 같은 필터/정렬로 읽은 task를 트리 순서로 펼치고 제목을 깊이만큼 들여쓴다
+// - CSV는 Excel이 한글을 깨뜨리지 않도록 UTF-8 BOM + CRLF
+// - 사용자가 넣은 글자는 수식으로 읽히지 않게 한다 (CSV는 ' 접두, xlsx는 문자열 셀)
+// ─────────────────────────────────────────────
+
+pub const CSV_CONTENT_TYPE: &str = "text/csv; charset=utf-8";
+pub const XLSX_CONTENT_TYPE: &str = "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet";
+
+const UTF8_BOM: &[u8] = b"\xEF\xBB\xBF";
+const MAX_INDENT: usize = 15;        // Excel 들여쓰기 상한
+
+enum Cell {
+    Text(Option<String>),
+    Title(String, usize),            // 제목, 트리 깊이
+    Date(Option<NaiveDate>),
+    Percent(i64),                    // 0~100
+    Int(Option<i64>),
+    Link(Option<String>),            // Jira 티켓 주소
+}
+
+/// 내보낼 task를 트리 순서(상위 → 하위)로 펼친 (task, 깊이).
+/// 상위 task가 목록에 없으면 (필터로 빠진 경우) 그 task를 최상위로 본다. 형제 순서는 받은 순서.
+pub fn tree_rows(tasks: Vec<Task>) -> Vec<(Task, usize)> {
+    let ids: HashSet<i64> = tasks.iter().map(|t| t.id).collect();
+    let mut children: HashMap<Option<i64>, Vec<Task>> = HashMap::new();
+    for task in tasks {
+        let parent = task.parent_task_id.filter(|p| ids.contains(p));
+        children.entry(parent).or_default().push(task);
+    }
+
+    let mut rows = Vec::with_capacity(ids.len());
+    let mut stack: Vec<(Task, usize)> = children.remove(&None).unwrap_or_default().into_iter().rev().map(|t| (t, 0)).collect();
+    while let Some((task, depth)) = stack.pop() {
+        if let Some(kids) = children.remove(&Some(task.id)) {
+            stack.extend(kids.into_iter().rev().map(|t| (t, depth + 1)));
+        }
+        rows.push((task, depth));
+    }
+    rows
+}
+
+fn headers() -> Vec<String> {
+    let mut headers: Vec<String> = [
+        "ID", "Jira", "제목", "Alias", "상태", "단계", "담당자", "프로젝트", "태그",
+        "Start Date", "Due Date", "계획 진행도", "실제 진행도", "지연", "단계 경과일",
+    ]
+    .iter()
+    .map(|h| h.to_string())
+    .collect();
+    headers.extend(STAGES.iter().chain(["Done"].iter()).map(|s| format!("{} 계획일", s)));
+    headers.push("Jira 링크".to_string());
+    headers
+}
+
+fn cells(task: &Task, depth: usize) -> Vec<Cell> {
+    let mut cells = vec![
+        Cell::Int(Some(task.id)),
+        Cell::Text(task.jira_ticket_key.clone()),
+        Cell::Title(task.title.clone(), depth),
+        Cell::Text(task.alias.clone()),
+        Cell::Text(Some(task.status.clone())),
+        Cell::Text(task.stage.clone()),
+        Cell::Text(task.assignee.clone()),
+        Cell::Text(task.project_key.clone()),
+        Cell::Text(task.tags.clone()),
+        Cell::Date(task.start_date),
+        Cell::Date(task.due_date),
+        Cell::Percent(task.ideal_progress),
+        Cell::Percent(task.actual_progress),
+        Cell::Text(task.is_late.then(|| "Y".to_string())),
+        Cell::Int(task.days_in_stage),
+    ];
+    for stage in STAGES.iter().chain(["Done"].iter()) {
+        let planned = task.stage_deadlines.iter().find(|d| d.stage == *stage).map(|d| d.planned_date);
+        cells.push(Cell::Date(planned));
+    }
+    cells.push(Cell::Link(jira_link(task)));
+    cells
+}
+
+/// 저장된 주소가 없으면 티켓 키로 만든다
+fn jira_link(task: &Task) -> Option<String> {
+    task.jira_url
+        .clone()
+        .or_else(|| task.jira_ticket_key.as_ref().map(|key| format!("{}{}", jira::BROWSE_URL, key)))
+}
+
+fn export_error(e: impl std::fmt::Display) -> ApiError {
+    ApiError::new(StatusCode::INTERNAL_SERVER_ERROR, "export_failed", e.to_string())
+}
+
+/// Excel이 수식으로 읽는 첫 글자
+const FORMULA_PREFIXES: [char; 6] = ['=', '+', '-', '@', '\t', '\r'];
+
+/// 수식으로 시작하는 CSV 칸은 ' 를 붙여 글자로 읽히게 한다
+fn csv_text(value: String) -> String {
+    if value.starts_with(FORMULA_PREFIXES) {
+        format!("'{}", value)
+    } else {
+        value
+    }
+}
+
+/// http(s) 주소만 링크로 쓴다. 아니면 (수식 포함) 문자열 셀로
+fn is_web_url(url: &str) -> bool {
+    let lower = url.to_ascii_lowercase();
+    lower.starts_with("http://") || lower.starts_with("https://")
+}
+
+pub fn to_csv(rows: &[(Task, usize)]) -> Result<Vec<u8>, ApiError> {
+    let mut writer = csv::WriterBuilder::new()
+        .terminator(csv::Terminator::CRLF)
+        .from_writer(UTF8_BOM.to_vec());
+    writer.write_record(headers()).map_err(export_error)?;
+    for (task, depth) in rows {
+        let record: Vec<String> = cells(task, *depth)
+            .into_iter()
+            .map(|cell| match cell {
+                Cell::Text(v) | Cell::Link(v) => csv_text(v.unwrap_or_default()),
+                Cell::Title(title, depth) => format!("{}{}", "  ".repeat(depth), csv_text(title)),
+                Cell::Date(d) => d.map(|d| d.to_string()).unwrap_or_default(),
+                Cell::Percent(p) => format!("{}%", p),
+                Cell::Int(n) => n.map(|n| n.to_string()).unwrap_or_default(),
+            })
+            .collect();
+        writer.write_record(record).map_err(export_error)?;
+    }
+    writer.into_inner().map_err(export_error)
+}
+
+pub fn to_xlsx(rows: &[(Task, usize)], sheet_name: &str) -> Result<Vec<u8>, ApiError> {
+    let header = Format::new().set_bold();
+    let date = Format::new().set_num_format("yyyy-mm-dd");
+    let percent = Format::new().set_num_format("0%");
+    let indents: Vec<Format> = (0..=MAX_INDENT).map(|i| Format::new().set_indent(i as u8)).collect();
+
+    let mut workbook = Workbook::new();
+    let sheet = workbook.add_worksheet();
+    // 시트 이름은 31자까지
+    sheet.set_name(sheet_name.chars().take(31).collect::<String>()).map_err(export_error)?;
+    let headers = headers();
+    for (col, title) in headers.iter().enumerate() {
+        sheet.write_string_with_format(0, col as u16, title, &header).map_err(export_error)?;
+    }
+
+    for (i, (task, depth)) in rows.iter().enumerate() {
+        let row = i as u32 + 1;
+        for (col, cell) in cells(task, *depth).into_iter().enumerate() {
+            let col = col as u16;
+            match cell {
+                Cell::Text(Some(v)) => sheet.write_string(row, col, v),
+                Cell::Title(title, depth) => sheet.write_string_with_format(row, col, title, &indents[depth.min(MAX_INDENT)]),
+                Cell::Date(Some(d)) => {
+                    let d = ExcelDateTime::from_ymd(d.year() as u16, d.month() as u8, d.day() as u8).map_err(export_error)?;
+                    sheet.write_datetime_with_format(row, col, d, &date)
+                }
+                Cell::Percent(p) => sheet.write_number_with_format(row, col, p as f64 / 100.0, &percent),
+                Cell::Int(Some(n)) => sheet.write_number(row, col, n as f64),
+                Cell::Link(Some(url)) if !is_web_url(&url) => sheet.write_string(row, col, url),
+                Cell::Link(Some(url)) => sheet.write_url_with_text(row, col, url.as_str(), task.jira_ticket_key.clone().unwrap_or_else(|| url.clone())),
+                Cell::Text(None) | Cell::Date(None) | Cell::Int(None) | Cell::Link(None) => continue,
+            }
+            .map_err(export_error)?;
+        }
+    }
+
+    sheet.set_freeze_panes(1, 0).map_err(export_error)?;
+    sheet.autofilter(0, 0, rows.len() as u32, headers.len() as u16 - 1).map_err(export_error)?;
+    sheet.autofit();
+    workbook.save_to_buffer().map_err(export_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn csv_text_quotes_formulas() {
+        for value in ["=1+2", "+1", "-1", "@SUM(A1)", "\tx", "\rx", "=HYPERLINK(\"http://x\")"] {
+            assert_eq!(csv_text(value.to_string()), format!("'{}", value));
+        }
+        for value in ["", "plain", "1-2", "a=b", "'quoted"] {
+            assert_eq!(csv_text(value.to_string()), value);
+        }
+    }
+
+    #[test]
+    fn only_web_urls_are_links() {
+        assert!(is_web_url("https://jira.example.com/browse/VPC-1"));
+        assert!(is_web_url("HTTP://jira.example.com"));
+        assert!(!is_web_url("=HYPERLINK(\"http://x\")"));
+        assert!(!is_web_url("javascript:alert(1)"));
+    }
+}
@@ -0,0 +1,302 @@
+use std::collections::HashMap;
+
+use chrono::NaiveDate;
+use sqlx::SqliteConnection;
+
+use crate::error::ApiError;
+use crate::models::{Board, CreateTaskRequest, ImportNode, ImportResult, ImportRowError};
+
+// ─────────────────────────────────────────────
+// CSV로 task 가져오기 (POST /api/tasks/import)
+//
+// - 열 이름은 CreateTaskRequest 필드와 같다 (title만 필수, project_key가 비면 board의 Jira 프로젝트)
+// - row_id: 파일 안에서만 쓰는 행 이름, parent: 상위 task (row_id → alias → Jira 키 순으로 찾음,
+//   파일에 없으면 board의 기존 task alias / Jira 키)
+// - board에 이미 있는 jira_ticket_key는 새로 만들지 않고 기존 task를 쓴다 (하위 행은 그 아래로)
+// - 먼저 전부 검사해서 계획(트리)을 만들고, dry-run이 아니고 오류가 없을 때만 추가한다
+// ─────────────────────────────────────────────
+
+pub const MAX_ROWS: usize = 2000;
+
+const COLUMNS: [&str; 11] = [
+    "row_id", "parent", "title", "description", "jira_ticket_key", "assignee",
+    "project_key", "alias", "start_date", "due_date", "jira_url",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Parent {
+    Row(usize),                      // 파일의 다른 행 (index)
+    Task(i64),                       // board의 기존 task
+}
+
+pub struct PlannedRow {
+    pub line: u64,                   // CSV 줄 번호 (header = 1)
+    pub row_id: Option<String>,
+    pub parent_ref: Option<String>,
+    pub parent: Option<Parent>,
+    pub req: CreateTaskRequest,
+    pub existing: Option<i64>,       // 같은 jira_ticket_key로 이미 있는 task
+}
+
+pub struct ImportPlan {
+    pub rows: Vec<PlannedRow>,
+    pub errors: Vec<ImportRowError>,
+}
+
+impl ImportPlan {
+    /// 상위 → 하위 순서 (추가할 때 parent id가 먼저 정해지도록)
+    pub fn order(&self) -> Vec<usize> {
+        let mut children: HashMap<Option<usize>, Vec<usize>> = HashMap::new();
+        for (i, row) in self.rows.iter().enumerate() {
+            let parent = match row.parent {
+                Some(Parent::Row(p)) => Some(p),
+                _ => None,
+            };
+            children.entry(parent).or_default().push(i);
+        }
+        let mut order = Vec::with_capacity(self.rows.len());
+        let mut stack: Vec<usize> = children.remove(&None).unwrap_or_default().into_iter().rev().collect();
+        while let Some(i) = stack.pop() {
+            order.push(i);
+            if let Some(kids) = children.remove(&Some(i)) {
+                stack.extend(kids.into_iter().rev());
+            }
+        }
+        order
+    }
+
+    /// 응답: 오류와 가져올 (가져온) 트리. ids는 행별 task id (기존 또는 새로 만든 것).
+    pub fn result(&self, dry_run: bool, ids: &[Option<i64>]) -> ImportResult {
+        let mut nodes: Vec<Option<ImportNode>> = self
+            .rows
+            .iter()
+            .enumerate()
+            .map(|(i, row)| {
+                Some(ImportNode {
+                    line: row.line,
+                    row_id: row.row_id.clone(),
+                    title: row.req.title.clone(),
+                    jira_ticket_key: row.req.jira_ticket_key.clone(),
+                    action: if row.existing.is_some() { "skip" } else { "create" }.to_string(),
+                    task_id: ids.get(i).copied().flatten().or(row.existing),
+                    parent_task_id: match row.parent {
+                        Some(Parent::Task(id)) => Some(id),
+                        _ => None,
+                    },
+                    children: Vec::new(),
+                })
+            })
+            .collect();
+
+        // 하위 → 상위 순서로 붙인다
+        let order = self.order();
+        for &i in order.iter().rev() {
+            if let Some(Parent::Row(p)) = self.rows[i].parent
+                && let Some(node) = nodes[i].take()
+                && let Some(parent) = nodes[p].as_mut()
+            {
+                parent.children.insert(0, node);
+            }
+        }
+        let tree: Vec<ImportNode> = order.iter().filter_map(|&i| nodes[i].take()).collect();
+
+        ImportResult {
+            dry_run,
+            created: self.rows.iter().filter(|r| r.existing.is_none()).count(),
+            skipped: self.rows.iter().filter(|r| r.existing.is_some()).count(),
+            errors: self.errors.clone(),
+            tree,
+        }
+    }
+}
+
+/// CSV를 읽고 검사해 계획을 만든다. 열 구성이 잘못됐으면 400, 행 오류는 plan.errors.
+pub async fn plan(conn: &mut SqliteConnection, board: &Board, csv_text: &str) -> Result<ImportPlan, ApiError> {
+    let csv_text = csv_text.trim_start_matches('\u{feff}');
+    let mut reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_reader(csv_text.as_bytes());
+    let headers: Vec<String> = reader
+        .headers()
+        .map_err(|e| ApiError::bad_request(format!("invalid CSV header: {}", e)))?
+        .iter()
+        .map(|h| h.to_lowercase())
+        .collect();
+    let unknown: Vec<&String> = headers.iter().filter(|h| !COLUMNS.contains(&h.as_str())).collect();
+    if !unknown.is_empty() {
+        return Err(ApiError::bad_request(format!("unknown CSV column(s): {:?}", unknown)).with("columns", COLUMNS));
+    }
+    if !headers.iter().any(|h| h == "title") {
+        return Err(ApiError::bad_request("CSV needs a title column").with("columns", COLUMNS));
+    }
+
+    let mut rows = Vec::new();
+    let mut errors = Vec::new();
+    for record in reader.records() {
+        let record = record.map_err(|e| ApiError::bad_request(format!("invalid CSV: {}", e)))?;
+        if rows.len() == MAX_ROWS {
+            return Err(ApiError::bad_request(format!("at most {} rows can be imported at once", MAX_ROWS)));
+        }
+        let line = record.position().map_or(0, |p| p.line());
+        let field = |name: &str| {
+            headers
+                .iter()
+                .position(|h| h == name)
+                .and_then(|i| record.get(i))
+                .filter(|v| !v.is_empty())
+                .map(str::to_string)
+        };
+        let mut date = |name: &str| match field(name) {
+            Some(v) => match NaiveDate::parse_from_str(&v, "%Y-%m-%d") {
+                Ok(d) => Some(d),
+                Err(_) => {
+                    errors.push(row_error(line, format!("{} must be YYYY-MM-DD, got {}", name, v)));
+                    None
+                }
+            },
+            None => None,
+        };
+        let (start_date, due_date) = (date("start_date"), date("due_date"));
+
+        let title = field("title").unwrap_or_default();
+        if title.is_empty() {
+            errors.push(row_error(line, "title is required".to_string()));
+        }
+        rows.push(PlannedRow {
+            line,
+            row_id: field("row_id"),
+            parent_ref: field("parent"),
+            parent: None,
+            req: CreateTaskRequest {
+                title,
+                description: field("description"),
+                jira_ticket_key: field("jira_ticket_key"),
+                assignee: field("assignee"),
+                project_key: field("project_key").or_else(|| board.jira_project.clone()),
+                parent_task_id: None,
+                alias: field("alias"),
+                start_date,
+                due_date,
+                jira_url: field("jira_url"),
+            },
+            existing: None,
+        });
+    }
+
+    check_duplicates(&rows, &mut errors);
+    for row in &mut rows {
+        if let Some(key) = &row.req.jira_ticket_key {
+            row.existing = existing_task(conn, board, "jira_ticket_key", key).await?;
+        }
+    }
+    resolve_parents(conn, board, &mut rows, &mut errors).await?;
+    check_cycles(&rows, &mut errors);
+
+    errors.sort_by_key(|e| e.line);
+    Ok(ImportPlan { rows, errors })
+}
+
+fn row_error(line: u64, message: String) -> ImportRowError {
+    ImportRowError { line, message }
+}
+
+/// 파일 안에서 row_id / jira_ticket_key가 겹치면 뒤의 행이 오류
+fn check_duplicates(rows: &[PlannedRow], errors: &mut Vec<ImportRowError>) {
+    let mut row_ids: HashMap<&str, u64> = HashMap::new();
+    let mut keys: HashMap<&str, u64> = HashMap::new();
+    for row in rows {
+        if let Some(id) = row.row_id.as_deref()
+            && let Some(first) = row_ids.insert(id, row.line)
+        {
+            errors.push(row_error(row.line, format!("row_id {} is already used on line {}", id, first)));
+        }
+        if let Some(key) = row.req.jira_ticket_key.as_deref()
+            && let Some(first) = keys.insert(key, row.line)
+        {
+            errors.push(row_error(row.line, format!("jira_ticket_key {} is already used on line {}", key, first)));
+        }
+    }
+}
+
+/// board에서 column = value인 task (여러 개면 가장 먼저 만든 것)
+async fn existing_task(
+    conn: &mut SqliteConnection,
+    board: &Board,
+    column: &str,
+    value: &str,
+) -> Result<Option<i64>, sqlx::Error> {
+    let row: Option<(i64,)> = sqlx::query_as(&format!(
+        "SELECT id FROM tasks WHERE board_id = ? AND {} = ? ORDER BY id LIMIT 1",
+        column
+    ))
+    .bind(board.id)
+    .bind(value)
+    .fetch_optional(&mut *conn)
+    .await?;
+    Ok(row.map(|(id,)| id))
+}
+
+/// parent 참조 → 파일의 행 (row_id, alias, Jira 키 순) → board의 기존 task (alias, Jira 키 순)
+async fn resolve_parents(
+    conn: &mut SqliteConnection,
+    board: &Board,
+    rows: &mut [PlannedRow],
+    errors: &mut Vec<ImportRowError>,
+) -> Result<(), sqlx::Error> {
+    let index = |get: fn(&PlannedRow) -> Option<&String>| -> HashMap<String, usize> {
+        let mut map = HashMap::new();
+        for (i, row) in rows.iter().enumerate() {
+            if let Some(v) = get(row) {
+                map.entry(v.clone()).or_insert(i);
+            }
+        }
+        map
+    };
+    let by_row_id = index(|r| r.row_id.as_ref());
+    let by_alias = index(|r| r.req.alias.as_ref());
+    let by_key = index(|r| r.req.jira_ticket_key.as_ref());
+
+    for (i, row) in rows.iter_mut().enumerate() {
+        let Some(reference) = row.parent_ref.clone() else { continue };
+        let in_file = by_row_id
+            .get(&reference)
+            .or_else(|| by_alias.get(&reference))
+            .or_else(|| by_key.get(&reference))
+            .copied();
+        let parent = match in_file {
+            Some(p) if p == i => {
+                errors.push(row_error(row.line, "a task cannot be its own parent".to_string()));
+                None
+            }
+            Some(p) => Some(Parent::Row(p)),
+            None => match existing_task(conn, board, "alias", &reference).await? {
+                Some(id) => Some(Parent::Task(id)),
+                None => existing_task(conn, board, "jira_ticket_key", &reference).await?.map(Parent::Task),
+            },
+        };
+        if in_file.is_none() && parent.is_none() {
+            errors.push(row_error(
+                row.line,
+                format!("parent {} matches no row_id, alias or Jira key in the file or on board {}", reference, board.key),
+            ));
+        }
+        row.parent = parent;
+    }
+    Ok(())
+}
+
+/// 파일 안의 parent 참조가 순환하면 그 행들은 오류
+fn check_cycles(rows: &[PlannedRow], errors: &mut Vec<ImportRowError>) {
+    for (i, row) in rows.iter().enumerate() {
+        let mut current = row.parent;
+        let mut steps = 0;
+        while let Some(Parent::Row(p)) = current {
+            if p == i || steps > rows.len() {
+                errors.push(row_error(row.line, "parent references form a cycle".to_string()));
+                break;
+            }
+            current = rows[p].parent;
+            steps += 1;
+        }
+    }
+}
@@ -1,6 +1,9 @@
-use crate::models::{JiraTicket, JiraQuery};
+use crate::models::{JiraIssueLink, JiraTicket, JiraQuery};
 use chrono::{Utc, NaiveDate};
 
+// 티켓 화면 주소 (VPC-101 같은 참조를 링크로 바꿀 때 사용)
+pub const BROWSE_URL: &str = "https://jira.example.com/browse/";
+
 pub struct JiraClient {
     // 실제 구현 시: base_url, api_token, user_email 등 보유
 }
@@ -38,6 +41,15 @@ impl JiraClient {
                         project,
                         200 + (i - 1) * 2 + j
                     )),
+                    // 첫 번째 sub-task가 두 번째 sub-task를 막음
+                    issue_links: if j == 1 {
+                        vec![JiraIssueLink {
+                            link_type: "blocks".to_string(),
+                            target_key: format!("{}-{}", project, 200 + (i - 1) * 2 + 2),
+                        }]
+                    } else {
+                        vec![]
+                    },
                 })
                 .collect();
 
@@ -59,6 +71,7 @@ impl JiraClient {
                 due_date: Some(NaiveDate::from_ymd_opt(2026, 3, 15 + i as u32 * 5).unwrap()),
                 created_at: Utc::now(),
                 jira_url: Some(format!("https://jira.example.com/browse/{}", parent_key)),
+                issue_links: vec![],
             });
         }
 
@@ -79,6 +92,7 @@ impl JiraClient {
                 due_date: Some(NaiveDate::from_ymd_opt(2026, 3, 1 + i as u32).unwrap()),
                 created_at: Utc::now(),
                 jira_url: Some(format!("https://jira.example.com/browse/{}", key)),
+                issue_links: vec![],
             });
         }
 
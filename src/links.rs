@@ -0,0 +1,121 @@
+use sqlx::SqliteConnection;
+
+use crate::error::ApiError;
+use crate::models::{BlockingTask, TaskLink};
+
+// ─────────────────────────────────────────────
+// Task 간 의존 관계: "A blocks B" (B는 A가 끝나야 진행 가능)
+// ─────────────────────────────────────────────
+
+pub const BLOCKS: &str = "blocks";
+pub const LINK_TYPES: [&str; 3] = [BLOCKS, "relates_to", "duplicates"];
+
+// parent 트리와 같은 이유로 재귀 CTE 상한을 둔다
+const MAX_LINK_DEPTH: i64 = 256;
+
+/// source → target 'blocks' 링크를 추가하면 사이클이 생기는지
+/// (target에서 blocks를 따라가 source에 닿으면 사이클)
+pub async fn would_create_cycle(
+    conn: &mut SqliteConnection,
+    source: i64,
+    target: i64,
+) -> Result<bool, sqlx::Error> {
+    if source == target {
+        return Ok(true);
+    }
+    let (found,): (bool,) = sqlx::query_as(
+        "WITH RECURSIVE reach(id, depth) AS (
+            SELECT ?1, 0
+            UNION
+            SELECT l.target_task_id, reach.depth + 1 FROM task_links l
+              JOIN reach ON l.source_task_id = reach.id
+             WHERE l.link_type = 'blocks' AND reach.depth < ?3
+         )
+         SELECT EXISTS (SELECT 1 FROM reach WHERE id = ?2)"
+    )
+    .bind(target)
+    .bind(source)
+    .bind(MAX_LINK_DEPTH)
+    .fetch_one(&mut *conn)
+    .await?;
+    Ok(found)
+}
+
+/// 링크 추가. 알 수 없는 타입, 없는 task, 다른 board의 task, 중복, blocks 사이클은 거부.
+pub async fn create_link(
+    conn: &mut SqliteConnection,
+    source: i64,
+    target: i64,
+    link_type: &str,
+) -> Result<TaskLink, ApiError> {
+    if !LINK_TYPES.contains(&link_type) {
+        return Err(ApiError::bad_request(format!(
+            "unknown link type {}; expected one of {:?}",
+            link_type, LINK_TYPES
+        )));
+    }
+    if source == target {
+        return Err(ApiError::bad_request("a task cannot be linked to itself"));
+    }
+    let (found, boards): (i64, i64) = sqlx::query_as(
+        "SELECT COUNT(*), COUNT(DISTINCT board_id) FROM tasks WHERE id IN (?, ?)"
+    )
+    .bind(source)
+    .bind(target)
+    .fetch_one(&mut *conn)
+    .await?;
+    if found < 2 {
+        return Err(ApiError::not_found(format!("task {} or {} not found", source, target)));
+    }
+    // 다른 board의 task와는 잇지 않는다 (board 단위 분석, 백업에서 빠진다)
+    if boards > 1 {
+        return Err(ApiError::bad_request(format!(
+            "task {} and task {} are on different boards; links must stay within one board",
+            source, target
+        )));
+    }
+    if link_type == BLOCKS && would_create_cycle(conn, source, target).await? {
+        return Err(ApiError::conflict(
+            "dependency_cycle",
+            format!("task {} blocking task {} would create a dependency cycle", source, target),
+        ));
+    }
+
+    let id = sqlx::query(
+        "INSERT INTO task_links (source_task_id, target_task_id, link_type) VALUES (?, ?, ?)"
+    )
+    .bind(source)
+    .bind(target)
+    .bind(link_type)
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| match e {
+        sqlx::Error::Database(ref db) if db.is_unique_violation() => ApiError::conflict(
+            "duplicate_link",
+            format!("task {} already {} task {}", source, link_type, target),
+        ),
+        e => e.into(),
+    })?
+    .last_insert_rowid();
+
+    Ok(sqlx::query_as::<_, TaskLink>("SELECT * FROM task_links WHERE id = ?")
+        .bind(id)
+        .fetch_one(&mut *conn)
+        .await?)
+}
+
+/// task를 막고 있는, 아직 Done이 아닌 task 목록
+pub async fn open_blockers(
+    conn: &mut SqliteConnection,
+    task_id: i64,
+) -> Result<Vec<BlockingTask>, sqlx::Error> {
+    sqlx::query_as::<_, BlockingTask>(
+        "SELECT t.id, t.title, t.status, t.stage, t.jira_ticket_key FROM task_links l
+           JOIN tasks t ON t.id = l.source_task_id
+          WHERE l.target_task_id = ? AND l.link_type = 'blocks' AND t.status != 'Done'
+          ORDER BY t.id"
+    )
+    .bind(task_id)
+    .fetch_all(&mut *conn)
+    .await
+}
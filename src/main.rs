@@ -1,98 +1,1066 @@
 mod models;
 mod db;
 mod jira;
+mod error;
+mod workflow;
+mod schedule;
+mod tree;
+mod rollup;
+mod links;
+mod analysis;
+mod attachments;
+mod markdown;
+mod auth;
+mod boards;
+mod events;
+mod task_query;
+mod views;
+mod export;
+mod import;
+mod backup;
+mod snapshot;
+#[cfg(test)]
+mod test_support;
+#[cfg(test)]
+mod route_tests;
 
 use axum::{
     routing::{get, post, put, delete as axum_delete},
-    extract::{State, Path},
+    extract::{State, Path, Query, Multipart, DefaultBodyLimit},
+    http::{header, HeaderMap, Method},
+    middleware,
+    response::{IntoResponse, Response, sse::{KeepAlive, Sse}},
     Json, Router,
 };
 use std::net::SocketAddr;
 use sqlx::SqlitePool;
 use tower_http::services::ServeDir;
-use tower_http::cors::{Any, CorsLayer};
+use tower_http::cors::{AllowOrigin, CorsLayer};
 use models::{
     JiraTicket, JiraQuery,
-    Task, TaskComment,
+    Task, TaskComment, TaskCommentRevision, CreateCommentRequest, UpdateCommentRequest, Attachment, MentionedComment, MentionQuery, TaskHistory, TaskDeletion, TaskStageTransition,
     CreateTaskRequest, UpdateTaskRequest, UpdateStatusRequest,
+    TaskFilter, TaskPageQuery, TaskListQuery, ExportQuery, ImportQuery, ImportResult, RestoreQuery, SavedView, CreateViewRequest, UpdateViewRequest, ViewPageQuery,
+    BulkTaskRequest, BulkOperation, BulkTaskResult,
     AddToKanbanRequest,
+    BoardSummary, WipLimit, SetWipLimitRequest,
+    TransitionRule, TransitionRuleInput,
+    Holiday, SetHolidayRequest,
+    TaskNode, TaskTreeQuery, MoveTaskRequest,
+    RollupRule, RollupViolation, SetRollupRuleRequest,
+    TaskLink, BlockingTask, CreateTaskLinkRequest,
+    ScheduleAnalysis, ScheduleAnalysisQuery,
+    EventsQuery,
+    Board, BoardWithRole, CreateBoardRequest, UpdateBoardRequest, MoveToBoardRequest,
+    User, Me, ApiToken, CreateApiTokenRequest, CreatedApiToken, BoardMembership, MemberPath, SetBoardRoleRequest, LoginRequest, LoginResponse, CreateUserRequest, UpdateUserRequest, ChangePasswordRequest,
 };
 use jira::JiraClient;
-use chrono::Utc;
+use events::EventHub;
+use error::ApiError;
+use auth::{BoardAccess, BoardAdmin, BoardMember, BoardViewer, CurrentUser};
+use chrono::{NaiveDate, Utc};
 
 #[derive(Clone)]
 struct AppState {
     db: SqlitePool,
     jira: std::sync::Arc<JiraClient>,
+    events: std::sync::Arc<EventHub>,
+    snapshots: std::sync::Arc<snapshot::Snapshots>,
+}
+
+// board 역할 extractor(auth::BoardAccess)가 DB를 꺼내 쓸 수 있도록
+impl axum::extract::FromRef<AppState> for SqlitePool {
+    fn from_ref(state: &AppState) -> Self {
+        state.db.clone()
+    }
 }
 
 #[tokio::main]
 async fn main() {
     let pool = db::init_db().await.expect("Failed to initialize database");
+    // 명령행 backup/restore: 서버를 띄우지 않고 끝낸다
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if !args.is_empty() {
+        if let Err(e) = backup::run_cli(&pool, &args).await {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+    auth::ensure_admin_user(&pool).await.expect("Failed to create the admin user");
+    {
+        let mut conn = pool.acquire().await.expect("Failed to acquire connection");
+        schedule::refresh_all_stage_deadlines(&mut conn)
+            .await
+            .expect("Failed to compute stage deadlines");
+    }
     let state = AppState {
         db: pool,
         jira: std::sync::Arc::new(JiraClient::new()),
+        events: std::sync::Arc::new(EventHub::new()),
+        snapshots: std::sync::Arc::new(snapshot::Snapshots::from_env()),
     };
+    state.snapshots.spawn_schedule(state.db.clone());
+
+    let app = app(state);
 
-    let cors = CorsLayer::new()
-        .allow_origin(Any)
-        .allow_methods(Any)
-        .allow_headers(Any);
+    let addr = SocketAddr::from(([127, 0, 0, 1], 3000));
+    println!("Listening on http://{}", addr);
+    let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
+    axum::serve(listener, app).await.unwrap();
+}
+
+/// 다른 origin에서의 호출은 KANBAN_ALLOWED_ORIGINS (쉼표로 구분, 예: "https://ci.example.com")에 있는 것만 허용.
+/// 설정하지 않으면 frontend/와 같은 origin에서만 쓸 수 있다.
+fn cors_layer() -> CorsLayer {
+    let origins: Vec<header::HeaderValue> = std::env::var("KANBAN_ALLOWED_ORIGINS")
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|origin| !origin.is_empty())
+        .filter_map(|origin| match header::HeaderValue::from_str(origin) {
+            Ok(value) => Some(value),
+            Err(_) => {
+                eprintln!("Ignoring invalid origin in KANBAN_ALLOWED_ORIGINS: {:?}", origin);
+                None
+            }
+        })
+        .collect();
+    CorsLayer::new()
+        .allow_origin(AllowOrigin::list(origins))
+        .allow_methods([Method::GET, Method::POST, Method::PUT, Method::DELETE])
+        .allow_headers([
+            header::AUTHORIZATION,
+            header::CONTENT_TYPE,
+            header::IF_MATCH,
+            header::HeaderName::from_static("last-event-id"),
+        ])
+        .allow_credentials(true)
+}
 
-    let app = Router::new()
+fn app(state: AppState) -> Router {
+    Router::new()
         // Backlog: Jira 티켓 조회 (DB 저장 없음)
         .route("/api/backlog/query", post(query_backlog))
         // Tasks: kanban 일감 관리
         .route("/api/tasks", get(list_tasks).post(create_task))
         .route("/api/tasks/from-backlog", post(add_tasks_from_backlog))
+        .route("/api/tasks/tree", get(get_task_tree))
+        .route("/api/tasks/bulk", post(bulk_update_tasks))
+        .route("/api/tasks/export", get(export_tasks))
+        .route("/api/tasks/import", post(import_tasks))
         .route("/api/tasks/:id", put(update_task))
         .route("/api/tasks/:id", axum_delete(delete_task))
         .route("/api/tasks/:id/status", post(update_task_status))
+        .route("/api/tasks/:id/move", post(move_task))
+        .route("/api/tasks/:id/board", post(move_task_to_board))
         .route("/api/tasks/:id/comments", get(list_task_comments).post(add_task_comment))
+        .route("/api/tasks/:id/comments/:comment_id", put(update_task_comment).delete(delete_task_comment))
+        .route("/api/tasks/:id/comments/:comment_id/revisions", get(list_comment_revisions))
+        .route(
+            "/api/tasks/:id/attachments",
+            post(upload_attachments).layer(DefaultBodyLimit::max(MAX_UPLOAD_BODY_BYTES)),
+        )
+        .route("/api/attachments/:id", get(download_attachment))
+        .route("/api/mentions", get(list_mentions))
+        // 실시간 변경 알림 (SSE)
+        .route("/api/events", get(stream_events))
+        .route("/api/tasks/:id/history", get(list_task_history))
+        .route("/api/tasks/:id/transitions", get(list_task_transitions))
+        .route("/api/tasks/:id/links", get(list_task_links).post(add_task_link))
+        .route("/api/tasks/:id/links/:link_id", axum_delete(delete_task_link))
+        .route("/api/tasks/:id/blockers", get(list_task_blockers))
+        // Board: 상태/단계별 카드 수, WIP 한도
+        .route("/api/board/summary", get(get_board_summary))
+        .route("/api/board/wip-limits", get(list_wip_limits).put(set_wip_limit))
+        .route("/api/board/members", get(list_board_members))
+        .route("/api/board/backup", get(backup_board))
+        .route("/api/board/deleted-tasks", get(list_deleted_tasks))
+        .route("/api/board/members/:user_id", put(set_board_member).delete(remove_board_member))
+        // 저장된 view (기본 board, :id는 view의 board)
+        .route("/api/views", get(list_views).post(create_view))
+        .route("/api/views/:id", get(get_view).put(update_view).delete(delete_view))
+        .route("/api/views/:id/tasks", get(list_view_tasks))
+        // Boards: /api/board/*, /api/tasks 등 board가 없는 경로는 기본 board
+        .route("/api/boards", get(list_boards).post(create_board))
+        .route(
+            "/api/boards/restore",
+            post(restore_board).layer(DefaultBodyLimit::max(MAX_ARCHIVE_BODY_BYTES)),
+        )
+        .route("/api/boards/:board", get(get_board).put(update_board))
+        .route("/api/boards/:board/backup", get(backup_board))
+        .route("/api/boards/:board/deleted-tasks", get(list_deleted_tasks))
+        .route("/api/boards/:board/tasks", get(list_tasks).post(create_task))
+        .route("/api/boards/:board/tasks/tree", get(get_task_tree))
+        .route("/api/boards/:board/tasks/bulk", post(bulk_update_tasks))
+        .route("/api/boards/:board/tasks/export", get(export_tasks))
+        .route("/api/boards/:board/tasks/import", post(import_tasks))
+        .route("/api/boards/:board/tasks/from-backlog", post(add_tasks_from_backlog))
+        .route("/api/boards/:board/backlog/query", post(query_backlog))
+        .route("/api/boards/:board/summary", get(get_board_summary))
+        .route("/api/boards/:board/wip-limits", get(list_wip_limits).put(set_wip_limit))
+        .route("/api/boards/:board/members", get(list_board_members))
+        .route("/api/boards/:board/members/:user_id", put(set_board_member).delete(remove_board_member))
+        .route("/api/boards/:board/views", get(list_views).post(create_view))
+        .route("/api/boards/:board/schedule/analysis", get(get_schedule_analysis))
+        // Schedule: critical path / what-if 분석 (board별, 여기는 기본 board)
+        .route("/api/schedule/analysis", get(get_schedule_analysis))
+        // Holidays: 단계별 계획 완료일 계산용 휴일
+        .route("/api/holidays", get(list_holidays))
+        .route("/api/holidays/:date", put(put_holiday).delete(delete_holiday))
+        // Roll-up: 하위 task → 상위 task 상태/일정 반영 규칙
+        .route("/api/rollup/rules", get(list_rollup_rules))
+        .route("/api/rollup/rules/:rule", put(set_rollup_rule))
+        .route("/api/rollup/violations", get(list_rollup_violations))
+        // Workflows: 상태 전이 규칙
+        .route("/api/workflows", get(list_workflows))
+        .route(
+            "/api/workflows/:name/transitions",
+            get(list_workflow_transitions).put(replace_workflow_transitions),
+        )
+        // Auth / Users: 로그인한 사용자, 계정 관리 (admin)
+        .route("/api/auth/logout", post(logout))
+        .route("/api/auth/me", get(get_me))
+        .route("/api/auth/password", put(change_password))
+        .route("/api/users", get(list_users).post(create_user))
+        .route("/api/users/:id", put(update_user))
+        .route("/api/tokens", get(list_api_tokens).post(create_api_token))
+        .route("/api/tokens/:id", axum_delete(revoke_api_token))
+        // DB snapshot: 예약 실행 상태, 지금 만들기 (admin)
+        .route("/api/snapshots", get(get_snapshot_status).post(take_snapshot))
+        // 위의 /api route는 모두 로그인 필요 (cookie 또는 Authorization: Bearer)
+        .route_layer(middleware::from_fn_with_state(state.db.clone(), auth::require_auth))
+        .route("/api/auth/login", post(login))
         .nest_service("/", ServeDir::new("frontend"))
-        .layer(cors)
-        .with_state(state);
+        .layer(cors_layer())
+        .with_state(state)
+}
 
-    let addr = SocketAddr::from(([127, 0, 0, 1], 3000));
-    println!("Listening on http://{}", addr);
-    let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
-    axum::serve(listener, app).await.unwrap();
+// ─── Events ──────────────────────────────────────────────────────────────────
+
+/// board 변경 알림 stream (SSE). ?board=로 한 board만 구독.
+/// 재접속하면 EventSource가 보내는 Last-Event-ID (또는 ?last_event_id=) 이후부터 이어서 보낸다.
+async fn stream_events(
+    State(state): State<AppState>,
+    user: CurrentUser,
+    Query(query): Query<EventsQuery>,
+    headers: HeaderMap,
+) -> Result<Sse<impl futures_util::Stream<Item = Result<axum::response::sse::Event, std::convert::Infallible>>>, ApiError> {
+    user.require_scope(auth::SCOPE_READ)?;
+    let mut conn = state.db.acquire().await?;
+    // board를 지정하면 그 board만, 아니면 볼 수 있는 모든 board
+    let boards = match &query.board {
+        Some(key) => {
+            let board = boards::fetch_board(&mut conn, key).await?;
+            if auth::board_role(&mut conn, &user, &board.key).await?.is_none() {
+                return Err(ApiError::forbidden(format!("you are not a member of board {}", board.key))
+                    .with("board", &board.key));
+            }
+            Some(vec![board.key])
+        }
+        None => auth::viewable_boards(&mut conn, &user).await?,
+    };
+    drop(conn);
+    let last_event_id = headers
+        .get("last-event-id")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse().ok())
+        .or(query.last_event_id);
+    Ok(Sse::new(state.events.subscribe(boards, last_event_id)).keep_alive(KeepAlive::default()))
+}
+
+/// commit 후 task 변경 알림 (변경된 task를 같이 보냄, 삭제됐으면 null)
+async fn publish_task_event(state: &AppState, kind: &'static str, board: &str, task_id: i64, actor: &str) {
+    let task = sqlx::query_as::<_, Task>("SELECT * FROM tasks WHERE id = ?")
+        .bind(task_id)
+        .fetch_optional(&state.db)
+        .await
+        .unwrap_or_default();
+    state.events.publish(kind, board, Some(task_id), Some(actor), serde_json::json!({ "task": task }));
+}
+
+/// roll-up 규칙이 바꾼 상위 task (rollup::apply_from의 결과)도 commit 후 알린다
+async fn publish_rollup_events(state: &AppState, ids: &[i64]) {
+    let mut seen = std::collections::HashSet::new();
+    for &id in ids {
+        if !seen.insert(id) {
+            continue;
+        }
+        let board: Option<(String,)> = sqlx::query_as(
+            "SELECT boards.key FROM boards JOIN tasks ON tasks.board_id = boards.id WHERE tasks.id = ?"
+        )
+        .bind(id)
+        .fetch_optional(&state.db)
+        .await
+        .unwrap_or_default();
+        if let Some((board,)) = board {
+            publish_task_event(state, events::TASK_UPDATED, &board, id, "rollup").await;
+        }
+    }
+}
+
+// ─── Auth / Users ────────────────────────────────────────────────────────────
+
+async fn login(
+    State(state): State<AppState>,
+    Json(req): Json<LoginRequest>,
+) -> Result<Response, ApiError> {
+    let user = sqlx::query_as::<_, User>("SELECT * FROM users WHERE username = ?")
+        .bind(req.username.trim())
+        .fetch_optional(&state.db)
+        .await?
+        .filter(|u| !u.disabled && auth::verify_password(&req.password, &u.password_hash))
+        .ok_or_else(|| ApiError::new(
+            axum::http::StatusCode::UNAUTHORIZED,
+            "invalid_credentials",
+            "username or password is incorrect",
+        ))?;
+
+    let (token, expires_at) = auth::create_session(&mut *state.db.acquire().await?, user.id).await?;
+    Ok((
+        [(header::SET_COOKIE, auth::session_cookie(&token, expires_at))],
+        Json(LoginResponse { token, expires_at, user }),
+    )
+        .into_response())
+}
+
+async fn logout(State(state): State<AppState>, headers: HeaderMap) -> Result<Response, ApiError> {
+    if let Some(token) = auth::token_from_headers(&headers) {
+        auth::delete_session(&mut *state.db.acquire().await?, &token).await?;
+    }
+    Ok(([(header::SET_COOKIE, auth::clear_session_cookie())], Json(true)).into_response())
+}
+
+async fn get_me(State(state): State<AppState>, user: CurrentUser) -> Result<Json<Me>, ApiError> {
+    let mut conn = state.db.acquire().await?;
+    // 기본 board는 누구나 볼 수 있으므로 역할이 항상 있다
+    let board_role = auth::board_role(&mut conn, &user, auth::DEFAULT_BOARD).await?.unwrap_or(auth::BoardRole::Viewer);
+    Ok(Json(Me {
+        user: auth::fetch_user(&mut conn, user.id).await?,
+        board_role: board_role.as_str().to_string(),
+    }))
+}
+
+/// 본인 비밀번호 변경. 다른 세션은 모두 끊는다.
+async fn change_password(
+    State(state): State<AppState>,
+    user: CurrentUser,
+    headers: HeaderMap,
+    Json(req): Json<ChangePasswordRequest>,
+) -> Result<Json<bool>, ApiError> {
+    user.require_session()?;
+    let mut tx = state.db.begin().await?;
+    let current = auth::fetch_user(&mut tx, user.id).await?;
+    if !auth::verify_password(&req.current_password, &current.password_hash) {
+        return Err(ApiError::forbidden("current password is incorrect"));
+    }
+    sqlx::query("UPDATE users SET password_hash = ? WHERE id = ?")
+        .bind(auth::hash_password(&req.new_password)?)
+        .bind(user.id)
+        .execute(&mut *tx)
+        .await?;
+    let keep = auth::token_from_headers(&headers).map(|t| auth::token_hash(&t));
+    sqlx::query("DELETE FROM sessions WHERE user_id = ? AND token_hash IS NOT ?")
+        .bind(user.id)
+        .bind(keep)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+    Ok(Json(true))
+}
+
+/// 계정 목록 (mention 대상 등, 로그인한 사용자 누구나)
+async fn list_users(State(state): State<AppState>) -> Json<Vec<User>> {
+    let users = sqlx::query_as::<_, User>("SELECT * FROM users ORDER BY username")
+        .fetch_all(&state.db)
+        .await
+        .unwrap_or_default();
+    Json(users)
+}
+
+async fn create_user(
+    State(state): State<AppState>,
+    user: CurrentUser,
+    Json(req): Json<CreateUserRequest>,
+) -> Result<Json<User>, ApiError> {
+    user.require_admin()?;
+    let role = req.role.as_deref().unwrap_or(auth::MEMBER_ROLE);
+    auth::validate_user_role(role)?;
+    let username = req.username.trim();
+    if username.is_empty() || !username.chars().all(|c| c.is_alphanumeric() || matches!(c, '_' | '.' | '-')) {
+        return Err(ApiError::bad_request(
+            "username must be non-empty and contain only letters, digits, '_', '.' or '-'",
+        ));
+    }
+    let created = sqlx::query_as::<_, User>(
+        "INSERT INTO users (username, display_name, password_hash, role) VALUES (?, ?, ?, ?) RETURNING *"
+    )
+    .bind(username)
+    .bind(&req.display_name)
+    .bind(auth::hash_password(&req.password)?)
+    .bind(role)
+    .fetch_one(&state.db)
+    .await
+    .map_err(|e| match e {
+        sqlx::Error::Database(ref db) if db.is_unique_violation() => {
+            ApiError::conflict("duplicate_username", format!("user {} already exists", username))
+        }
+        e => e.into(),
+    })?;
+    Ok(Json(created))
+}
+
+/// 계정 수정 (admin). 비활성화하거나 비밀번호를 재설정하면 그 사용자의 세션을 끊는다.
+async fn update_user(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+    user: CurrentUser,
+    Json(req): Json<UpdateUserRequest>,
+) -> Result<Json<User>, ApiError> {
+    user.require_admin()?;
+    if let Some(role) = &req.role {
+        auth::validate_user_role(role)?;
+    }
+    if id == user.id && (req.disabled == Some(true) || req.role.as_deref().is_some_and(|r| r != auth::ADMIN_ROLE)) {
+        return Err(ApiError::bad_request("admins cannot disable or demote themselves"));
+    }
+    let password_hash = req.password.as_deref().map(auth::hash_password).transpose()?;
+
+    let mut tx = state.db.begin().await?;
+    auth::fetch_user(&mut tx, id).await?;
+    let updated = sqlx::query_as::<_, User>(
+        "UPDATE users SET
+            display_name  = COALESCE(?, display_name),
+            role          = COALESCE(?, role),
+            disabled      = COALESCE(?, disabled),
+            password_hash = COALESCE(?, password_hash)
+         WHERE id = ? RETURNING *"
+    )
+    .bind(&req.display_name)
+    .bind(&req.role)
+    .bind(req.disabled)
+    .bind(&password_hash)
+    .bind(id)
+    .fetch_one(&mut *tx)
+    .await?;
+    if req.disabled == Some(true) || password_hash.is_some() {
+        sqlx::query("DELETE FROM sessions WHERE user_id = ?")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+    }
+    tx.commit().await?;
+    Ok(Json(updated))
+}
+
+/// 내 API 토큰 목록 (폐기된 것 포함, 최근 사용 시각 표시용)
+async fn list_api_tokens(State(state): State<AppState>, user: CurrentUser) -> Result<Json<Vec<ApiToken>>, ApiError> {
+    user.require_session()?;
+    let tokens = sqlx::query_as::<_, ApiToken>(
+        "SELECT * FROM api_tokens WHERE user_id = ? ORDER BY revoked_at IS NOT NULL, created_at DESC, id DESC"
+    )
+    .bind(user.id)
+    .fetch_all(&state.db)
+    .await?;
+    Ok(Json(tokens))
+}
+
+async fn create_api_token(
+    State(state): State<AppState>,
+    user: CurrentUser,
+    Json(req): Json<CreateApiTokenRequest>,
+) -> Result<Json<CreatedApiToken>, ApiError> {
+    user.require_session()?;
+    let name = req.name.trim();
+    if name.is_empty() {
+        return Err(ApiError::bad_request("token name must not be empty"));
+    }
+    let scopes = auth::normalize_scopes(&req.scopes)?;
+    let expires_at = match req.expires_in_days {
+        Some(days) if days <= 0 => return Err(ApiError::bad_request("expires_in_days must be positive")),
+        Some(days) => Some(Utc::now() + chrono::Duration::days(days)),
+        None => None,
+    };
+
+    let (token, api_token) =
+        auth::create_api_token(&mut *state.db.acquire().await?, user.id, name, &scopes, expires_at).await?;
+    Ok(Json(CreatedApiToken { token, api_token }))
+}
+
+/// 토큰 폐기. 본인 토큰만 (admin은 모든 토큰).
+async fn revoke_api_token(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+    user: CurrentUser,
+) -> Result<Json<bool>, ApiError> {
+    user.require_session()?;
+    let result = sqlx::query(
+        "UPDATE api_tokens SET revoked_at = ?
+          WHERE id = ? AND revoked_at IS NULL AND (user_id = ? OR ?)"
+    )
+    .bind(Utc::now())
+    .bind(id)
+    .bind(user.id)
+    .bind(user.is_admin())
+    .execute(&state.db)
+    .await?;
+    if result.rows_affected() == 0 {
+        return Err(ApiError::not_found(format!("active API token {} not found", id)));
+    }
+    Ok(Json(true))
+}
+
+// ─── Boards ──────────────────────────────────────────────────────────────────
+
+/// board 목록과 각 board에서의 내 역할
+async fn list_boards(State(state): State<AppState>, user: CurrentUser) -> Result<Json<Vec<BoardWithRole>>, ApiError> {
+    let mut conn = state.db.acquire().await?;
+    let boards = sqlx::query_as::<_, Board>("SELECT * FROM boards ORDER BY id")
+        .fetch_all(&mut *conn)
+        .await?;
+    let mut result = Vec::with_capacity(boards.len());
+    for board in boards {
+        // 접근할 수 없는 board는 목록에서도 뺀다
+        if let Some(role) = auth::board_role(&mut conn, &user, &board.key).await? {
+            result.push(BoardWithRole { board, role: role.as_str().to_string() });
+        }
+    }
+    Ok(Json(result))
+}
+
+async fn get_board(BoardAccess { board, role, .. }: BoardViewer) -> Json<BoardWithRole> {
+    Json(BoardWithRole { board, role: role.as_str().to_string() })
+}
+
+/// board 생성 (admin)
+async fn create_board(
+    State(state): State<AppState>,
+    user: CurrentUser,
+    Json(req): Json<CreateBoardRequest>,
+) -> Result<Json<Board>, ApiError> {
+    user.require_admin()?;
+    boards::validate_key(&req.key)?;
+    let name = req.name.trim();
+    if name.is_empty() {
+        return Err(ApiError::bad_request("board name must not be empty"));
+    }
+    let workflow = req.workflow.as_deref().unwrap_or(workflow::DEFAULT_WORKFLOW);
+
+    let mut conn = state.db.acquire().await?;
+    boards::validate_workflow(&mut conn, workflow).await?;
+    let board = sqlx::query_as::<_, Board>(
+        "INSERT INTO boards (key, name, jira_project, workflow) VALUES (?, ?, ?, ?) RETURNING *"
+    )
+    .bind(&req.key)
+    .bind(name)
+    .bind(&req.jira_project)
+    .bind(workflow)
+    .fetch_one(&mut *conn)
+    .await
+    .map_err(|e| match e {
+        sqlx::Error::Database(ref db) if db.is_unique_violation() => {
+            ApiError::conflict("duplicate_board", format!("board {} already exists", req.key))
+        }
+        e => e.into(),
+    })?;
+    Ok(Json(board))
+}
+
+/// board 이름, 기본 Jira 프로젝트, workflow 변경
+async fn update_board(
+    State(state): State<AppState>,
+    BoardAccess { user, board, .. }: BoardAdmin,
+    Json(req): Json<UpdateBoardRequest>,
+) -> Result<Json<Board>, ApiError> {
+    let name = req.name.as_deref().map(str::trim);
+    if name.is_some_and(str::is_empty) {
+        return Err(ApiError::bad_request("board name must not be empty"));
+    }
+    let mut conn = state.db.acquire().await?;
+    if let Some(workflow) = req.workflow.as_deref() {
+        boards::validate_workflow(&mut conn, workflow).await?;
+    }
+    let updated = sqlx::query_as::<_, Board>(
+        "UPDATE boards SET
+            name         = COALESCE(?, name),
+            jira_project = COALESCE(?, jira_project),
+            workflow     = COALESCE(?, workflow)
+         WHERE id = ? RETURNING *"
+    )
+    .bind(name)
+    .bind(&req.jira_project)
+    .bind(&req.workflow)
+    .bind(board.id)
+    .fetch_one(&mut *conn)
+    .await?;
+    state.events.publish(events::BOARD_UPDATED, &board.key, None, Some(&user.username), serde_json::json!({ "board": updated }));
+    Ok(Json(updated))
+}
+
+// ─── Backup ──────────────────────────────────────────────────────────────────
+
+// archive에는 첨부 파일 내용이 없으므로 task 수만큼 커진다
+const MAX_ARCHIVE_BODY_BYTES: usize = 64 * 1024 * 1024;
+
+/// board 전체를 JSON archive로 내려받는다 (board admin)
+async fn backup_board(
+    State(state): State<AppState>,
+    BoardAccess { board, .. }: BoardAdmin,
+) -> Result<Response, ApiError> {
+    let mut tx = state.db.begin().await?;
+    let archive = backup::export_board(&mut tx, &board).await?;
+    tx.commit().await?;
+
+    let filename = format!("board-{}-{}.json", board.key, Utc::now().date_naive());
+    Ok((
+        [(header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}\"", filename))],
+        Json(archive),
+    )
+        .into_response())
+}
+
+/// archive를 새 board로 복원 (admin). id는 모두 새로 매기고 결과에 옛 id → 새 id를 돌려준다.
+async fn restore_board(
+    State(state): State<AppState>,
+    user: CurrentUser,
+    Query(query): Query<RestoreQuery>,
+    Json(archive): Json<backup::BoardArchive>,
+) -> Result<Json<backup::RestoreReport>, ApiError> {
+    user.require_admin()?;
+    let mut tx = state.db.begin().await?;
+    let report = backup::restore_board(&mut tx, &archive, query.board.as_deref()).await?;
+    tx.commit().await?;
+    state.events.publish(
+        events::BOARD_UPDATED,
+        &report.board.key,
+        None,
+        Some(&user.username),
+        serde_json::json!({ "board": report.board }),
+    );
+    Ok(Json(report))
+}
+
+/// snapshot 설정, 마지막으로 만든 snapshot (시각, 크기), 남아 있는 파일 목록
+async fn get_snapshot_status(
+    State(state): State<AppState>,
+    user: CurrentUser,
+) -> Result<Json<snapshot::SnapshotStatus>, ApiError> {
+    user.require_admin()?;
+    Ok(Json(state.snapshots.status().await))
+}
+
+/// 지금 DB snapshot을 만든다. 예약 실행 중이면 끝날 때까지 기다린다.
+async fn take_snapshot(
+    State(state): State<AppState>,
+    user: CurrentUser,
+) -> Result<Json<snapshot::LastSnapshot>, ApiError> {
+    user.require_admin()?;
+    let snapshot = state.snapshots.take(&state.db).await.map_err(|e| {
+        eprintln!("Database snapshot failed: {}", e);
+        ApiError::new(axum::http::StatusCode::INTERNAL_SERVER_ERROR, "snapshot_failed", e)
+    })?;
+    Ok(Json(snapshot))
+}
+
+// ─── Board Members ───────────────────────────────────────────────────────────
+
+/// board_members에 등록된 사용자 (등록되지 않은 사용자는 viewer)
+async fn list_board_members(
+    State(state): State<AppState>,
+    BoardAccess { board, .. }: BoardViewer,
+) -> Json<Vec<BoardMembership>> {
+    let members = sqlx::query_as::<_, BoardMembership>(
+        "SELECT m.board, m.user_id, u.username, u.display_name, m.role
+           FROM board_members m JOIN users u ON u.id = m.user_id
+          WHERE m.board = ?
+          ORDER BY u.username"
+    )
+    .bind(&board.key)
+    .fetch_all(&state.db)
+    .await
+    .unwrap_or_default();
+    Json(members)
+}
+
+async fn set_board_member(
+    State(state): State<AppState>,
+    Path(MemberPath { user_id }): Path<MemberPath>,
+    access: BoardAdmin,
+    Json(req): Json<SetBoardRoleRequest>,
+) -> Result<Json<bool>, ApiError> {
+    let Some(role) = auth::BoardRole::parse(&req.role) else {
+        return Err(ApiError::bad_request(format!(
+            "unknown role {}; expected one of viewer, member, admin",
+            req.role
+        )));
+    };
+    let mut conn = state.db.acquire().await?;
+    auth::fetch_user(&mut conn, user_id).await?;
+    sqlx::query(
+        "INSERT INTO board_members (board, user_id, role) VALUES (?, ?, ?)
+         ON CONFLICT(board, user_id) DO UPDATE SET role = excluded.role"
+    )
+    .bind(&access.board.key)
+    .bind(user_id)
+    .bind(role.as_str())
+    .execute(&mut *conn)
+    .await?;
+    Ok(Json(true))
+}
+
+async fn remove_board_member(
+    State(state): State<AppState>,
+    Path(MemberPath { user_id }): Path<MemberPath>,
+    access: BoardAdmin,
+) -> Result<Json<bool>, ApiError> {
+    let result = sqlx::query("DELETE FROM board_members WHERE board = ? AND user_id = ?")
+        .bind(&access.board.key)
+        .bind(user_id)
+        .execute(&state.db)
+        .await?;
+    Ok(Json(result.rows_affected() > 0))
+}
+
+// ─── Saved Views ─────────────────────────────────────────────────────────────
+
+/// board의 저장된 view 중 내 것과 공유된 것
+async fn list_views(
+    State(state): State<AppState>,
+    BoardAccess { user, board, .. }: BoardViewer,
+) -> Result<Json<Vec<SavedView>>, ApiError> {
+    Ok(Json(views::list_views(&mut *state.db.acquire().await?, &board, &user).await?))
+}
+
+async fn create_view(
+    State(state): State<AppState>,
+    BoardAccess { user, role, board }: BoardViewer,
+    Json(req): Json<CreateViewRequest>,
+) -> Result<Json<SavedView>, ApiError> {
+    let name = views::validate_name(&req.name)?;
+    views::check_share(req.shared, role, &board)?;
+    let query = views::normalize_query(req.query)?;
+
+    let mut conn = state.db.acquire().await?;
+    let result = sqlx::query("INSERT INTO saved_views (board_id, owner_id, name, shared, query) VALUES (?, ?, ?, ?, ?)")
+        .bind(board.id)
+        .bind(user.id)
+        .bind(&name)
+        .bind(req.shared)
+        .bind(sqlx::types::Json(&query))
+        .execute(&mut *conn)
+        .await?;
+    Ok(Json(views::fetch_view(&mut conn, result.last_insert_rowid(), &user).await?))
+}
+
+async fn get_view(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+    BoardAccess { user, .. }: BoardViewer,
+) -> Result<Json<SavedView>, ApiError> {
+    Ok(Json(views::fetch_view(&mut *state.db.acquire().await?, id, &user).await?))
+}
+
+/// 이름/공유 여부/조건 변경. 본인 view, 또는 공유 view면 board admin.
+async fn update_view(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+    BoardAccess { user, role, board }: BoardViewer,
+    Json(req): Json<UpdateViewRequest>,
+) -> Result<Json<SavedView>, ApiError> {
+    let mut conn = state.db.acquire().await?;
+    let view = views::fetch_view(&mut conn, id, &user).await?;
+    views::check_editable(&view, &user, role)?;
+    let name = req.name.as_deref().map(views::validate_name).transpose()?;
+    if let Some(shared) = req.shared {
+        views::check_share(shared, role, &board)?;
+    }
+    let query = req.query.map(views::normalize_query).transpose()?;
+
+    sqlx::query(
+        "UPDATE saved_views SET
+            name       = COALESCE(?, name),
+            shared     = COALESCE(?, shared),
+            query      = COALESCE(?, query),
+            updated_at = ?
+         WHERE id = ?"
+    )
+    .bind(name)
+    .bind(req.shared)
+    .bind(query.as_ref().map(sqlx::types::Json))
+    .bind(Utc::now())
+    .bind(id)
+    .execute(&mut *conn)
+    .await?;
+    Ok(Json(views::fetch_view(&mut conn, id, &user).await?))
+}
+
+async fn delete_view(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+    BoardAccess { user, role, .. }: BoardViewer,
+) -> Result<Json<bool>, ApiError> {
+    let mut conn = state.db.acquire().await?;
+    let view = views::fetch_view(&mut conn, id, &user).await?;
+    views::check_editable(&view, &user, role)?;
+    let result = sqlx::query("DELETE FROM saved_views WHERE id = ?")
+        .bind(id)
+        .execute(&mut *conn)
+        .await?;
+    Ok(Json(result.rows_affected() > 0))
+}
+
+/// view의 조건으로 task 목록 조회 (GET /api/tasks와 같은 응답). limit/cursor만 바꿀 수 있다.
+async fn list_view_tasks(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+    BoardAccess { user, board, .. }: BoardViewer,
+    Query(page): Query<ViewPageQuery>,
+) -> Result<Response, ApiError> {
+    let view = views::fetch_view(&mut *state.db.acquire().await?, id, &user).await?;
+    let mut query = views::resolve_query(&view, &user);
+    if page.limit.is_some() {
+        query.page.limit = page.limit;
+    }
+    query.page.cursor = page.cursor;
+    task_list_response(&state, &user, &board, &query).await
 }
 
 // ─── Backlog ─────────────────────────────────────────────────────────────────
 
-/// Jira 티켓 조회. DB에 저장하지 않음.
+/// Jira 티켓 조회. DB에 저장하지 않음. project가 없으면 board의 기본 Jira 프로젝트.
 async fn query_backlog(
     State(state): State<AppState>,
-    Json(query): Json<JiraQuery>,
+    BoardAccess { board, .. }: BoardViewer,
+    Json(mut query): Json<JiraQuery>,
 ) -> Json<Vec<JiraTicket>> {
+    if query.project.is_none() {
+        query.project = board.jira_project;
+    }
     let tickets = state.jira.query_tickets(query).await;
     Json(tickets)
 }
 
 // ─── Tasks ───────────────────────────────────────────────────────────────────
 
-/// 모든 Task 목록 조회 (트리 구조를 위해 flat list 반환, 프론트에서 재구성)
-async fn list_tasks(State(state): State<AppState>) -> Json<Vec<Task>> {
-    let tasks = sqlx::query_as::<_, Task>(
-        "SELECT * FROM tasks ORDER BY parent_task_id NULLS FIRST, id ASC"
+/// board의 Task 목록 조회 (트리 구조를 위해 flat list 반환, 프론트에서 재구성)
+/// 필터/정렬/limit을 줄 수 있고, 다음 페이지가 있으면 X-Next-Cursor 헤더로 cursor를 알려 준다.
+async fn list_tasks(
+    State(state): State<AppState>,
+    BoardAccess { user, board, .. }: BoardViewer,
+    Query(filter): Query<TaskFilter>,
+    Query(page): Query<TaskPageQuery>,
+) -> Result<Response, ApiError> {
+    task_list_response(&state, &user, &board, &TaskListQuery { filter, page }).await
+}
+
+/// task 목록 한 페이지 + 진행도/Markdown 계산 (목록 API와 저장된 view 실행이 같이 쓴다)
+async fn task_list_response(
+    state: &AppState,
+    user: &CurrentUser,
+    board: &Board,
+    query: &TaskListQuery,
+) -> Result<Response, ApiError> {
+    let page = task_query::list(&mut *state.db.acquire().await?, board.id, query).await?;
+    let mut tasks = page.tasks;
+    if let Err(e) = schedule::annotate(&state.db, &mut tasks).await {
+        eprintln!("Error computing task progress: {}", e);
+    }
+    if let Err(e) = markdown::annotate_tasks(&state.db, user, &mut tasks).await {
+        eprintln!("Error rendering task descriptions: {}", e);
+    }
+    let mut response = Json(tasks).into_response();
+    if let Some(cursor) = page.next_cursor {
+        response.headers_mut().insert("x-next-cursor", cursor.into());
+    }
+    Ok(response)
+}
+
+/// 목록 API와 같은 필터/정렬로 task 표를 CSV 또는 Excel 파일로 내려받는다.
+/// 트리 순서로 펼쳐 제목을 들여쓰고, 진행도/단계별 계획일/Jira 링크를 같이 넣는다.
+async fn export_tasks(
+    State(state): State<AppState>,
+    BoardAccess { board, .. }: BoardViewer,
+    Query(filter): Query<TaskFilter>,
+    Query(mut page): Query<TaskPageQuery>,
+    Query(export): Query<ExportQuery>,
+) -> Result<Response, ApiError> {
+    let format = export.format.as_deref().unwrap_or("csv");
+    if format != "csv" && format != "xlsx" {
+        return Err(ApiError::bad_request(format!("unknown export format: {} (csv or xlsx)", format)));
+    }
+    page.limit = None;
+    page.cursor = None;
+    let query = TaskListQuery { filter, page };
+    let mut tasks = task_query::list(&mut *state.db.acquire().await?, board.id, &query).await?.tasks;
+    schedule::annotate(&state.db, &mut tasks).await?;
+
+    let rows = export::tree_rows(tasks);
+    let (body, content_type) = if format == "xlsx" {
+        (export::to_xlsx(&rows, &board.key)?, export::XLSX_CONTENT_TYPE)
+    } else {
+        (export::to_csv(&rows)?, export::CSV_CONTENT_TYPE)
+    };
+    let filename = format!("tasks-{}-{}.{}", board.key, Utc::now().date_naive(), format);
+    Ok((
+        [
+            (header::CONTENT_TYPE, content_type.to_string()),
+            (header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}\"", filename)),
+        ],
+        body,
     )
-    .fetch_all(&state.db)
-    .await
-    .unwrap_or_default();
-    Json(tasks)
+        .into_response())
+}
+
+/// Task 트리 조회. root가 없으면 최상위 task 전체, max_depth로 깊이 제한 (root = 0)
+async fn get_task_tree(
+    State(state): State<AppState>,
+    BoardAccess { user, board, .. }: BoardViewer,
+    Query(query): Query<TaskTreeQuery>,
+) -> Result<Json<Vec<TaskNode>>, ApiError> {
+    if let Some(root) = query.root
+        && fetch_task(&mut *state.db.acquire().await?, root).await?.board_id != board.id
+    {
+        return Err(ApiError::not_found(format!("task {} not found on board {}", root, board.key)));
+    }
+    Ok(Json(tree::load_tree(&state.db, &user, board.id, query.root, query.max_depth).await?))
+}
+
+/// Task(와 subtree)의 상위 task 변경 / 형제 사이 순서 변경
+async fn move_task(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+    BoardAccess { user, board, .. }: BoardMember,
+    Json(req): Json<MoveTaskRequest>,
+) -> Result<Json<Vec<TaskNode>>, ApiError> {
+    let mut tx = state.db.begin().await?;
+    let task = fetch_task(&mut tx, id).await?;
+    tree::move_task(&mut tx, &task, req.parent_task_id, req.position).await?;
+    let mut rolled_up = Vec::new();
+    if let Some(old_parent) = task.parent_task_id {
+        rolled_up.extend(rollup::apply_from(&mut tx, old_parent).await?);
+    }
+    rolled_up.extend(rollup::apply_from(&mut tx, id).await?);
+    tx.commit().await?;
+
+    publish_task_event(&state, events::TASK_MOVED, &board.key, id, &user.username).await;
+    publish_rollup_events(&state, &rolled_up).await;
+    Ok(Json(tree::load_tree(&state.db, &user, task.board_id, Some(id), None).await?))
+}
+
+/// Task와 subtree 전체를 다른 board로 이동. 양쪽 board 모두 member 이상이어야 한다.
+/// 원래 board의 상위 task에서는 떨어져 새 board의 최상위 task가 된다.
+async fn move_task_to_board(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+    BoardAccess { user, board: from, .. }: BoardMember,
+    Json(req): Json<MoveToBoardRequest>,
+) -> Result<Json<Vec<TaskNode>>, ApiError> {
+    let mut tx = state.db.begin().await?;
+    let to = boards::fetch_board(&mut tx, &req.board).await?;
+    if to.id == from.id {
+        return Err(ApiError::bad_request(format!("task {} is already on board {}", id, to.key)));
+    }
+    let role = auth::board_role(&mut tx, &user, &to.key).await?;
+    if role < Some(auth::BoardRole::Member) {
+        return Err(ApiError::forbidden(format!(
+            "moving a task to board {} requires the member role there",
+            to.key
+        ))
+        .with("board", &to.key)
+        .with("role", role)
+        .with("required", auth::BoardRole::Member));
+    }
+
+    let task = fetch_task(&mut tx, id).await?;
+    let boards::MovedTasks { tasks: moved, dropped_links } = boards::move_to_board(&mut tx, id, &to).await?;
+    // 상위 task에서 떼어 냈으므로 물려받던 due가 바뀐다
+    schedule::refresh_stage_deadlines(&mut tx, id).await?;
+    // 옮겨 온 task는 target board에서 새로 만든 task(Pending)가 지금 상태로 가는 것으로 보고
+    // target의 workflow와 WIP 한도를 검사한다 (함께 옮긴 task도 세므로 옮긴 뒤에 검사)
+    for &moved_id in &moved {
+        let arrived = fetch_task(&mut tx, moved_id).await?;
+        let before = Task { status: "Pending".to_string(), stage: None, ..arrived.clone() };
+        workflow::check_transition(&mut tx, &to.workflow, &before, &arrived, None, role).await?;
+        let violations = workflow::check_wip(&mut tx, &before, &arrived.status, arrived.stage.as_deref()).await?;
+        if let Some(violation) = violations.first() {
+            return Err(violation.clone().into_error().with("task_id", moved_id).with("violations", &violations));
+        }
+    }
+    let detail = serde_json::json!({
+        "from": from.key,
+        "to": to.key,
+        "old_parent_task_id": task.parent_task_id,
+        "tasks": moved,
+        "dropped_links": dropped_links,
+    });
+    sqlx::query("INSERT INTO task_history (task_id, action, detail, actor) VALUES (?, 'board_move', ?, ?)")
+        .bind(id)
+        .bind(detail.to_string())
+        .bind(&user.username)
+        .execute(&mut *tx)
+        .await?;
+    let rolled_up = match task.parent_task_id {
+        Some(old_parent) => rollup::apply_from(&mut tx, old_parent).await?,
+        None => Vec::new(),
+    };
+    tx.commit().await?;
+
+    // 양쪽 board 모두에 알린다
+    for board in [&from, &to] {
+        state.events.publish(events::TASK_MOVED, &board.key, Some(id), Some(&user.username), detail.clone());
+    }
+    publish_rollup_events(&state, &rolled_up).await;
+    Ok(Json(tree::load_tree(&state.db, &user, to.id, Some(id), None).await?))
 }
 
-/// 새 Task 생성 (수동)
+/// 새 Task 생성 (수동). 상위 task는 같은 board에 있어야 한다.
 async fn create_task(
     State(state): State<AppState>,
+    BoardAccess { user, board, .. }: BoardMember,
     Json(req): Json<CreateTaskRequest>,
-) -> Json<Option<Task>> {
-    let result = sqlx::query(
-        "INSERT INTO tasks (jira_ticket_key, title, description, status, assignee, project_key,
+) -> Result<Json<Task>, ApiError> {
+    if let Some(parent) = req.parent_task_id {
+        boards::check_parent_board(&mut *state.db.acquire().await?, &board, parent).await?;
+    }
+    let (mut task, rolled_up) = insert_task(&state.db, &board, &req).await?;
+    state.events.publish(events::TASK_CREATED, &board.key, Some(task.id), Some(&user.username), serde_json::json!({ "task": task }));
+    publish_rollup_events(&state, &rolled_up).await;
+    // 참조 링크는 만든 사용자가 볼 수 있는 board 기준이므로 알림에는 넣지 않는다
+    markdown::annotate_tasks(&state.db, &user, std::slice::from_mut(&mut task)).await?;
+    Ok(Json(task))
+}
+
+/// Task를 추가하고 단계별 계획 완료일, 상위 task roll-up을 같은 transaction에서 반영.
+/// roll-up으로 바뀐 상위 task id를 같이 돌려준다.
+async fn insert_task(db: &SqlitePool, board: &Board, req: &CreateTaskRequest) -> Result<(Task, Vec<i64>), sqlx::Error> {
+    let mut tx = db.begin().await?;
+    let id = insert_task_row(&mut tx, board, req).await?;
+    let rolled_up = match req.parent_task_id {
+        Some(parent) => rollup::apply_from(&mut tx, parent).await?,
+        None => Vec::new(),
+    };
+    tx.commit().await?;
+
+    let mut task = sqlx::query_as::<_, Task>("SELECT * FROM tasks WHERE id = ?")
+        .bind(id)
+        .fetch_one(db)
+        .await?;
+    schedule::annotate(db, std::slice::from_mut(&mut task)).await?;
+    Ok((task, rolled_up))
+}
+
+/// INSERT + 단계별 계획 완료일 (roll-up은 호출한 쪽에서)
+async fn insert_task_row(
+    conn: &mut sqlx::SqliteConnection,
+    board: &Board,
+    req: &CreateTaskRequest,
+) -> Result<i64, sqlx::Error> {
+    let id = sqlx::query(
+        "INSERT INTO tasks (board_id, jira_ticket_key, title, description, status, assignee, project_key,
                             parent_task_id, alias, start_date, due_date, jira_url)
-         VALUES (?, ?, ?, 'Pending', ?, ?, ?, ?, ?, ?, ?)"
+         VALUES (?, ?, ?, ?, 'Pending', ?, ?, ?, ?, ?, ?, ?)"
     )
+    .bind(board.id)
     .bind(&req.jira_ticket_key)
     .bind(&req.title)
     .bind(&req.description)
@@ -103,34 +1071,77 @@ async fn create_task(
     .bind(req.start_date.map(|d| d.to_string()))
     .bind(req.due_date.map(|d| d.to_string()))
     .bind(&req.jira_url)
-    .execute(&state.db)
-    .await;
+    .execute(&mut *conn)
+    .await?
+    .last_insert_rowid();
 
-    match result {
-        Ok(r) => {
-            let task = sqlx::query_as::<_, Task>("SELECT * FROM tasks WHERE id = ?")
-                .bind(r.last_insert_rowid())
-                .fetch_optional(&state.db)
-                .await
-                .unwrap_or(None);
-            Json(task)
-        }
-        Err(e) => {
-            eprintln!("Error creating task: {}", e);
-            Json(None)
+    schedule::refresh_stage_deadlines(&mut *conn, id).await?;
+    Ok(id)
+}
+
+/// CSV로 task 일괄 추가 (열 구성은 import 모듈 참고). body는 CSV 텍스트.
+/// 전부 검사한 뒤 오류가 없을 때만 한 transaction으로 추가하고, dry_run이면 결과 트리만 돌려준다.
+async fn import_tasks(
+    State(state): State<AppState>,
+    BoardAccess { user, board, .. }: BoardMember,
+    Query(query): Query<ImportQuery>,
+    body: String,
+) -> Result<Json<ImportResult>, ApiError> {
+    let mut tx = state.db.begin().await?;
+    let mut plan = import::plan(&mut tx, &board, &body).await?;
+    if query.dry_run {
+        return Ok(Json(plan.result(true, &[])));
+    }
+    if !plan.errors.is_empty() {
+        return Err(ApiError::bad_request(format!(
+            "{} error(s) in the CSV; nothing was imported",
+            plan.errors.len()
+        ))
+        .with("errors", &plan.errors));
+    }
+
+    let mut ids: Vec<Option<i64>> = vec![None; plan.rows.len()];
+    let mut created = Vec::new();
+    for i in plan.order() {
+        let row = &mut plan.rows[i];
+        if let Some(existing) = row.existing {
+            ids[i] = Some(existing);
+            continue;
         }
+        row.req.parent_task_id = match row.parent {
+            Some(import::Parent::Row(p)) => ids[p],
+            Some(import::Parent::Task(id)) => Some(id),
+            None => None,
+        };
+        let id = insert_task_row(&mut tx, &board, &row.req).await?;
+        ids[i] = Some(id);
+        created.push(id);
     }
+    // 하위 task부터 상위 task roll-up
+    let mut rolled_up = Vec::new();
+    for &id in created.iter().rev() {
+        rolled_up.extend(rollup::apply_from(&mut tx, id).await?);
+    }
+    tx.commit().await?;
+
+    for &id in &created {
+        publish_task_event(&state, events::TASK_CREATED, &board.key, id, &user.username).await;
+    }
+    rolled_up.retain(|id| !created.contains(id));
+    publish_rollup_events(&state, &rolled_up).await;
+    Ok(Json(plan.result(false, &ids)))
 }
 
 /// Backlog에서 선택한 Jira 티켓들을 Task로 변환하여 추가
 /// include_subtasks=true이면 subtask도 함께 추가
 async fn add_tasks_from_backlog(
     State(state): State<AppState>,
+    BoardAccess { user, board, .. }: BoardMember,
     Json(req): Json<AddToKanbanRequest>,
-) -> Json<Vec<Task>> {
-    // 요청된 티켓들을 Jira에서 조회하기 위해 mock에서 전체 조회
+) -> Result<Json<Vec<Task>>, ApiError> {
+    // 요청된 티켓들을 Jira에서 조회하기 위해 mock에서 board 프로젝트 전체 조회
     let all_tickets = state.jira.query_tickets(JiraQuery {
-        project: None,
+        project: board.jira_project.clone(),
         user: None,
         start_date: None,
         end_date: None,
@@ -148,218 +1159,1457 @@ async fn add_tasks_from_backlog(
     }
     flatten(all_tickets, &mut ticket_map);
 
-    let mut created_tasks: Vec<Task> = Vec::new();
+    fn task_request(ticket: &JiraTicket, parent_task_id: Option<i64>) -> CreateTaskRequest {
+        CreateTaskRequest {
+            title: ticket.title.clone(),
+            description: ticket.description.clone(),
+            jira_ticket_key: Some(ticket.key.clone()),
+            assignee: ticket.assignee.clone(),
+            project_key: Some(ticket.project_key.clone()),
+            parent_task_id,
+            alias: None,
+            start_date: ticket.start_date,
+            due_date: ticket.due_date,
+            jira_url: ticket.jira_url.clone(),
+        }
+    }
+
+    // 추가, 단계별 계획 완료일, roll-up, 링크를 한 transaction으로 (중간에 실패하면 아무것도 추가되지 않음)
+    let mut tx = state.db.begin().await?;
+    let mut created: Vec<i64> = Vec::new();
+    let mut rolled_up = Vec::new();
 
     for key in &req.ticket_keys {
-        if let Some(ticket) = ticket_map.get(key) {
-            // 이미 같은 jira_ticket_key로 존재하는지 확인
-            let existing: Option<Task> = sqlx::query_as::<_, Task>(
-                "SELECT * FROM tasks WHERE jira_ticket_key = ?"
-            )
-            .bind(&ticket.key)
-            .fetch_optional(&state.db)
-            .await
-            .unwrap_or(None);
+        let Some(ticket) = ticket_map.get(key) else { continue };
+        // 이 board에 이미 같은 jira_ticket_key로 존재하는지 확인
+        let parent_id = match task_id_by_jira_key(&mut tx, board.id, &ticket.key).await? {
+            Some(existing) => existing,
+            None => {
+                let id = insert_task_row(&mut tx, &board, &task_request(ticket, None)).await?;
+                created.push(id);
+                id
+            }
+        };
 
-            let parent_id = if let Some(existing) = existing {
-                existing.id
-            } else {
-                let r = sqlx::query(
-                    "INSERT INTO tasks (jira_ticket_key, title, description, status, assignee,
-                                       project_key, start_date, due_date, jira_url)
-                     VALUES (?, ?, ?, 'Pending', ?, ?, ?, ?, ?)"
-                )
-                .bind(&ticket.key)
-                .bind(&ticket.title)
-                .bind(&ticket.description)
-                .bind(&ticket.assignee)
-                .bind(&ticket.project_key)
-                .bind(ticket.start_date.map(|d| d.to_string()))
-                .bind(ticket.due_date.map(|d| d.to_string()))
-                .bind(&ticket.jira_url)
-                .execute(&state.db)
-                .await
-                .unwrap();
-                let inserted_id = r.last_insert_rowid();
-                if let Ok(Some(t)) = sqlx::query_as::<_, Task>("SELECT * FROM tasks WHERE id = ?")
-                    .bind(inserted_id)
-                    .fetch_optional(&state.db)
-                    .await
-                {
-                    created_tasks.push(t);
+        // subtask도 추가 (ticket_map에서 parent_key가 이 티켓인 것들)
+        if req.include_subtasks {
+            let subtasks: Vec<&JiraTicket> = ticket_map
+                .values()
+                .filter(|t| t.parent_key.as_deref() == Some(key))
+                .collect();
+            for sub_ticket in subtasks {
+                if task_id_by_jira_key(&mut tx, board.id, &sub_ticket.key).await?.is_none() {
+                    let id = insert_task_row(&mut tx, &board, &task_request(sub_ticket, Some(parent_id))).await?;
+                    created.push(id);
+                    rolled_up.extend(rollup::apply_from(&mut tx, parent_id).await?);
                 }
-                inserted_id
-            };
+            }
+        }
+    }
 
-            // subtask도 추가
-            if req.include_subtasks {
-                // ticket_map에서 parent_key가 이 티켓인 것들 찾기
-                let subtask_keys: Vec<String> = ticket_map
-                    .values()
-                    .filter(|t| t.parent_key.as_deref() == Some(key))
-                    .map(|t| t.key.clone())
-                    .collect();
-
-                for sub_key in subtask_keys {
-                    if let Some(sub_ticket) = ticket_map.get(&sub_key) {
-                        let already: Option<Task> = sqlx::query_as::<_, Task>(
-                            "SELECT * FROM tasks WHERE jira_ticket_key = ?"
-                        )
-                        .bind(&sub_ticket.key)
-                        .fetch_optional(&state.db)
-                        .await
-                        .unwrap_or(None);
-
-                        if already.is_none() {
-                            let sr = sqlx::query(
-                                "INSERT INTO tasks (jira_ticket_key, title, description, status,
-                                                    assignee, project_key, parent_task_id,
-                                                    start_date, due_date, jira_url)
-                                 VALUES (?, ?, ?, 'Pending', ?, ?, ?, ?, ?, ?)"
-                            )
-                            .bind(&sub_ticket.key)
-                            .bind(&sub_ticket.title)
-                            .bind(&sub_ticket.description)
-                            .bind(&sub_ticket.assignee)
-                            .bind(&sub_ticket.project_key)
-                            .bind(parent_id)
-                            .bind(sub_ticket.start_date.map(|d| d.to_string()))
-                            .bind(sub_ticket.due_date.map(|d| d.to_string()))
-                            .bind(&sub_ticket.jira_url)
-                            .execute(&state.db)
-                            .await
-                            .unwrap();
-                            if let Ok(Some(t)) = sqlx::query_as::<_, Task>(
-                                "SELECT * FROM tasks WHERE id = ?"
-                            )
-                            .bind(sr.last_insert_rowid())
-                            .fetch_optional(&state.db)
-                            .await
-                            {
-                                created_tasks.push(t);
-                            }
-                        }
-                    }
+    // Jira issue link → task link (양쪽 티켓이 모두 kanban에 있을 때만)
+    if req.include_links {
+        let selected = |t: &JiraTicket| {
+            req.ticket_keys.contains(&t.key)
+                || (req.include_subtasks
+                    && t.parent_key.as_ref().is_some_and(|p| req.ticket_keys.contains(p)))
+        };
+        for ticket in ticket_map.values() {
+            for link in &ticket.issue_links {
+                let target_selected = ticket_map.get(&link.target_key).is_some_and(selected);
+                if !selected(ticket) && !target_selected {
+                    continue;
+                }
+                let (Some(source), Some(target)) = (
+                    task_id_by_jira_key(&mut tx, board.id, &ticket.key).await?,
+                    task_id_by_jira_key(&mut tx, board.id, &link.target_key).await?,
+                ) else {
+                    continue;
+                };
+                match links::create_link(&mut tx, source, target, &link.link_type).await {
+                    // 이미 있는 링크나 사이클을 만드는 링크는 건너뜀
+                    Ok(_) => {}
+                    Err(e) if e.status == axum::http::StatusCode::CONFLICT => {}
+                    Err(e) => return Err(e),
                 }
             }
         }
     }
+    tx.commit().await?;
+
+    let mut created_tasks = sqlx::query_as::<_, Task>(
+        "SELECT * FROM tasks WHERE id IN (SELECT value FROM json_each(?)) ORDER BY id"
+    )
+    .bind(serde_json::to_string(&created).unwrap_or_default())
+    .fetch_all(&state.db)
+    .await?;
+    schedule::annotate(&state.db, &mut created_tasks).await?;
+    for task in &created_tasks {
+        state.events.publish(events::TASK_CREATED, &board.key, Some(task.id), Some(&user.username), serde_json::json!({ "task": task }));
+    }
+    markdown::annotate_tasks(&state.db, &user, &mut created_tasks).await?;
+    rolled_up.retain(|id| !created.contains(id));
+    publish_rollup_events(&state, &rolled_up).await;
+    Ok(Json(created_tasks))
+}
 
-    Json(created_tasks)
+async fn task_id_by_jira_key(conn: &mut sqlx::SqliteConnection, board_id: i64, key: &str) -> Result<Option<i64>, sqlx::Error> {
+    let id: Option<(i64,)> = sqlx::query_as("SELECT id FROM tasks WHERE jira_ticket_key = ? AND board_id = ?")
+        .bind(key)
+        .bind(board_id)
+        .fetch_optional(&mut *conn)
+        .await?;
+    Ok(id.map(|(id,)| id))
 }
 
-/// Task 업데이트 (alias, dates, 메타데이터 등)
+/// Task 업데이트 (alias, dates, 메타데이터 등).
+/// status/stage가 바뀌면 workflow 전이 규칙과 blocker를 검사한다.
+/// If-Match(또는 version)가 현재 버전과 다르면 409 + 현재 task.
 async fn update_task(
     State(state): State<AppState>,
     Path(id): Path<i64>,
+    BoardAccess { user, role, board }: BoardMember,
+    headers: HeaderMap,
     Json(req): Json<UpdateTaskRequest>,
-) -> Json<bool> {
-    let result = sqlx::query(
+) -> Result<Response, ApiError> {
+    let expected = expected_version(&headers, req.version)?;
+    let mut tx = state.db.begin().await?;
+
+    let task = fetch_task(&mut tx, id).await?;
+    check_version(&task, expected)?;
+    if let Some(status) = req.status.as_deref()
+        && !workflow::STATUSES.contains(&status)
+    {
+        return Err(ApiError::bad_request(format!("unknown status: {}", status)));
+    }
+    if let Some(stage) = req.stage.as_deref()
+        && !workflow::STAGES.contains(&stage)
+    {
+        return Err(ApiError::bad_request(format!("unknown stage: {}", stage)));
+    }
+    let updated = apply_task_update(&task, &req);
+    workflow::check_transition(
+        &mut tx,
+        &board.workflow,
+        &task,
+        &updated,
+        req.comment.as_deref(),
+        Some(role),
+    )
+    .await?;
+    if updated.status == "InProgress" && task.status != "InProgress" {
+        let blockers = links::open_blockers(&mut tx, id).await?;
+        if !blockers.is_empty() {
+            return Err(blocked_error(id, &blockers));
+        }
+    }
+    // WIP 한도 override는 status API에서만 (reason을 history에 남긴다)
+    let violations = workflow::check_wip(&mut tx, &task, &updated.status, updated.stage.as_deref()).await?;
+    if let Some(violation) = violations.first() {
+        return Err(violation.clone().into_error().with("violations", &violations));
+    }
+
+    // 지울 수 있는 필드는 (보냈는지, 값) 두 개를 bind: 보냈으면 null이어도 그 값으로
+    let changed = sqlx::query(
         "UPDATE tasks SET
             title       = COALESCE(?, title),
-            description = COALESCE(?, description),
+            description = CASE WHEN ? THEN ? ELSE description END,
             status      = COALESCE(?, status),
-            stage       = COALESCE(?, stage),
-            assignee    = COALESCE(?, assignee),
-            alias       = COALESCE(?, alias),
-            tags        = COALESCE(?, tags),
-            keywords    = COALESCE(?, keywords),
-            start_date  = COALESCE(?, start_date),
-            due_date    = COALESCE(?, due_date),
-            updated_at  = ?
-         WHERE id = ?"
+            stage       = ?,
+            assignee    = CASE WHEN ? THEN ? ELSE assignee END,
+            alias       = CASE WHEN ? THEN ? ELSE alias END,
+            tags        = CASE WHEN ? THEN ? ELSE tags END,
+            keywords    = CASE WHEN ? THEN ? ELSE keywords END,
+            start_date  = CASE WHEN ? THEN ? ELSE start_date END,
+            due_date    = CASE WHEN ? THEN ? ELSE due_date END,
+            project_key = CASE WHEN ? THEN ? ELSE project_key END,
+            jira_url    = CASE WHEN ? THEN ? ELSE jira_url END,
+            updated_at  = ?,
+            version     = version + 1
+         WHERE id = ? AND version = ?"
     )
     .bind(&req.title)
-    .bind(&req.description)
+    .bind(req.description.is_some())
+    .bind(req.description.clone().flatten())
     .bind(&req.status)
-    .bind(&req.stage)
-    .bind(&req.assignee)
-    .bind(&req.alias)
-    .bind(&req.tags)
-    .bind(&req.keywords)
-    .bind(req.start_date.map(|d| d.to_string()))
-    .bind(req.due_date.map(|d| d.to_string()))
+    .bind(&updated.stage)
+    .bind(req.assignee.is_some())
+    .bind(req.assignee.clone().flatten())
+    .bind(req.alias.is_some())
+    .bind(req.alias.clone().flatten())
+    .bind(req.tags.is_some())
+    .bind(req.tags.clone().flatten())
+    .bind(req.keywords.is_some())
+    .bind(req.keywords.clone().flatten())
+    .bind(req.start_date.is_some())
+    .bind(req.start_date.flatten().map(|d| d.to_string()))
+    .bind(req.due_date.is_some())
+    .bind(req.due_date.flatten().map(|d| d.to_string()))
+    .bind(req.project_key.is_some())
+    .bind(req.project_key.clone().flatten())
+    .bind(req.jira_url.is_some())
+    .bind(req.jira_url.clone().flatten())
     .bind(Utc::now())
     .bind(id)
-    .execute(&state.db)
-    .await;
+    .bind(task.version)
+    .execute(&mut *tx)
+    .await?
+    .rows_affected();
+    if changed == 0 {
+        return Err(version_conflict(&fetch_task(&mut tx, id).await?, task.version));
+    }
 
-    Json(result.is_ok())
+    if req.due_date.is_some() {
+        schedule::refresh_stage_deadlines(&mut tx, id).await?;
+    }
+
+    // 상위 task 변경은 move와 같은 검사 (같은 board, 사이클 금지) 후 형제 맨 뒤로
+    let mut rolled_up = Vec::new();
+    if let Some(parent) = req.parent_task_id
+        && parent != task.parent_task_id
+    {
+        tree::move_task(&mut tx, &task, parent, None).await?;
+        if let Some(old_parent) = task.parent_task_id {
+            rolled_up.extend(rollup::apply_from(&mut tx, old_parent).await?);
+        }
+    }
+
+    schedule::record_transition(
+        &mut tx,
+        &task,
+        &updated.status,
+        updated.stage.as_deref(),
+        Some(&user.username),
+    )
+    .await?;
+    rolled_up.extend(rollup::apply_from(&mut tx, id).await?);
+
+    if let Some(comment) = req.comment.as_deref() {
+        insert_comment(&mut tx, id, &user.username, comment).await?;
+    }
+
+    let version = current_version(&mut tx, id).await?;
+    tx.commit().await?;
+    publish_task_event(&state, events::TASK_UPDATED, &board.key, id, &user.username).await;
+    rolled_up.retain(|&r| r != id);
+    publish_rollup_events(&state, &rolled_up).await;
+    Ok(([(header::ETAG, etag(version))], Json(true)).into_response())
+}
+
+/// UpdateTaskRequest를 적용한 뒤의 task (전이 규칙 검사용)
+fn apply_task_update(task: &Task, req: &UpdateTaskRequest) -> Task {
+    let mut t = task.clone();
+    if let Some(v) = &req.title { t.title = v.clone(); }
+    if let Some(v) = &req.description { t.description = v.clone(); }
+    if let Some(v) = &req.status { t.status = v.clone(); }
+    if let Some(v) = &req.stage { t.stage = Some(v.clone()); }
+    if let Some(v) = &req.assignee { t.assignee = v.clone(); }
+    if let Some(v) = &req.alias { t.alias = v.clone(); }
+    if let Some(v) = &req.tags { t.tags = v.clone(); }
+    if let Some(v) = &req.keywords { t.keywords = v.clone(); }
+    if let Some(v) = req.start_date { t.start_date = v; }
+    if let Some(v) = req.due_date { t.due_date = v; }
+    if let Some(v) = &req.project_key { t.project_key = v.clone(); }
+    if let Some(v) = &req.jira_url { t.jira_url = v.clone(); }
+    if let Some(v) = req.parent_task_id { t.parent_task_id = v; }
+    t.stage = stage_for_status(&t.status, t.stage.as_deref()).map(str::to_string);
+    t
+}
+
+/// If-Match 헤더 (없으면 body의 version)로 받은 기대 버전. "*"이면 확인하지 않는다 (None).
+/// 둘 다 없으면 428: 덮어쓰기를 막으려면 클라이언트가 읽은 버전을 보내야 한다.
+fn expected_version(headers: &HeaderMap, body_version: Option<i64>) -> Result<Option<i64>, ApiError> {
+    let Some(value) = headers.get(header::IF_MATCH) else {
+        return body_version.map(Some).ok_or_else(|| ApiError::new(
+            axum::http::StatusCode::PRECONDITION_REQUIRED,
+            "precondition_required",
+            "send the task version in an If-Match header or the version field",
+        ));
+    };
+    let value = value.to_str().unwrap_or_default().trim();
+    if value == "*" {
+        return Ok(None);
+    }
+    value
+        .trim_start_matches("W/")
+        .trim_matches('"')
+        .parse()
+        .map(Some)
+        .map_err(|_| ApiError::bad_request(format!("invalid If-Match value: {}", value)))
+}
+
+/// 다른 사람이 먼저 바꿨으면 409와 현재 task를 돌려준다
+fn check_version(task: &Task, expected: Option<i64>) -> Result<(), ApiError> {
+    match expected {
+        Some(version) if version != task.version => Err(version_conflict(task, version)),
+        _ => Ok(()),
+    }
 }
 
-/// Task 상태/단계 변경
+fn version_conflict(task: &Task, expected: i64) -> ApiError {
+    ApiError::conflict(
+        "version_conflict",
+        format!(
+            "task {} was modified by someone else (current version {}, yours {})",
+            task.id, task.version, expected
+        ),
+    )
+    .with("current_version", task.version)
+    .with("task", task)
+}
+
+async fn current_version(conn: &mut sqlx::SqliteConnection, id: i64) -> Result<i64, sqlx::Error> {
+    let (version,): (i64,) = sqlx::query_as("SELECT version FROM tasks WHERE id = ?")
+        .bind(id)
+        .fetch_one(&mut *conn)
+        .await?;
+    Ok(version)
+}
+
+fn etag(version: i64) -> String {
+    format!("\"{}\"", version)
+}
+
+async fn fetch_task(conn: &mut sqlx::SqliteConnection, id: i64) -> Result<Task, ApiError> {
+    sqlx::query_as::<_, Task>("SELECT * FROM tasks WHERE id = ?")
+        .bind(id)
+        .fetch_optional(&mut *conn)
+        .await?
+        .ok_or_else(|| ApiError::not_found(format!("task {} not found", id)))
+}
+
+/// Task 상태/단계 변경.
+/// workflow 전이 규칙을 먼저 검사하고, 막고 있는 task가 남아 있는데 InProgress로 옮기거나
+/// WIP 한도를 넘기는 이동은 409로 거부한다.
+/// override=true + reason이면 blocker/WIP 한도를 무시하고 허용하되 history에 기록.
 async fn update_task_status(
     State(state): State<AppState>,
     Path(id): Path<i64>,
+    BoardAccess { user, role, board }: BoardMember,
+    headers: HeaderMap,
     Json(req): Json<UpdateStatusRequest>,
-) -> Json<bool> {
-    // Done으로 가면 stage 초기화
-    let stage = if req.status == "Done" || req.status == "Pending" {
+) -> Result<Response, ApiError> {
+    let expected = expected_version(&headers, req.version)?;
+    let mut tx = state.db.begin().await?;
+    let task = fetch_task(&mut tx, id).await?;
+    check_version(&task, expected)?;
+    let mut rolled_up = change_status(&mut tx, &board, &user, role, &task, &req).await?;
+
+    let version = current_version(&mut tx, id).await?;
+    tx.commit().await?;
+    publish_task_event(&state, events::TASK_MOVED, &board.key, id, &user.username).await;
+    rolled_up.retain(|&r| r != id);
+    publish_rollup_events(&state, &rolled_up).await;
+    Ok(([(header::ETAG, etag(version))], Json(true)).into_response())
+}
+
+/// Done이나 Pending으로 가면 stage 초기화 (status API와 PUT이 같은 규칙)
+fn stage_for_status<'a>(status: &str, stage: Option<&'a str>) -> Option<&'a str> {
+    if status == "Done" || status == "Pending" {
         None
     } else {
-        req.stage.as_deref()
+        stage
+    }
+}
+
+/// 상태/단계 변경 본체 (단건 status API와 bulk가 같이 쓴다). 호출한 쪽의 transaction 안에서 실행.
+/// roll-up으로 바뀐 task id를 돌려준다.
+async fn change_status(
+    conn: &mut sqlx::SqliteConnection,
+    board: &Board,
+    user: &CurrentUser,
+    role: auth::BoardRole,
+    task: &Task,
+    req: &UpdateStatusRequest,
+) -> Result<Vec<i64>, ApiError> {
+    let id = task.id;
+    if !workflow::STATUSES.contains(&req.status.as_str()) {
+        return Err(ApiError::bad_request(format!("unknown status: {}", req.status)));
+    }
+    if let Some(stage) = req.stage.as_deref()
+        && !workflow::STAGES.contains(&stage)
+    {
+        return Err(ApiError::bad_request(format!("unknown stage: {}", stage)));
+    }
+
+    let stage = stage_for_status(&req.status, req.stage.as_deref());
+
+    let reason = req.reason.as_deref().map(str::trim).filter(|r| !r.is_empty());
+    if req.override_limits && reason.is_none() {
+        return Err(ApiError::bad_request("override requires a non-empty reason"));
+    }
+
+    let mut moved = task.clone();
+    moved.status = req.status.clone();
+    moved.stage = stage.map(str::to_string);
+    workflow::check_transition(
+        &mut *conn,
+        &board.workflow,
+        task,
+        &moved,
+        req.comment.as_deref(),
+        Some(role),
+    )
+    .await?;
+
+    let blockers = if req.status == "InProgress" && task.status != "InProgress" {
+        links::open_blockers(&mut *conn, id).await?
+    } else {
+        vec![]
     };
+    if !blockers.is_empty() {
+        if !req.override_limits {
+            return Err(blocked_error(id, &blockers));
+        }
+        let detail = serde_json::json!({ "blockers": blockers, "reason": reason });
+        sqlx::query("INSERT INTO task_history (task_id, action, detail, actor) VALUES (?, 'blocked_override', ?, ?)")
+            .bind(id)
+            .bind(detail.to_string())
+            .bind(&user.username)
+            .execute(&mut *conn)
+            .await?;
+    }
 
-    let result = sqlx::query(
-        "UPDATE tasks SET status = ?, stage = ?, updated_at = ? WHERE id = ?"
+    let violations = workflow::check_wip(&mut *conn, task, &req.status, stage).await?;
+    if !violations.is_empty() {
+        if !req.override_limits {
+            return Err(violations[0].clone().into_error().with("violations", &violations));
+        }
+        let detail = serde_json::json!({
+            "from": { "status": task.status, "stage": task.stage },
+            "to": { "status": req.status, "stage": stage },
+            "violations": violations,
+            "reason": reason,
+        });
+        sqlx::query("INSERT INTO task_history (task_id, action, detail, actor) VALUES (?, 'wip_override', ?, ?)")
+            .bind(id)
+            .bind(detail.to_string())
+            .bind(&user.username)
+            .execute(&mut *conn)
+            .await?;
+    }
+
+    let changed = sqlx::query(
+        "UPDATE tasks SET status = ?, stage = ?, updated_at = ?, version = version + 1
+         WHERE id = ? AND version = ?"
     )
     .bind(&req.status)
     .bind(stage)
     .bind(Utc::now())
     .bind(id)
-    .execute(&state.db)
-    .await;
+    .bind(task.version)
+    .execute(&mut *conn)
+    .await?
+    .rows_affected();
+    if changed == 0 {
+        return Err(version_conflict(&fetch_task(&mut *conn, id).await?, task.version));
+    }
 
-    Json(result.is_ok())
+    schedule::record_transition(&mut *conn, task, &req.status, stage, Some(&user.username)).await?;
+    let rolled_up = rollup::apply_from(&mut *conn, id).await?;
+
+    if let Some(comment) = req.comment.as_deref() {
+        insert_comment(&mut *conn, id, &user.username, comment).await?;
+    }
+
+    Ok(rolled_up)
 }
 
-/// Task 삭제
+fn blocked_error(id: i64, blockers: &[BlockingTask]) -> ApiError {
+    let keys: Vec<String> = blockers
+        .iter()
+        .map(|b| b.jira_ticket_key.clone().unwrap_or_else(|| format!("#{}", b.id)))
+        .collect();
+    ApiError::conflict(
+        "blocked",
+        format!("task {} is blocked by unfinished task(s): {}", id, keys.join(", ")),
+    )
+    .with("blockers", blockers)
+}
+
+/// Task 삭제. 상위 task에는 roll-up을 다시 적용한다.
 async fn delete_task(
     State(state): State<AppState>,
     Path(id): Path<i64>,
+    BoardAccess { user, board, .. }: BoardAdmin,
 ) -> Json<bool> {
-    let result = sqlx::query("DELETE FROM tasks WHERE id = ?")
-        .bind(id)
-        .execute(&state.db)
-        .await;
+    let result: Result<Vec<i64>, sqlx::Error> = async {
+        let mut tx = state.db.begin().await?;
+        let task = sqlx::query_as::<_, Task>("SELECT * FROM tasks WHERE id = ?")
+            .bind(id)
+            .fetch_optional(&mut *tx)
+            .await?;
+        if let Some(task) = &task {
+            record_deletion(&mut tx, task, "delete", &user.username).await?;
+        }
+        sqlx::query("DELETE FROM tasks WHERE id = ?")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        let rolled_up = match task.and_then(|t| t.parent_task_id) {
+            Some(parent) => rollup::apply_from(&mut tx, parent).await?,
+            None => Vec::new(),
+        };
+        tx.commit().await?;
+        Ok(rolled_up)
+    }
+    .await;
+    match &result {
+        Ok(rolled_up) => {
+            state.events.publish(events::TASK_DELETED, &board.key, Some(id), Some(&user.username), serde_json::json!({ "id": id }));
+            publish_rollup_events(&state, rolled_up).await;
+        }
+        Err(e) => eprintln!("Error deleting task: {}", e),
+    }
     Json(result.is_ok())
 }
 
+/// 지우기 직전의 task를 task_deletions에 남긴다 (task_history는 task와 같이 지워지므로)
+async fn record_deletion(
+    conn: &mut sqlx::SqliteConnection,
+    task: &Task,
+    action: &str,
+    actor: &str,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "INSERT INTO task_deletions (task_id, board_id, title, action, detail, actor) VALUES (?, ?, ?, ?, ?, ?)"
+    )
+    .bind(task.id)
+    .bind(task.board_id)
+    .bind(&task.title)
+    .bind(action)
+    .bind(serde_json::to_string(task).unwrap_or_default())
+    .bind(actor)
+    .execute(&mut *conn)
+    .await?;
+    Ok(())
+}
+
+// ─── Bulk ────────────────────────────────────────────────────────────────────
+
+const BULK_LIMIT: usize = 500;
+
+/// 여러 task에 같은 작업을 한 번에 적용 (task_ids 또는 filter로 선택).
+/// 하나의 transaction에서 task마다 적용하고 결과를 모은다. 하나라도 실패하면 전부 rollback하고
+/// 409 bulk_failed + task별 결과, 모두 성공하면 commit 후 task별 결과를 돌려준다.
+/// 삭제는 board admin만 가능하다.
+async fn bulk_update_tasks(
+    State(state): State<AppState>,
+    BoardAccess { user, role, board }: BoardMember,
+    Json(req): Json<BulkTaskRequest>,
+) -> Result<Json<Vec<BulkTaskResult>>, ApiError> {
+    let deleting = matches!(req.operation, BulkOperation::Delete);
+    if deleting && role < auth::BoardRole::Admin {
+        return Err(ApiError::forbidden(format!("deleting tasks requires the admin role on board {}", board.key))
+            .with("board", &board.key)
+            .with("role", role)
+            .with("required", auth::BoardRole::Admin));
+    }
+
+    let mut tx = state.db.begin().await?;
+    let ids = match (&req.task_ids, &req.filter) {
+        (Some(ids), None) => {
+            let mut seen = std::collections::HashSet::new();
+            ids.iter().copied().filter(|id| seen.insert(*id)).collect()
+        }
+        (None, Some(filter)) if task_query::is_empty(filter) => {
+            return Err(ApiError::bad_request("filter needs at least one condition"));
+        }
+        (None, Some(filter)) => task_query::filter_ids(&mut tx, board.id, filter).await?,
+        _ => return Err(ApiError::bad_request("send either task_ids or filter")),
+    };
+    if ids.len() > BULK_LIMIT {
+        return Err(ApiError::bad_request(format!(
+            "{} tasks selected; at most {} can be changed at once",
+            ids.len(),
+            BULK_LIMIT
+        )));
+    }
+
+    // 상위/하위 task를 같이 지울 때 순서와 관계없이 지울 수 있도록 FK 검사를 commit 시점으로 미룬다
+    if deleting {
+        sqlx::query("PRAGMA defer_foreign_keys = ON").execute(&mut *tx).await?;
+    }
+
+    let mut results = Vec::with_capacity(ids.len());
+    let mut parents = Vec::new();
+    let mut rolled_up = Vec::new();
+    for &id in &ids {
+        let result = bulk_apply(&mut tx, &board, &user, role, id, &req.operation, &ids, &mut rolled_up).await;
+        if let Ok(Some(parent)) = result {
+            parents.push(parent);
+        }
+        results.push(BulkTaskResult {
+            task_id: id,
+            ok: result.is_ok(),
+            error: result.err().map(|e| serde_json::Value::Object(e.body)),
+        });
+    }
+
+    let failed = results.iter().filter(|r| !r.ok).count();
+    if failed > 0 {
+        tx.rollback().await?;
+        return Err(ApiError::conflict(
+            "bulk_failed",
+            format!("{} of {} task(s) failed; nothing was changed", failed, results.len()),
+        )
+        .with("results", &results));
+    }
+
+    // 지운 task의 상위 task (같이 지운 것 제외)에 roll-up 다시 적용
+    for parent in parents {
+        if !ids.contains(&parent) {
+            rolled_up.extend(rollup::apply_from(&mut tx, parent).await?);
+        }
+    }
+    tx.commit().await?;
+
+    let kind = match req.operation {
+        BulkOperation::SetStatus(_) => events::TASK_MOVED,
+        BulkOperation::Delete => events::TASK_DELETED,
+        _ => events::TASK_UPDATED,
+    };
+    for &id in &ids {
+        if deleting {
+            state.events.publish(kind, &board.key, Some(id), Some(&user.username), serde_json::json!({ "id": id }));
+        } else {
+            publish_task_event(&state, kind, &board.key, id, &user.username).await;
+        }
+    }
+    rolled_up.retain(|id| !ids.contains(id));
+    publish_rollup_events(&state, &rolled_up).await;
+    Ok(Json(results))
+}
+
+/// task 하나에 bulk 작업 적용. 삭제했으면 원래 상위 task id를 돌려준다 (roll-up용).
+/// roll-up으로 바뀐 task는 rolled_up에 모은다.
+#[allow(clippy::too_many_arguments)]
+async fn bulk_apply(
+    conn: &mut sqlx::SqliteConnection,
+    board: &Board,
+    user: &CurrentUser,
+    role: auth::BoardRole,
+    id: i64,
+    operation: &BulkOperation,
+    selected: &[i64],
+    rolled_up: &mut Vec<i64>,
+) -> Result<Option<i64>, ApiError> {
+    let task = fetch_task(&mut *conn, id).await?;
+    if task.board_id != board.id {
+        return Err(ApiError::not_found(format!("task {} not found on board {}", id, board.key)));
+    }
+
+    let (op, from, to) = match operation {
+        BulkOperation::SetStatus(req) => {
+            rolled_up.extend(change_status(&mut *conn, board, user, role, &task, req).await?);
+            (
+                "set_status",
+                serde_json::json!({ "status": task.status, "stage": task.stage }),
+                serde_json::json!({ "status": req.status, "stage": req.stage }),
+            )
+        }
+        BulkOperation::SetAssignee { assignee } => {
+            let assignee = assignee.as_deref().map(str::trim).filter(|a| !a.is_empty());
+            set_task_fields(&mut *conn, &task, "assignee = ?", vec![assignee.map(str::to_string)]).await?;
+            ("set_assignee", serde_json::json!(task.assignee), serde_json::json!(assignee))
+        }
+        BulkOperation::ShiftDates { days } => {
+            let shift = |date: Option<NaiveDate>| -> Result<Option<NaiveDate>, ApiError> {
+                date.map(|d| {
+                    d.checked_add_signed(chrono::Duration::days(*days))
+                        .ok_or_else(|| ApiError::bad_request(format!("cannot shift {} by {} days", d, days)))
+                })
+                .transpose()
+            };
+            let (start, due) = (shift(task.start_date)?, shift(task.due_date)?);
+            let values = vec![start.map(|d| d.to_string()), due.map(|d| d.to_string())];
+            set_task_fields(&mut *conn, &task, "start_date = ?, due_date = ?", values).await?;
+            schedule::refresh_stage_deadlines(&mut *conn, id).await?;
+            rolled_up.extend(rollup::apply_from(&mut *conn, id).await?);
+            (
+                "shift_dates",
+                serde_json::json!({ "start_date": task.start_date, "due_date": task.due_date }),
+                serde_json::json!({ "start_date": start, "due_date": due }),
+            )
+        }
+        BulkOperation::AddTags { tags } | BulkOperation::RemoveTags { tags } => {
+            let adding = matches!(operation, BulkOperation::AddTags { .. });
+            let mut current = split_tags(task.tags.as_deref());
+            let given = tags.iter().map(|t| t.trim()).filter(|t| !t.is_empty());
+            if adding {
+                for tag in given {
+                    if !current.iter().any(|t| t == tag) {
+                        current.push(tag.to_string());
+                    }
+                }
+            } else {
+                let given: Vec<&str> = given.collect();
+                current.retain(|t| !given.contains(&t.as_str()));
+            }
+            let joined = (!current.is_empty()).then(|| current.join(", "));
+            set_task_fields(&mut *conn, &task, "tags = ?", vec![joined.clone()]).await?;
+            (
+                if adding { "add_tags" } else { "remove_tags" },
+                serde_json::json!(task.tags),
+                serde_json::json!(joined),
+            )
+        }
+        BulkOperation::Delete => {
+            // 하위 task가 있으면 같이 선택해야 지울 수 있다
+            let children: Vec<i64> = sqlx::query_as::<_, (i64,)>("SELECT id FROM tasks WHERE parent_task_id = ?")
+                .bind(id)
+                .fetch_all(&mut *conn)
+                .await?
+                .into_iter()
+                .map(|(c,)| c)
+                .filter(|c| !selected.contains(c))
+                .collect();
+            if !children.is_empty() {
+                return Err(ApiError::bad_request(format!(
+                    "task {} has subtasks that are not selected for deletion",
+                    id
+                ))
+                .with("children", children));
+            }
+            record_deletion(&mut *conn, &task, "bulk_delete", &user.username).await?;
+            sqlx::query("DELETE FROM tasks WHERE id = ?").bind(id).execute(&mut *conn).await?;
+            return Ok(task.parent_task_id);
+        }
+    };
+
+    let detail = serde_json::json!({ "op": op, "from": from, "to": to });
+    sqlx::query("INSERT INTO task_history (task_id, action, detail, actor) VALUES (?, 'bulk_update', ?, ?)")
+        .bind(id)
+        .bind(detail.to_string())
+        .bind(&user.username)
+        .execute(&mut *conn)
+        .await?;
+    Ok(None)
+}
+
+/// bulk용 단순 필드 변경 ("col = ?" 순서대로 값 bind, updated_at/version 같이 갱신)
+async fn set_task_fields(
+    conn: &mut sqlx::SqliteConnection,
+    task: &Task,
+    assignments: &str,
+    values: Vec<Option<String>>,
+) -> Result<(), ApiError> {
+    let sql = format!(
+        "UPDATE tasks SET {}, updated_at = ?, version = version + 1 WHERE id = ? AND version = ?",
+        assignments
+    );
+    let mut query = sqlx::query(&sql);
+    for value in values {
+        query = query.bind(value);
+    }
+    let changed = query
+        .bind(Utc::now())
+        .bind(task.id)
+        .bind(task.version)
+        .execute(&mut *conn)
+        .await?
+        .rows_affected();
+    if changed == 0 {
+        return Err(version_conflict(&fetch_task(&mut *conn, task.id).await?, task.version));
+    }
+    Ok(())
+}
+
+/// "a, b,c" → ["a", "b", "c"]
+fn split_tags(tags: Option<&str>) -> Vec<String> {
+    tags.unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
 // ─── Task Comments ────────────────────────────────────────────────────────────
 
 async fn list_task_comments(
     State(state): State<AppState>,
     Path(task_id): Path<i64>,
-) -> Json<Vec<TaskComment>> {
-    let comments = sqlx::query_as::<_, TaskComment>(
+    BoardAccess { user, .. }: BoardViewer,
+) -> Result<Json<Vec<TaskComment>>, ApiError> {
+    let mut conn = state.db.acquire().await?;
+    let mut comments = sqlx::query_as::<_, TaskComment>(
         "SELECT * FROM task_comments WHERE task_id = ? ORDER BY created_at ASC"
     )
     .bind(task_id)
-    .fetch_all(&state.db)
-    .await
-    .unwrap_or_default();
-    Json(comments)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    // 삭제된 comment의 첨부는 보여주지 않는다
+    let files = attachments::load_for_comments(&mut conn, task_id).await?;
+    for comment in comments.iter_mut().filter(|c| c.deleted_at.is_none()) {
+        comment.files = files.iter().filter(|f| f.comment_id == Some(comment.id)).cloned().collect();
+    }
+    markdown::annotate_comments(&mut conn, &user, &mut comments).await?;
+    Ok(Json(comments))
 }
 
 async fn add_task_comment(
     State(state): State<AppState>,
     Path(task_id): Path<i64>,
-    Json(payload): Json<serde_json::Value>,
-) -> Json<bool> {
-    let author = payload.get("author").and_then(|v| v.as_str()).unwrap_or("익명");
-    let content = payload.get("content").and_then(|v| v.as_str()).unwrap_or("");
-    let attachments = payload.get("attachments").and_then(|v| v.as_str());
+    BoardAccess { user, board, .. }: BoardMember,
+    Json(req): Json<CreateCommentRequest>,
+) -> Result<Json<TaskComment>, ApiError> {
+    if req.content.trim().is_empty() {
+        return Err(ApiError::bad_request("comment content must not be empty"));
+    }
 
-    let result = sqlx::query(
+    let mut tx = state.db.begin().await?;
+    fetch_task(&mut tx, task_id).await?;
+    let mut comment = sqlx::query_as::<_, TaskComment>(
         "INSERT INTO task_comments (task_id, author, content, attachments)
-         VALUES (?, ?, ?, ?)"
+         VALUES (?, ?, ?, ?) RETURNING *"
+    )
+    .bind(task_id)
+    .bind(&user.username)
+    .bind(&req.content)
+    .bind(&req.attachments)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    attachments::link_to_comment(&mut tx, task_id, comment.id, &req.attachment_ids).await?;
+    markdown::save_mentions(&mut tx, &comment).await?;
+    comment.files = sqlx::query_as::<_, Attachment>("SELECT * FROM attachments WHERE comment_id = ? ORDER BY id")
+        .bind(comment.id)
+        .fetch_all(&mut *tx)
+        .await?;
+    tx.commit().await?;
+    state.events.publish(events::COMMENT_ADDED, &board.key, Some(task_id), Some(&user.username), serde_json::json!({ "comment": comment }));
+    // 참조 링크는 작성자가 볼 수 있는 board 기준이므로 알림에는 넣지 않는다
+    markdown::annotate_comments(&mut *state.db.acquire().await?, &user, std::slice::from_mut(&mut comment)).await?;
+    Ok(Json(comment))
+}
+
+/// 수정/삭제 대상 comment. 작성자 본인이나 admin이 아니면 403.
+async fn fetch_own_comment(
+    conn: &mut sqlx::SqliteConnection,
+    task_id: i64,
+    comment_id: i64,
+    user: &CurrentUser,
+    is_board_admin: bool,
+) -> Result<TaskComment, ApiError> {
+    let comment = sqlx::query_as::<_, TaskComment>(
+        "SELECT * FROM task_comments WHERE id = ? AND task_id = ?"
+    )
+    .bind(comment_id)
+    .bind(task_id)
+    .fetch_optional(&mut *conn)
+    .await?
+    .ok_or_else(|| ApiError::not_found(format!("comment {} not found on task {}", comment_id, task_id)))?;
+
+    if comment.deleted_at.is_some() {
+        return Err(ApiError::conflict("comment_deleted", format!("comment {} has been deleted", comment_id)));
+    }
+    if !is_board_admin && user.username != comment.author {
+        return Err(ApiError::forbidden(format!(
+            "only {} or an admin can change comment {}",
+            comment.author, comment_id
+        )));
+    }
+    Ok(comment)
+}
+
+/// 바뀌기 전 내용을 revision으로 남긴다
+async fn save_comment_revision(
+    conn: &mut sqlx::SqliteConnection,
+    comment: &TaskComment,
+    edited_by: &str,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "INSERT INTO task_comment_revisions (comment_id, content, attachments, edited_by) VALUES (?, ?, ?, ?)"
+    )
+    .bind(comment.id)
+    .bind(&comment.content)
+    .bind(&comment.attachments)
+    .bind(edited_by)
+    .execute(&mut *conn)
+    .await?;
+    Ok(())
+}
+
+async fn update_task_comment(
+    State(state): State<AppState>,
+    Path((task_id, comment_id)): Path<(i64, i64)>,
+    BoardAccess { user, role, board }: BoardMember,
+    Json(req): Json<UpdateCommentRequest>,
+) -> Result<Json<TaskComment>, ApiError> {
+    if req.content.trim().is_empty() {
+        return Err(ApiError::bad_request("comment content must not be empty"));
+    }
+    let mut tx = state.db.begin().await?;
+    let comment = fetch_own_comment(&mut tx, task_id, comment_id, &user, role == auth::BoardRole::Admin).await?;
+    save_comment_revision(&mut tx, &comment, &user.username).await?;
+
+    let mut updated = sqlx::query_as::<_, TaskComment>(
+        "UPDATE task_comments SET content = ?, attachments = COALESCE(?, attachments), edited_at = ?
+         WHERE id = ? RETURNING *"
+    )
+    .bind(&req.content)
+    .bind(&req.attachments)
+    .bind(Utc::now())
+    .bind(comment_id)
+    .fetch_one(&mut *tx)
+    .await?;
+    markdown::save_mentions(&mut tx, &updated).await?;
+    tx.commit().await?;
+    state.events.publish(events::COMMENT_UPDATED, &board.key, Some(task_id), Some(&user.username), serde_json::json!({ "comment": updated }));
+    markdown::annotate_comments(&mut *state.db.acquire().await?, &user, std::slice::from_mut(&mut updated)).await?;
+    Ok(Json(updated))
+}
+
+/// 삭제: 내용을 지우고 tombstone을 남긴다 (목록에서 자리가 비지 않도록)
+async fn delete_task_comment(
+    State(state): State<AppState>,
+    Path((task_id, comment_id)): Path<(i64, i64)>,
+    BoardAccess { user, role, board }: BoardMember,
+) -> Result<Json<TaskComment>, ApiError> {
+    let mut tx = state.db.begin().await?;
+    let comment = fetch_own_comment(&mut tx, task_id, comment_id, &user, role == auth::BoardRole::Admin).await?;
+    save_comment_revision(&mut tx, &comment, &user.username).await?;
+
+    let deleted = sqlx::query_as::<_, TaskComment>(
+        "UPDATE task_comments SET content = '', attachments = NULL, deleted_at = ?, deleted_by = ?
+         WHERE id = ? RETURNING *"
+    )
+    .bind(Utc::now())
+    .bind(&user.username)
+    .bind(comment_id)
+    .fetch_one(&mut *tx)
+    .await?;
+    markdown::save_mentions(&mut tx, &deleted).await?;
+    attachments::delete_for_comment(&mut tx, comment_id).await?;
+    tx.commit().await?;
+    state.events.publish(events::COMMENT_DELETED, &board.key, Some(task_id), Some(&user.username), serde_json::json!({ "comment": deleted }));
+    Ok(Json(deleted))
+}
+
+/// comment의 이전 내용들 (오래된 것부터)
+async fn list_comment_revisions(
+    State(state): State<AppState>,
+    Path((task_id, comment_id)): Path<(i64, i64)>,
+    _access: BoardViewer,
+) -> Json<Vec<TaskCommentRevision>> {
+    let revisions = sqlx::query_as::<_, TaskCommentRevision>(
+        "SELECT r.* FROM task_comment_revisions r
+           JOIN task_comments c ON c.id = r.comment_id
+          WHERE r.comment_id = ? AND c.task_id = ?
+          ORDER BY r.created_at ASC, r.id ASC"
+    )
+    .bind(comment_id)
+    .bind(task_id)
+    .fetch_all(&state.db)
+    .await
+    .unwrap_or_default();
+    Json(revisions)
+}
+
+/// 상태 전이와 함께 남기는 comment
+async fn insert_comment(
+    conn: &mut sqlx::SqliteConnection,
+    task_id: i64,
+    author: &str,
+    content: &str,
+) -> Result<(), sqlx::Error> {
+    let comment = sqlx::query_as::<_, TaskComment>(
+        "INSERT INTO task_comments (task_id, author, content) VALUES (?, ?, ?) RETURNING *"
     )
     .bind(task_id)
     .bind(author)
     .bind(content)
-    .bind(attachments)
+    .fetch_one(&mut *conn)
+    .await?;
+    markdown::save_mentions(conn, &comment).await?;
+    Ok(())
+}
+
+/// user(기본: 로그인한 사용자)를 mention한 comment (최신순, 삭제된 comment 제외)
+async fn list_mentions(
+    State(state): State<AppState>,
+    user: CurrentUser,
+    Query(q): Query<MentionQuery>,
+) -> Result<Json<Vec<MentionedComment>>, ApiError> {
+    // 다른 사용자의 mention은 admin만 볼 수 있다
+    let username = match q.user {
+        Some(other) if other != user.username => {
+            user.require_admin()?;
+            other
+        }
+        _ => user.username.clone(),
+    };
+    let mut conn = state.db.acquire().await?;
+    // 호출한 사용자가 볼 수 있는 board의 comment만
+    let boards = auth::viewable_boards(&mut conn, &user)
+        .await?
+        .map(|keys| serde_json::to_string(&keys).unwrap_or_default());
+    let mut mentioned = sqlx::query_as::<_, MentionedComment>(
+        "SELECT c.id AS comment_id, c.task_id, t.title AS task_title, c.author, c.content,
+                c.created_at, c.edited_at
+           FROM comment_mentions m
+           JOIN task_comments c ON c.id = m.comment_id
+           JOIN tasks t ON t.id = c.task_id
+           JOIN boards b ON b.id = t.board_id
+          WHERE m.username = ?1 AND c.deleted_at IS NULL
+            AND (?2 IS NULL OR b.key IN (SELECT value FROM json_each(?2)))
+          ORDER BY c.created_at DESC, c.id DESC"
+    )
+    .bind(&username)
+    .bind(boards)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let texts: Vec<&str> = mentioned.iter().map(|m| m.content.as_str()).collect();
+    let refs = markdown::load_refs(&mut conn, &user, &texts).await?;
+    for m in mentioned.iter_mut() {
+        m.content_html = markdown::render(&m.content, &refs);
+    }
+    Ok(Json(mentioned))
+}
+
+// ─── Attachments ─────────────────────────────────────────────────────────────
+
+// 한 번에 여러 파일을 올릴 수 있도록 파일 하나 한도보다 넉넉하게
+const MAX_UPLOAD_BODY_BYTES: usize = 5 * attachments::MAX_ATTACHMENT_BYTES;
+
+/// multipart 업로드: "file" 필드 (여러 개 가능).
+/// 올린 파일은 comment 작성 시 attachment_ids로 연결한다.
+async fn upload_attachments(
+    State(state): State<AppState>,
+    Path(task_id): Path<i64>,
+    BoardAccess { user, .. }: BoardMember,
+    mut multipart: Multipart,
+) -> Result<Json<Vec<Attachment>>, ApiError> {
+    let bad_multipart = |e: axum::extract::multipart::MultipartError| {
+        ApiError::new(e.status(), "invalid_multipart", e.body_text())
+    };
+
+    let mut files = Vec::new();
+    while let Some(field) = multipart.next_field().await.map_err(bad_multipart)? {
+        if field.name() == Some("file") {
+            let file_name = field.file_name().unwrap_or("attachment").to_string();
+            let content_type = field.content_type().unwrap_or("application/octet-stream").to_string();
+            let data = field.bytes().await.map_err(bad_multipart)?;
+            files.push((file_name, content_type, data));
+        }
+    }
+    if files.is_empty() {
+        return Err(ApiError::bad_request("no file field in upload"));
+    }
+
+    let mut tx = state.db.begin().await?;
+    fetch_task(&mut tx, task_id).await?;
+    let mut result = Vec::with_capacity(files.len());
+    for (file_name, content_type, data) in files {
+        let sha256 = attachments::store(&data, &content_type).await?;
+        let attachment = sqlx::query_as::<_, Attachment>(
+            "INSERT INTO attachments (task_id, sha256, file_name, content_type, size, uploaded_by)
+             VALUES (?, ?, ?, ?, ?, ?) RETURNING *"
+        )
+        .bind(task_id)
+        .bind(&sha256)
+        .bind(&file_name)
+        .bind(&content_type)
+        .bind(data.len() as i64)
+        .bind(&user.username)
+        .fetch_one(&mut *tx)
+        .await?;
+        result.push(attachment);
+    }
+    tx.commit().await?;
+    Ok(Json(result))
+}
+
+async fn download_attachment(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+    _access: BoardViewer,
+) -> Result<Response, ApiError> {
+    let attachment = sqlx::query_as::<_, Attachment>("SELECT * FROM attachments WHERE id = ?")
+        .bind(id)
+        .fetch_optional(&state.db)
+        .await?
+        .ok_or_else(|| ApiError::not_found(format!("attachment {} not found", id)))?;
+
+    let data = tokio::fs::read(attachments::blob_path(&attachment.sha256))
+        .await
+        .map_err(|e| {
+            eprintln!("Attachment read error: {}", e);
+            ApiError::not_found(format!("attachment {} content is missing", id))
+        })?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, attachment.content_type.clone()),
+            (
+                header::CONTENT_DISPOSITION,
+                attachments::content_disposition(&attachment.file_name, &attachment.content_type),
+            ),
+            (header::X_CONTENT_TYPE_OPTIONS, "nosniff".to_string()),
+        ],
+        data,
+    )
+        .into_response())
+}
+
+// ─── Task Links ──────────────────────────────────────────────────────────────
+
+/// task가 source 또는 target인 모든 링크
+async fn list_task_links(
+    State(state): State<AppState>,
+    Path(task_id): Path<i64>,
+    _access: BoardViewer,
+) -> Json<Vec<TaskLink>> {
+    let task_links = sqlx::query_as::<_, TaskLink>(
+        "SELECT * FROM task_links WHERE source_task_id = ?1 OR target_task_id = ?1 ORDER BY id ASC"
+    )
+    .bind(task_id)
+    .fetch_all(&state.db)
+    .await
+    .unwrap_or_default();
+    Json(task_links)
+}
+
+/// 링크 추가: 이 task가 target_task_id를 link_type 한다 (예: blocks)
+async fn add_task_link(
+    State(state): State<AppState>,
+    Path(task_id): Path<i64>,
+    BoardAccess { user, board, .. }: BoardMember,
+    Json(req): Json<CreateTaskLinkRequest>,
+) -> Result<Json<TaskLink>, ApiError> {
+    let mut tx = state.db.begin().await?;
+    let link = links::create_link(&mut tx, task_id, req.target_task_id, &req.link_type).await?;
+    tx.commit().await?;
+    publish_task_event(&state, events::TASK_UPDATED, &board.key, task_id, &user.username).await;
+    Ok(Json(link))
+}
+
+async fn delete_task_link(
+    State(state): State<AppState>,
+    Path((task_id, link_id)): Path<(i64, i64)>,
+    BoardAccess { user, board, .. }: BoardMember,
+) -> Json<bool> {
+    let result = sqlx::query(
+        "DELETE FROM task_links WHERE id = ?1 AND (source_task_id = ?2 OR target_task_id = ?2)"
+    )
+    .bind(link_id)
+    .bind(task_id)
     .execute(&state.db)
     .await;
+    let deleted = result.is_ok_and(|r| r.rows_affected() > 0);
+    if deleted {
+        publish_task_event(&state, events::TASK_UPDATED, &board.key, task_id, &user.username).await;
+    }
+    Json(deleted)
+}
 
-    Json(result.is_ok())
+/// 이 task를 막고 있는, 아직 끝나지 않은 task
+async fn list_task_blockers(
+    State(state): State<AppState>,
+    Path(task_id): Path<i64>,
+    _access: BoardViewer,
+) -> Result<Json<Vec<BlockingTask>>, ApiError> {
+    let mut conn = state.db.acquire().await?;
+    Ok(Json(links::open_blockers(&mut conn, task_id).await?))
+}
+
+// ─── Task History ─────────────────────────────────────────────────────────────
+
+/// 상태/단계 전이 기록 (들어간 시각, 나간 시각)
+async fn list_task_transitions(
+    State(state): State<AppState>,
+    Path(task_id): Path<i64>,
+    _access: BoardViewer,
+) -> Json<Vec<TaskStageTransition>> {
+    let transitions = sqlx::query_as::<_, TaskStageTransition>(
+        "SELECT * FROM task_stage_transitions WHERE task_id = ? ORDER BY id ASC"
+    )
+    .bind(task_id)
+    .fetch_all(&state.db)
+    .await
+    .unwrap_or_default();
+    Json(transitions)
+}
+
+async fn list_task_history(
+    State(state): State<AppState>,
+    Path(task_id): Path<i64>,
+    _access: BoardViewer,
+) -> Json<Vec<TaskHistory>> {
+    let history = sqlx::query_as::<_, TaskHistory>(
+        "SELECT * FROM task_history WHERE task_id = ? ORDER BY created_at ASC, id ASC"
+    )
+    .bind(task_id)
+    .fetch_all(&state.db)
+    .await
+    .unwrap_or_default();
+    Json(history)
+}
+
+/// board에서 지운 task 기록 (최근 것부터)
+async fn list_deleted_tasks(
+    State(state): State<AppState>,
+    BoardAccess { board, .. }: BoardViewer,
+) -> Result<Json<Vec<TaskDeletion>>, ApiError> {
+    let deletions = sqlx::query_as::<_, TaskDeletion>(
+        "SELECT * FROM task_deletions WHERE board_id = ? ORDER BY created_at DESC, id DESC"
+    )
+    .bind(board.id)
+    .fetch_all(&state.db)
+    .await?;
+    Ok(Json(deletions))
+}
+
+// ─── Board ───────────────────────────────────────────────────────────────────
+
+/// 상태/단계별 현재 카드 수와 WIP 한도
+async fn get_board_summary(
+    State(state): State<AppState>,
+    BoardAccess { board, .. }: BoardViewer,
+) -> Result<Json<BoardSummary>, ApiError> {
+    Ok(Json(workflow::board_summary(&state.db, board.id).await?))
+}
+
+async fn list_wip_limits(
+    State(state): State<AppState>,
+    BoardAccess { board, .. }: BoardViewer,
+) -> Json<Vec<WipLimit>> {
+    let limits = sqlx::query_as::<_, WipLimit>("SELECT scope, wip_limit FROM wip_limits WHERE board_id = ? ORDER BY scope")
+        .bind(board.id)
+        .fetch_all(&state.db)
+        .await
+        .unwrap_or_default();
+    Json(limits)
+}
+
+/// WIP 한도 설정 (wip_limit = null이면 해제)
+async fn set_wip_limit(
+    State(state): State<AppState>,
+    BoardAccess { user, board, .. }: BoardAdmin,
+    Json(req): Json<SetWipLimitRequest>,
+) -> Result<Json<bool>, ApiError> {
+    if !workflow::is_wip_scope(&req.scope) {
+        return Err(ApiError::bad_request(format!(
+            "WIP limits apply to InProgress or one of {:?}, not {}",
+            workflow::STAGES, req.scope
+        )));
+    }
+    match req.wip_limit {
+        Some(limit) if limit < 0 => {
+            return Err(ApiError::bad_request("wip_limit must be >= 0"));
+        }
+        Some(limit) => {
+            sqlx::query(
+                "INSERT INTO wip_limits (board_id, scope, wip_limit) VALUES (?, ?, ?)
+                 ON CONFLICT(board_id, scope) DO UPDATE SET wip_limit = excluded.wip_limit"
+            )
+            .bind(board.id)
+            .bind(&req.scope)
+            .bind(limit)
+            .execute(&state.db)
+            .await?;
+        }
+        None => {
+            sqlx::query("DELETE FROM wip_limits WHERE board_id = ? AND scope = ?")
+                .bind(board.id)
+                .bind(&req.scope)
+                .execute(&state.db)
+                .await?;
+        }
+    }
+    state.events.publish(events::BOARD_UPDATED, &board.key, None, Some(&user.username), serde_json::json!({ "wip_limit": req }));
+    Ok(Json(true))
+}
+
+// ─── Schedule ────────────────────────────────────────────────────────────────
+
+/// board의 critical path, task별 float, 프로젝트 예상 완료일.
+/// task_id + due_date를 주면 그 due_date 변경을 가정한 결과(what-if)를 함께 돌려준다.
+async fn get_schedule_analysis(
+    State(state): State<AppState>,
+    BoardAccess { board, .. }: BoardViewer,
+    Query(query): Query<ScheduleAnalysisQuery>,
+) -> Result<Json<ScheduleAnalysis>, ApiError> {
+    let what_if = match (query.task_id, query.due_date) {
+        (Some(task_id), Some(due_date)) => {
+            if fetch_task(&mut *state.db.acquire().await?, task_id).await?.board_id != board.id {
+                return Err(ApiError::not_found(format!("task {} not found on board {}", task_id, board.key)));
+            }
+            Some((task_id, due_date))
+        }
+        (None, None) => None,
+        _ => return Err(ApiError::bad_request("what-if needs both task_id and due_date")),
+    };
+    Ok(Json(analysis::analyze(&state.db, board.id, query.project_key.as_deref(), what_if).await?))
+}
+
+// ─── Holidays ────────────────────────────────────────────────────────────────
+
+async fn list_holidays(State(state): State<AppState>) -> Json<Vec<Holiday>> {
+    let holidays = sqlx::query_as::<_, Holiday>("SELECT * FROM holidays ORDER BY date")
+        .fetch_all(&state.db)
+        .await
+        .unwrap_or_default();
+    Json(holidays)
+}
+
+/// 휴일 추가/수정. 모든 task의 단계별 계획 완료일을 다시 계산한다.
+async fn put_holiday(
+    State(state): State<AppState>,
+    Path(date): Path<NaiveDate>,
+    user: CurrentUser,
+    Json(req): Json<SetHolidayRequest>,
+) -> Result<Json<bool>, ApiError> {
+    // 모든 board가 같이 쓰는 설정이므로 board admin이 아니라 전체 admin
+    user.require_admin()?;
+    let mut tx = state.db.begin().await?;
+    sqlx::query(
+        "INSERT INTO holidays (date, name) VALUES (?, ?)
+         ON CONFLICT(date) DO UPDATE SET name = excluded.name"
+    )
+    .bind(date.to_string())
+    .bind(&req.name)
+    .execute(&mut *tx)
+    .await?;
+    schedule::refresh_all_stage_deadlines(&mut tx).await?;
+    tx.commit().await?;
+    Ok(Json(true))
+}
+
+async fn delete_holiday(
+    State(state): State<AppState>,
+    Path(date): Path<NaiveDate>,
+    user: CurrentUser,
+) -> Result<Json<bool>, ApiError> {
+    user.require_admin()?;
+    let mut tx = state.db.begin().await?;
+    sqlx::query("DELETE FROM holidays WHERE date = ?")
+        .bind(date.to_string())
+        .execute(&mut *tx)
+        .await?;
+    schedule::refresh_all_stage_deadlines(&mut tx).await?;
+    tx.commit().await?;
+    Ok(Json(true))
+}
+
+// ─── Roll-up ─────────────────────────────────────────────────────────────────
+
+async fn list_rollup_rules(State(state): State<AppState>) -> Json<Vec<RollupRule>> {
+    let rules = sqlx::query_as::<_, RollupRule>("SELECT * FROM rollup_rules ORDER BY rule")
+        .fetch_all(&state.db)
+        .await
+        .unwrap_or_default();
+    Json(rules)
+}
+
+/// roll-up 규칙 켜기/끄기. 켤 때는 모든 상위 task에 바로 적용한다.
+async fn set_rollup_rule(
+    State(state): State<AppState>,
+    Path(rule): Path<String>,
+    user: CurrentUser,
+    Json(req): Json<SetRollupRuleRequest>,
+) -> Result<Json<bool>, ApiError> {
+    user.require_admin()?;
+    if !rollup::RULES.contains(&rule.as_str()) {
+        return Err(ApiError::not_found(format!("unknown roll-up rule: {}", rule)));
+    }
+
+    let mut tx = state.db.begin().await?;
+    sqlx::query("UPDATE rollup_rules SET enabled = ? WHERE rule = ?")
+        .bind(req.enabled)
+        .bind(&rule)
+        .execute(&mut *tx)
+        .await?;
+    let mut rolled_up = Vec::new();
+    if req.enabled {
+        // apply_from은 위쪽으로 올라가며 적용하므로 순서와 관계없이 전체가 반영된다
+        let parents: Vec<(i64,)> = sqlx::query_as(
+            "SELECT DISTINCT parent_task_id FROM tasks WHERE parent_task_id IS NOT NULL"
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+        for (parent,) in parents {
+            rolled_up.extend(rollup::apply_from(&mut tx, parent).await?);
+        }
+    }
+    tx.commit().await?;
+    publish_rollup_events(&state, &rolled_up).await;
+    Ok(Json(true))
+}
+
+/// roll-up 규칙과 맞지 않는 상위 task 목록
+/// 볼 수 있는 board의 상위 task만
+async fn list_rollup_violations(State(state): State<AppState>, user: CurrentUser) -> Result<Json<Vec<RollupViolation>>, ApiError> {
+    let mut conn = state.db.acquire().await?;
+    let boards = auth::viewable_boards(&mut conn, &user).await?;
+    Ok(Json(rollup::violations(&mut conn, boards.as_deref()).await?))
+}
+
+// ─── Workflows ───────────────────────────────────────────────────────────────
+
+async fn list_workflows(State(state): State<AppState>) -> Json<Vec<String>> {
+    let names: Vec<(String,)> = sqlx::query_as("SELECT name FROM workflows ORDER BY name")
+        .fetch_all(&state.db)
+        .await
+        .unwrap_or_default();
+    Json(names.into_iter().map(|(n,)| n).collect())
+}
+
+async fn list_workflow_transitions(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> Result<Json<Vec<TransitionRule>>, ApiError> {
+    let mut conn = state.db.acquire().await?;
+    Ok(Json(workflow::list_transitions(&mut conn, &name).await?))
+}
+
+/// workflow의 전이 규칙 전체를 교체 (없으면 workflow 생성). 빈 목록이면 모든 전이 허용.
+async fn replace_workflow_transitions(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    user: CurrentUser,
+    Json(rules): Json<Vec<TransitionRuleInput>>,
+) -> Result<Json<Vec<TransitionRule>>, ApiError> {
+    user.require_admin()?;
+    for rule in &rules {
+        workflow::validate_rule(rule)?;
+    }
+
+    let mut tx = state.db.begin().await?;
+    sqlx::query("INSERT OR IGNORE INTO workflows (name) VALUES (?)")
+        .bind(&name)
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query("DELETE FROM workflow_transitions WHERE workflow = ?")
+        .bind(&name)
+        .execute(&mut *tx)
+        .await?;
+    for rule in &rules {
+        sqlx::query(
+            "INSERT INTO workflow_transitions (workflow, from_state, to_state, required_fields, require_comment, allowed_roles)
+             VALUES (?, ?, ?, ?, ?, ?)"
+        )
+        .bind(&name)
+        .bind(&rule.from_state)
+        .bind(&rule.to_state)
+        .bind(&rule.required_fields)
+        .bind(rule.require_comment)
+        .bind(&rule.allowed_roles)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(ref db) if db.is_unique_violation() => ApiError::bad_request(format!(
+                "duplicate rule {} -> {}",
+                rule.from_state, rule.to_state
+            )),
+            e => e.into(),
+        })?;
+    }
+    let saved = workflow::list_transitions(&mut tx, &name).await?;
+    tx.commit().await?;
+
+    Ok(Json(saved))
 }
@@ -0,0 +1,290 @@
+use std::collections::{BTreeSet, HashMap};
+
+use pulldown_cmark::{CowStr, Event, Options, Parser, Tag, TagEnd, TextMergeStream};
+use sqlx::{SqliteConnection, SqlitePool};
+
+use crate::auth::{self, CurrentUser};
+use crate::jira;
+use crate::models::{Task, TaskComment};
+
+// ─────────────────────────────────────────────
+// description / comment Markdown → sanitize된 HTML
+//
+// - @user       → <span class="mention">
+// - #123        → task 링크 (사용자가 볼 수 있는 board의 task만, 아니면 그냥 텍스트)
+// - VPC-101     → 볼 수 있는 board에 있으면 task 링크, 없으면 Jira 링크
+// 코드와 기존 링크 안의 텍스트는 바꾸지 않는다.
+// ─────────────────────────────────────────────
+
+/// 참조를 링크로 바꿀 때 쓰는 task 목록 (요청마다 한 번 읽음)
+pub struct Refs {
+    titles: HashMap<i64, String>,
+    by_jira_key: HashMap<String, i64>,
+}
+
+/// texts에 나온 #id / Jira 키 중 user가 볼 수 있는 board의 task만 읽는다
+pub async fn load_refs(conn: &mut SqliteConnection, user: &CurrentUser, texts: &[&str]) -> Result<Refs, sqlx::Error> {
+    let mut ids = BTreeSet::new();
+    let mut keys = BTreeSet::new();
+    for text in texts {
+        for token in tokenize(text) {
+            match token {
+                Token::TaskRef(id, _) => {
+                    ids.insert(id);
+                }
+                Token::JiraKey(key) => {
+                    keys.insert(key);
+                }
+                Token::Text(_) | Token::Mention(_) => {}
+            }
+        }
+    }
+    if ids.is_empty() && keys.is_empty() {
+        return Ok(Refs { titles: HashMap::new(), by_jira_key: HashMap::new() });
+    }
+    let boards = auth::viewable_boards(conn, user)
+        .await?
+        .map(|keys| serde_json::to_string(&keys).unwrap_or_default());
+    let rows: Vec<(i64, String, Option<String>)> = sqlx::query_as(
+        "SELECT t.id, t.title, t.jira_ticket_key
+           FROM tasks t JOIN boards b ON b.id = t.board_id
+          WHERE (t.id IN (SELECT value FROM json_each(?1))
+                 OR t.jira_ticket_key IN (SELECT value FROM json_each(?2)))
+            AND (?3 IS NULL OR b.key IN (SELECT value FROM json_each(?3)))"
+    )
+    .bind(serde_json::to_string(&ids).unwrap_or_default())
+    .bind(serde_json::to_string(&keys).unwrap_or_default())
+    .bind(boards)
+    .fetch_all(&mut *conn)
+    .await?;
+    let mut refs = Refs { titles: HashMap::new(), by_jira_key: HashMap::new() };
+    for (id, title, key) in rows {
+        if let Some(key) = key {
+            refs.by_jira_key.insert(key, id);
+        }
+        refs.titles.insert(id, title);
+    }
+    Ok(refs)
+}
+
+enum Token<'a> {
+    Text(&'a str),
+    Mention(&'a str),
+    TaskRef(i64, &'a str),
+    JiraKey(&'a str),
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+fn is_name_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '_' | '.' | '-')
+}
+
+/// "VPC-101" 형태 (대문자로 시작하는 프로젝트 키 + '-' + 숫자)
+fn jira_key_len(s: &str) -> Option<usize> {
+    let bytes = s.as_bytes();
+    let mut i = 0;
+    if !bytes.first()?.is_ascii_uppercase() {
+        return None;
+    }
+    while i < bytes.len() && (bytes[i].is_ascii_uppercase() || bytes[i].is_ascii_digit()) {
+        i += 1;
+    }
+    if i < 2 || bytes.get(i) != Some(&b'-') {
+        return None;
+    }
+    let digits = bytes[i + 1..].iter().take_while(|b| b.is_ascii_digit()).count();
+    (digits > 0).then_some(i + 1 + digits)
+}
+
+/// 텍스트를 일반 텍스트와 mention/참조로 나눈다.
+/// 앞 글자가 단어 문자면 (이메일 주소 등) 참조로 보지 않는다.
+fn tokenize(text: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    let mut plain_start = 0;
+    let mut prev: Option<char> = None;
+    let mut iter = text.char_indices().peekable();
+
+    while let Some((i, c)) = iter.next() {
+        let at_boundary = !prev.is_some_and(is_word_char);
+        prev = Some(c);
+        if !at_boundary {
+            continue;
+        }
+        let rest = &text[i..];
+        let len = match c {
+            '@' => {
+                // 끝의 '.'/'-'는 문장 부호로 본다
+                let name = rest[1..]
+                    .char_indices()
+                    .take_while(|&(_, c)| is_name_char(c))
+                    .last()
+                    .map_or(0, |(j, c)| j + c.len_utf8());
+                let name = rest[1..1 + name].trim_end_matches(['.', '-']);
+                (!name.is_empty()).then(|| (Token::Mention(name), 1 + name.len()))
+            }
+            '#' => {
+                let digits = rest[1..].bytes().take_while(|b| b.is_ascii_digit()).count();
+                rest[1..1 + digits]
+                    .parse()
+                    .ok()
+                    .filter(|_| !rest[1 + digits..].starts_with(is_word_char))
+                    .map(|id| (Token::TaskRef(id, &rest[..1 + digits]), 1 + digits))
+            }
+            _ => jira_key_len(rest)
+                .filter(|&n| !rest[n..].starts_with(is_word_char))
+                .map(|n| (Token::JiraKey(&rest[..n]), n)),
+        };
+
+        if let Some((token, len)) = len {
+            if plain_start < i {
+                tokens.push(Token::Text(&text[plain_start..i]));
+            }
+            tokens.push(token);
+            plain_start = i + len;
+            while iter.peek().is_some_and(|&(j, _)| j < plain_start) {
+                prev = iter.next().map(|(_, c)| c);
+            }
+        }
+    }
+    if plain_start < text.len() {
+        tokens.push(Token::Text(&text[plain_start..]));
+    }
+    tokens
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+fn task_link(id: i64, label: &str, refs: &Refs) -> String {
+    format!(
+        "<a href=\"#task-{}\" class=\"task-ref\" data-task-id=\"{}\" title=\"{}\">{}</a>",
+        id,
+        id,
+        escape(&refs.titles[&id]),
+        escape(label)
+    )
+}
+
+fn reference_html(token: &Token, refs: &Refs) -> Option<String> {
+    match *token {
+        Token::Text(_) => None,
+        Token::Mention(name) => Some(format!("<span class=\"mention\">@{}</span>", escape(name))),
+        Token::TaskRef(id, label) => refs.titles.contains_key(&id).then(|| task_link(id, label, refs)),
+        Token::JiraKey(key) => Some(match refs.by_jira_key.get(key) {
+            Some(&id) => task_link(id, key, refs),
+            None => format!("<a href=\"{}{}\" class=\"jira-ref\">{}</a>", jira::BROWSE_URL, key, key),
+        }),
+    }
+}
+
+fn sanitizer() -> ammonia::Builder<'static> {
+    let mut builder = ammonia::Builder::default();
+    builder
+        .add_tag_attributes("a", ["class", "title", "data-task-id"])
+        .add_tag_attributes("span", ["class"])
+        .add_tag_attributes("input", ["type", "checked", "disabled"])
+        .add_tags(["input"]);
+    builder
+}
+
+pub fn render(text: &str, refs: &Refs) -> String {
+    let mut options = Options::empty();
+    options.insert(Options::ENABLE_TABLES);
+    options.insert(Options::ENABLE_STRIKETHROUGH);
+    options.insert(Options::ENABLE_TASKLISTS);
+
+    let mut in_link = 0usize;
+    let mut in_code = 0usize;
+    let mut events = Vec::new();
+    for event in TextMergeStream::new(Parser::new_ext(text, options)) {
+        match &event {
+            Event::Start(Tag::Link { .. }) => in_link += 1,
+            Event::End(TagEnd::Link) => in_link = in_link.saturating_sub(1),
+            Event::Start(Tag::CodeBlock(_)) => in_code += 1,
+            Event::End(TagEnd::CodeBlock) => in_code = in_code.saturating_sub(1),
+            Event::Text(t) if in_link == 0 && in_code == 0 => {
+                for token in tokenize(t) {
+                    events.push(match reference_html(&token, refs) {
+                        Some(html) => Event::InlineHtml(CowStr::from(html)),
+                        None => Event::Text(CowStr::from(match token {
+                            Token::Text(s) | Token::Mention(s) | Token::TaskRef(_, s) | Token::JiraKey(s) => s.to_string(),
+                        })),
+                    });
+                }
+                continue;
+            }
+            _ => {}
+        }
+        events.push(event);
+    }
+
+    let mut html = String::new();
+    pulldown_cmark::html::push_html(&mut html, events.into_iter());
+    sanitizer().clean(&html).to_string()
+}
+
+/// 텍스트에 나온 @user 이름들 (코드 안 제외, 중복 제거)
+pub fn mentions(text: &str) -> BTreeSet<String> {
+    let mut in_code = 0usize;
+    let mut names = BTreeSet::new();
+    for event in TextMergeStream::new(Parser::new(text)) {
+        match &event {
+            Event::Start(Tag::CodeBlock(_)) => in_code += 1,
+            Event::End(TagEnd::CodeBlock) => in_code = in_code.saturating_sub(1),
+            Event::Text(t) if in_code == 0 => {
+                names.extend(tokenize(t).into_iter().filter_map(|t| match t {
+                    Token::Mention(name) => Some(name.to_string()),
+                    _ => None,
+                }));
+            }
+            _ => {}
+        }
+    }
+    names
+}
+
+/// comment의 mention 목록을 다시 저장 (작성/수정/삭제 시)
+pub async fn save_mentions(conn: &mut SqliteConnection, comment: &TaskComment) -> Result<(), sqlx::Error> {
+    sqlx::query("DELETE FROM comment_mentions WHERE comment_id = ?")
+        .bind(comment.id)
+        .execute(&mut *conn)
+        .await?;
+    if comment.deleted_at.is_some() {
+        return Ok(());
+    }
+    for name in mentions(&comment.content) {
+        sqlx::query("INSERT INTO comment_mentions (comment_id, task_id, username) VALUES (?, ?, ?)")
+            .bind(comment.id)
+            .bind(comment.task_id)
+            .bind(name)
+            .execute(&mut *conn)
+            .await?;
+    }
+    Ok(())
+}
+
+pub async fn annotate_tasks(db: &SqlitePool, user: &CurrentUser, tasks: &mut [Task]) -> Result<(), sqlx::Error> {
+    let texts: Vec<&str> = tasks.iter().filter_map(|t| t.description.as_deref()).collect();
+    let refs = load_refs(&mut *db.acquire().await?, user, &texts).await?;
+    for task in tasks.iter_mut() {
+        task.description_html = task.description.as_deref().map(|d| render(d, &refs));
+    }
+    Ok(())
+}
+
+pub async fn annotate_comments(
+    conn: &mut SqliteConnection,
+    user: &CurrentUser,
+    comments: &mut [TaskComment],
+) -> Result<(), sqlx::Error> {
+    let texts: Vec<&str> = comments.iter().filter(|c| c.deleted_at.is_none()).map(|c| c.content.as_str()).collect();
+    let refs = load_refs(conn, user, &texts).await?;
+    for comment in comments.iter_mut().filter(|c| c.deleted_at.is_none()) {
+        comment.content_html = render(&comment.content, &refs);
+    }
+    Ok(())
+}
@@ -1,4 +1,4 @@
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 use sqlx::FromRow;
 use chrono::{DateTime, Utc, NaiveDate};
 
@@ -21,6 +21,14 @@ pub struct JiraTicket {
     pub due_date: Option<NaiveDate>,
     pub created_at: DateTime<Utc>,
     pub jira_url: Option<String>,
+    #[serde(default)]
+    pub issue_links: Vec<JiraIssueLink>, // "VPC-203 blocks VPC-204" 같은 issue link
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct JiraIssueLink {
+    pub link_type: String,     // "blocks" | "relates_to" | "duplicates"
+    pub target_key: String,    // 이 티켓이 link_type 하는 대상 티켓
 }
 
 // ─────────────────────────────────────────────
@@ -44,10 +52,109 @@ pub struct Task {
     pub start_date: Option<NaiveDate>,
     pub due_date: Option<NaiveDate>,
     pub jira_url: Option<String>,
+    pub sort_order: i64,             // 형제 task 사이의 순서
+    pub board_id: i64,
+    pub version: i64,                // 변경될 때마다 1씩 증가 (낙관적 동시성 제어)
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+
+    // 서버에서 계산하는 값 (schedule::annotate)
+    #[sqlx(skip)]
+    #[serde(default)]
+    pub ideal_progress: i64,         // 0~100, 일정상 지금쯤 되어 있어야 할 진행도
+    #[sqlx(skip)]
+    #[serde(default)]
+    pub actual_progress: i64,        // 0~100, 현재 단계 기준 진행도
+    #[sqlx(skip)]
+    #[serde(default)]
+    pub days_in_stage: Option<i64>,  // 현재 상태/단계에 머문 일 수
+    #[sqlx(skip)]
+    #[serde(default)]
+    pub is_late: bool,
+    #[sqlx(skip)]
+    #[serde(default)]
+    pub stage_deadlines: Vec<StageDeadline>, // due_date 기준 단계별 계획 완료일 (영업일)
+    #[sqlx(skip)]
+    #[serde(default)]
+    pub stage_overdue: bool,         // 현재 단계의 계획 완료일이 지남
+
+    // description의 Markdown을 sanitize한 HTML (markdown::annotate_tasks)
+    #[sqlx(skip)]
+    #[serde(default)]
+    pub description_html: Option<String>,
+
+    // include_ancestors로 트리를 유지하려고 넣은 상위 task (조건에는 맞지 않음)
+    #[sqlx(skip)]
+    #[serde(default)]
+    pub context_only: bool,
+}
+
+// 트리 구조 응답 (GET /api/tasks/tree)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TaskNode {
+    #[serde(flatten)]
+    pub task: Task,
+    pub depth: i64,                  // 조회 root 기준 깊이 (root = 0)
+    pub children: Vec<TaskNode>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct StageDeadline {
+    pub stage: String,               // stage 이름 또는 "Done"
+    pub planned_date: NaiveDate,
+}
+
+// ─────────────────────────────────────────────
+// Board: 팀별 kanban (task는 한 board에 속함)
+// ─────────────────────────────────────────────
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct Board {
+    pub id: i64,
+    pub key: String,                 // URL에 쓰는 이름 (/api/boards/:board/...)
+    pub name: String,
+    pub jira_project: Option<String>, // backlog 조회 기본 프로젝트
+    pub workflow: String,            // task 전이 규칙에 쓰는 workflow 이름
+    pub created_at: DateTime<Utc>,
+}
+
+// board 목록: 로그인한 사용자의 역할 포함 (GET /api/boards)
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BoardWithRole {
+    #[serde(flatten)]
+    pub board: Board,
+    pub role: String,
+}
+
+// ─────────────────────────────────────────────
+// 저장된 view: 이름 붙인 task 목록 조회 조건 (본인만 보거나 board에 공유)
+// ─────────────────────────────────────────────
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct SavedView {
+    pub id: i64,
+    pub board_id: i64,
+    pub board: String,               // board key
+    pub owner_id: i64,
+    pub owner: String,               // 만든 사용자 username
+    pub name: String,
+    pub shared: bool,                // true면 board를 볼 수 있는 모든 사용자에게 보임
+    #[sqlx(json)]
+    pub query: TaskListQuery,        // GET /api/tasks 쿼리와 같은 필터/정렬 (assignee "@me" = 보는 사용자)
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
+// ─────────────────────────────────────────────
+// 휴일: 단계별 계획 완료일 계산 시 주말과 함께 제외
+// ─────────────────────────────────────────────
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct Holiday {
+    pub date: NaiveDate,
+    pub name: Option<String>,
+}
+
 // ─────────────────────────────────────────────
 // Task Comment
 // ─────────────────────────────────────────────
@@ -57,15 +164,236 @@ pub struct TaskComment {
     pub id: i64,
     pub task_id: i64,
     pub author: String,
+    pub content: String,             // 삭제된 comment는 빈 문자열 (tombstone)
+    pub attachments: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub edited_at: Option<DateTime<Utc>>,
+    pub deleted_at: Option<DateTime<Utc>>,
+    pub deleted_by: Option<String>,
+    #[sqlx(skip)]
+    #[serde(default)]
+    pub content_html: String,        // content의 Markdown을 sanitize한 HTML
+    #[sqlx(skip)]
+    #[serde(default)]
+    pub files: Vec<Attachment>,      // 업로드된 첨부 (attachments는 예전 URL 방식)
+}
+
+// comment 첨부 파일 (내용은 uploads/ 아래 sha256 이름으로 저장)
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct Attachment {
+    pub id: i64,
+    pub task_id: i64,
+    pub comment_id: Option<i64>,     // 업로드 후 comment 작성 전에는 NULL
+    pub sha256: String,
+    pub file_name: String,
+    pub content_type: String,
+    pub size: i64,
+    pub uploaded_by: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+// comment 수정/삭제 전 내용
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct TaskCommentRevision {
+    pub id: i64,
+    pub comment_id: i64,
     pub content: String,
     pub attachments: Option<String>,
+    pub edited_by: Option<String>,
+    pub created_at: DateTime<Utc>,   // 이 내용이 교체된 시각
+}
+
+// ─────────────────────────────────────────────
+// Task 단계 전이 기록: to_* 상태에 들어간 시각과 나간 시각
+// ─────────────────────────────────────────────
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct TaskStageTransition {
+    pub id: i64,
+    pub task_id: i64,
+    pub from_status: Option<String>,
+    pub from_stage: Option<String>,
+    pub to_status: String,
+    pub to_stage: Option<String>,
+    pub actor: Option<String>,
+    pub entered_at: DateTime<Utc>,
+    pub exited_at: Option<DateTime<Utc>>, // 아직 머물러 있으면 null
+}
+
+// ─────────────────────────────────────────────
+// Task Link: task 간 의존 관계 (source blocks target 등)
+// ─────────────────────────────────────────────
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct TaskLink {
+    pub id: i64,
+    pub source_task_id: i64,
+    pub target_task_id: i64,
+    pub link_type: String,           // "blocks" | "relates_to" | "duplicates"
+    pub created_at: DateTime<Utc>,
+}
+
+// task를 막고 있는 (Done이 아닌) task
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct BlockingTask {
+    pub id: i64,
+    pub title: String,
+    pub status: String,
+    pub stage: Option<String>,
+    pub jira_ticket_key: Option<String>,
+}
+
+// ─────────────────────────────────────────────
+// Task History: WIP override 등 task에 대한 기록
+// ─────────────────────────────────────────────
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct TaskHistory {
+    pub id: i64,
+    pub task_id: i64,
+    pub action: String,          // "wip_override" 등
+    pub detail: Option<String>,  // JSON
+    pub actor: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct TaskDeletion {
+    pub id: i64,
+    pub task_id: i64,                // 지워진 task (더 이상 없음)
+    pub board_id: i64,
+    pub title: String,
+    pub action: String,              // "delete" | "bulk_delete"
+    pub detail: Option<String>,      // JSON: 지우기 직전의 task
+    pub actor: Option<String>,
     pub created_at: DateTime<Utc>,
 }
 
+// ─────────────────────────────────────────────
+// Roll-up 규칙: 하위 task → 상위 task 상태/일정/진행도 반영
+// ─────────────────────────────────────────────
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct RollupRule {
+    pub rule: String,
+    pub enabled: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RollupViolation {
+    pub task_id: i64,                // 규칙과 맞지 않는 상위 task
+    pub title: String,
+    pub rule: String,
+    pub enabled: bool,               // 규칙이 켜져 있는지 (꺼져 있으면 자동 반영 안 됨)
+    pub message: String,
+}
+
+// ─────────────────────────────────────────────
+// 일정 분석 (GET /api/schedule/analysis): critical path, float, what-if
+// ─────────────────────────────────────────────
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TaskSchedule {
+    pub task_id: i64,
+    pub title: String,
+    pub status: String,
+    pub parent_task_id: Option<i64>,
+    pub project_key: Option<String>,
+    pub planned_start: NaiveDate,
+    pub planned_finish: Option<NaiveDate>,      // 실효 due_date (상위 task 상속 포함)
+    pub projected_start: Option<NaiveDate>,     // Done이면 null
+    pub projected_finish: Option<NaiveDate>,
+    pub latest_finish: Option<NaiveDate>,       // 상위/프로젝트 due를 지키기 위한 최종 완료일
+    pub float_days: Option<i64>,                // latest_finish - projected_finish
+    pub critical: bool,                         // float <= 0
+    pub delays_parent: bool,                    // 예상 완료가 상위 task due보다 늦음
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ProjectSchedule {
+    pub project_key: Option<String>,
+    pub due_date: Option<NaiveDate>,            // 프로젝트 안 task due 중 가장 늦은 날
+    pub projected_finish: Option<NaiveDate>,
+    pub is_late: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ProjectedChange {
+    pub task_id: i64,
+    pub projected_finish_before: Option<NaiveDate>,
+    pub projected_finish_after: Option<NaiveDate>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct WhatIfResult {
+    pub task_id: i64,
+    pub due_date: NaiveDate,
+    pub projects_before: Vec<ProjectSchedule>,
+    pub projects_after: Vec<ProjectSchedule>,
+    pub changed: Vec<ProjectedChange>,          // 예상 완료일이 바뀌는 task
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ScheduleAnalysis {
+    pub tasks: Vec<TaskSchedule>,
+    pub critical_path: Vec<i64>,                // 가장 늦게 끝나는 critical task까지 이어진 critical 선행 task 사슬 (앞에서부터)
+    pub projects: Vec<ProjectSchedule>,
+    pub skipped: Vec<i64>,                      // 의존 관계 사이클로 분석에서 빠진 task
+    pub what_if: Option<WhatIfResult>,
+}
+
+// ─────────────────────────────────────────────
+// Board summary: 상태/단계별 카드 수와 WIP 한도
+// ─────────────────────────────────────────────
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct WipLimit {
+    pub scope: String,
+    pub wip_limit: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BoardColumn {
+    pub scope: String,           // "Pending" | "InProgress" | stage | "Done"
+    pub count: i64,
+    pub wip_limit: Option<i64>,
+    pub over_limit: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BoardSummary {
+    pub columns: Vec<BoardColumn>,
+}
+
+// ─────────────────────────────────────────────
+// Workflow 전이 규칙: from_state → to_state
+// state = "Pending" | "Done" | stage 이름 | "InProgress"(stage 없음), from_state는 "*" 가능
+// ─────────────────────────────────────────────
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct TransitionRule {
+    pub id: i64,
+    pub workflow: String,
+    pub from_state: String,
+    pub to_state: String,
+    pub required_fields: Option<String>, // 쉼표 구분: "assignee,due_date"
+    pub require_comment: bool,           // 전이 시 comment 필수 (되돌리기 등)
+    pub allowed_roles: Option<String>,   // 쉼표 구분, 비어 있으면 제한 없음
+}
+
 // ─────────────────────────────────────────────
 // Request / Response DTOs
 // ─────────────────────────────────────────────
 
+/// PATCH 필드용: 필드가 없으면 (serde default) None, null이면 Some(None), 값이면 Some(Some(v))
+fn double_option<'de, T, D>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct JiraQuery {
     pub project: Option<String>,
@@ -79,6 +407,8 @@ pub struct JiraQuery {
 pub struct AddToKanbanRequest {
     pub ticket_keys: Vec<String>,    // 선택된 Jira 티켓 키들
     pub include_subtasks: bool,      // contain된 하위 티켓도 포함할지
+    #[serde(default)]
+    pub include_links: bool,         // Jira issue link도 task link로 가져올지
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -97,20 +427,500 @@ pub struct CreateTaskRequest {
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct UpdateTaskRequest {
+    // PATCH 의미: 필드가 없으면 그대로, null이면 지움 (Option<Option<_>> 필드)
     pub title: Option<String>,
-    pub description: Option<String>,
+    #[serde(default, deserialize_with = "double_option")]
+    pub description: Option<Option<String>>,
     pub status: Option<String>,
     pub stage: Option<String>,
-    pub assignee: Option<String>,
-    pub alias: Option<String>,
-    pub tags: Option<String>,
-    pub keywords: Option<String>,
-    pub start_date: Option<NaiveDate>,
-    pub due_date: Option<NaiveDate>,
+    #[serde(default, deserialize_with = "double_option")]
+    pub assignee: Option<Option<String>>,
+    #[serde(default, deserialize_with = "double_option")]
+    pub alias: Option<Option<String>>,
+    #[serde(default, deserialize_with = "double_option")]
+    pub tags: Option<Option<String>>,
+    #[serde(default, deserialize_with = "double_option")]
+    pub keywords: Option<Option<String>>,
+    #[serde(default, deserialize_with = "double_option")]
+    pub start_date: Option<Option<NaiveDate>>,
+    #[serde(default, deserialize_with = "double_option")]
+    pub due_date: Option<Option<NaiveDate>>,
+    #[serde(default, deserialize_with = "double_option")]
+    pub project_key: Option<Option<String>>,
+    #[serde(default, deserialize_with = "double_option")]
+    pub jira_url: Option<Option<String>>,
+    #[serde(default, deserialize_with = "double_option")]
+    pub parent_task_id: Option<Option<i64>>, // null이면 최상위로 (같은 board 안에서만)
+    pub comment: Option<String>,     // 상태 전이 시 남길 comment
+    pub version: Option<i64>,        // If-Match 헤더 대신 쓸 수 있음
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct UpdateStatusRequest {
     pub status: String,
     pub stage: Option<String>,
+    #[serde(rename = "override", default)]
+    pub override_limits: bool,       // WIP 한도/blocker를 무시하고 이동 (reason 필수)
+    pub reason: Option<String>,
+    pub comment: Option<String>,     // 전이 규칙이 comment를 요구할 때
+    pub version: Option<i64>,        // If-Match 헤더 대신 쓸 수 있음
+}
+
+// 여러 task에 한 번에 적용 (POST /api/tasks/bulk). task_ids와 filter 중 하나만.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BulkTaskRequest {
+    pub task_ids: Option<Vec<i64>>,
+    pub filter: Option<TaskFilter>,
+    pub operation: BulkOperation,
+}
+
+// 조건에 맞는 board의 task (모든 조건 AND, task_query::push_filter)
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+#[serde(default)]
+pub struct TaskFilter {
+    pub status: Option<String>,
+    pub stage: Option<String>,
+    pub assignee: Option<String>,
+    pub project_key: Option<String>,
+    pub tag: Option<String>,             // tags 중 하나와 정확히 일치
+    pub due_before: Option<NaiveDate>,   // due_date <= (포함)
+    pub due_after: Option<NaiveDate>,    // due_date >= (포함)
+    pub due_within: Option<i64>,         // 오늘부터 N일 안에 마감 (저장된 view의 "이번 주 마감" 등)
+    pub overdue: Option<bool>,           // Done이 아닌데 due_date가 지남
+    pub has_jira: Option<bool>,          // jira_ticket_key 유무
+    #[serde(alias = "parent")]
+    pub parent_task_id: Option<i64>,
+    pub q: Option<String>,               // 제목/설명/alias/keywords/Jira 키 부분 일치
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum BulkOperation {
+    SetStatus(UpdateStatusRequest),
+    SetAssignee { assignee: Option<String> },
+    ShiftDates { days: i64 },                // start_date/due_date를 N일 이동 (음수면 앞으로)
+    AddTags { tags: Vec<String> },
+    RemoveTags { tags: Vec<String> },
+    Delete,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BulkTaskResult {
+    pub task_id: i64,
+    pub ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<serde_json::Value>,  // 실패한 경우의 에러 body
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TransitionRuleInput {
+    pub from_state: String,
+    pub to_state: String,
+    pub required_fields: Option<String>,
+    #[serde(default)]
+    pub require_comment: bool,
+    pub allowed_roles: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SetWipLimitRequest {
+    pub scope: String,               // "InProgress" 또는 stage 이름
+    pub wip_limit: Option<i64>,      // null이면 한도 해제
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateBoardRequest {
+    pub key: String,
+    pub name: String,
+    pub jira_project: Option<String>,
+    pub workflow: Option<String>,    // 기본 "default"
+}
+
+// CSV 가져오기 결과: 오류가 있으면 아무것도 추가하지 않는다
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ImportResult {
+    pub dry_run: bool,
+    pub created: usize,              // 새로 만든 (만들) task 수
+    pub skipped: usize,              // jira_ticket_key가 이미 있어 건너뛴 행 수
+    pub errors: Vec<ImportRowError>,
+    pub tree: Vec<ImportNode>,       // 가져온 (가져올) task 트리
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ImportRowError {
+    pub line: u64,                   // CSV 줄 번호 (header = 1)
+    pub message: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ImportNode {
+    pub line: u64,
+    pub row_id: Option<String>,
+    pub title: String,
+    pub jira_ticket_key: Option<String>,
+    pub action: String,              // "create" | "skip"
+    pub task_id: Option<i64>,        // skip이면 기존 task, 가져온 뒤면 새 task
+    pub parent_task_id: Option<i64>, // board의 기존 task 아래에 붙는 경우
+    pub children: Vec<ImportNode>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateViewRequest {
+    pub name: String,
+    #[serde(default)]
+    pub shared: bool,
+    #[serde(default)]
+    pub query: TaskListQuery,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateViewRequest {
+    pub name: Option<String>,
+    pub shared: Option<bool>,
+    pub query: Option<TaskListQuery>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateBoardRequest {
+    pub name: Option<String>,
+    pub jira_project: Option<String>,
+    pub workflow: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MoveToBoardRequest {
+    pub board: String,               // 옮길 board key (subtree 전체가 함께 이동)
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SetHolidayRequest {
+    pub name: Option<String>,
+}
+
+// GET /api/tasks 쿼리의 정렬/페이지 부분 (필터는 TaskFilter로 따로 읽는다)
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+pub struct TaskPageQuery {
+    pub sort: Option<String>,            // tree(기본)|id|title|status|assignee|start_date|due_date|created_at|updated_at, '-'면 내림차순
+    pub limit: Option<i64>,              // 없으면 전부
+    pub cursor: Option<i64>,             // 이전 페이지의 X-Next-Cursor (마지막 task id)
+    #[serde(default)]
+    pub include_ancestors: bool,         // 조건에 맞는 task의 상위 task도 포함 (트리 유지용)
+}
+
+// task 목록 조건 전체. 저장된 view의 query JSON은 두 부분을 한 object에 펼친 모양
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+pub struct TaskListQuery {
+    #[serde(flatten)]
+    pub filter: TaskFilter,
+    #[serde(flatten)]
+    pub page: TaskPageQuery,
+}
+
+// GET /api/tasks/export: 나머지 쿼리는 TaskFilter와 TaskPageQuery (limit/cursor는 무시하고 전부)
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExportQuery {
+    pub format: Option<String>,      // "csv"(기본) | "xlsx"
+}
+
+// POST /api/boards/restore?board=<key>: 없으면 archive의 board key
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RestoreQuery {
+    pub board: Option<String>,
+}
+
+// POST /api/tasks/import?dry_run=true: 검사와 미리보기만
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ImportQuery {
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+// 저장된 view 실행 (GET /api/views/:id/tasks): 페이지만 바꿀 수 있다
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ViewPageQuery {
+    pub limit: Option<i64>,
+    pub cursor: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TaskTreeQuery {
+    pub root: Option<i64>,           // 없으면 최상위 task 전체
+    pub max_depth: Option<i64>,      // root = 0
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MoveTaskRequest {
+    pub parent_task_id: Option<i64>, // null이면 최상위로 이동
+    pub position: Option<usize>,     // 새 형제들 사이의 위치 (없으면 맨 뒤)
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SetRollupRuleRequest {
+    pub enabled: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateTaskLinkRequest {
+    pub target_task_id: i64,         // 이 task가 link_type 하는 대상
+    pub link_type: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ScheduleAnalysisQuery {
+    pub project_key: Option<String>,
+    pub task_id: Option<i64>,        // what-if: 이 task의 due_date를
+    pub due_date: Option<NaiveDate>, // 이 날짜로 바꾸면? (저장하지 않음)
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateCommentRequest {
+    pub content: String,
+    pub attachments: Option<String>,
+    #[serde(default)]
+    pub attachment_ids: Vec<i64>,    // POST /api/tasks/:id/attachments 로 올린 파일
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateCommentRequest {
+    pub content: String,
+    pub attachments: Option<String>,
+}
+
+// 나를 mention한 comment (GET /api/mentions)
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct MentionedComment {
+    pub comment_id: i64,
+    pub task_id: i64,
+    pub task_title: String,
+    pub author: String,
+    pub content: String,
+    pub created_at: DateTime<Utc>,
+    pub edited_at: Option<DateTime<Utc>>,
+    #[sqlx(skip)]
+    #[serde(default)]
+    pub content_html: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EventsQuery {
+    pub board: Option<String>,       // board key (없으면 모든 board)
+    pub last_event_id: Option<u64>,  // Last-Event-ID 헤더 대신 쓸 수 있음
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MentionQuery {
+    pub user: Option<String>,        // 없으면 로그인한 사용자 (다른 사용자는 admin만)
+}
+
+// ─────────────────────────────────────────────
+// User: 로그인 계정
+// ─────────────────────────────────────────────
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct User {
+    pub id: i64,
+    pub username: String,
+    pub display_name: Option<String>,
+    #[serde(skip)]
+    pub password_hash: String,
+    pub role: String,                // "admin" | "member" (전이 규칙의 allowed_roles는 board 역할과 비교)
+    pub disabled: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+// board 구성원과 역할
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct BoardMembership {
+    pub board: String,
+    pub user_id: i64,
+    pub username: String,
+    pub display_name: Option<String>,
+    pub role: String,                // "viewer" | "member" | "admin"
+}
+
+// /api/board/members/:user_id와 /api/boards/:board/members/:user_id 둘 다에서 쓰도록 이름으로 꺼낸다
+#[derive(Debug, Deserialize)]
+pub struct MemberPath {
+    pub user_id: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SetBoardRoleRequest {
+    pub role: String,
+}
+
+// 로그인한 사용자와 board 역할 (GET /api/auth/me)
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Me {
+    #[serde(flatten)]
+    pub user: User,
+    pub board_role: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LoginRequest {
+    pub username: String,
+    pub password: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LoginResponse {
+    pub token: String,               // cookie 대신 Authorization: Bearer 로 써도 됨
+    pub expires_at: DateTime<Utc>,
+    pub user: User,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateUserRequest {
+    pub username: String,
+    pub display_name: Option<String>,
+    pub password: String,
+    pub role: Option<String>,        // 기본 "member"
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateUserRequest {
+    pub display_name: Option<String>,
+    pub role: Option<String>,
+    pub disabled: Option<bool>,
+    pub password: Option<String>,    // admin의 비밀번호 재설정
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ChangePasswordRequest {
+    pub current_password: String,
+    pub new_password: String,
+}
+
+// 개인 API 토큰 (token_hash는 읽지 않음)
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct ApiToken {
+    pub id: i64,
+    pub user_id: i64,
+    pub name: String,
+    pub token_prefix: String,        // 목록에서 구분용 (예: "kbp_1a2b3c")
+    pub scopes: String,              // 쉼표 구분: read, write:tasks, admin
+    pub created_at: DateTime<Utc>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateApiTokenRequest {
+    pub name: String,
+    pub scopes: Vec<String>,
+    pub expires_in_days: Option<i64>, // 없으면 만료 없음
+}
+
+// 발급 직후 응답: 평문 토큰은 이때만 볼 수 있다
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreatedApiToken {
+    pub token: String,
+    #[serde(flatten)]
+    pub api_token: ApiToken,
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+
+    type Field = fn(&UpdateTaskRequest) -> Option<Option<Value>>;
+
+    /// UpdateTaskRequest의 필드 하나: 이름, 처음 값, 바꿀 값, null로 지울 수 있는지, 읽는 법
+    pub(crate) struct UpdateField {
+        pub name: &'static str,
+        pub old: Value,
+        pub new: Value,
+        pub nullable: bool,
+        get: Field,
+    }
+
+    fn field(name: &'static str, old: Value, new: Value, nullable: bool, get: Field) -> UpdateField {
+        UpdateField { name, old, new, nullable, get }
+    }
+
+    fn value<T: Into<Value>>(v: Option<T>) -> Option<Value> {
+        v.map(Into::into)
+    }
+
+    /// UpdateTaskRequest의 모든 필드 (route_tests의 PUT 테스트도 같이 쓴다)
+    pub(crate) fn update_fields() -> Vec<UpdateField> {
+        vec![
+            field("title", json!("old"), json!("new"), false, |r| r.title.clone().map(|v| Some(v.into()))),
+            field("description", json!("old text"), json!("new text"), true, |r| r.description.clone().map(value)),
+            field("status", json!("Pending"), json!("InProgress"), false, |r| r.status.clone().map(|v| Some(v.into()))),
+            field("stage", json!("개발"), json!("검증"), false, |r| r.stage.clone().map(|v| Some(v.into()))),
+            field("assignee", json!("kim"), json!("lee"), true, |r| r.assignee.clone().map(value)),
+            field("alias", json!("old-alias"), json!("new-alias"), true, |r| r.alias.clone().map(value)),
+            field("tags", json!("infra"), json!("infra,net"), true, |r| r.tags.clone().map(value)),
+            field("keywords", json!("vpc"), json!("vpc,subnet"), true, |r| r.keywords.clone().map(value)),
+            field("start_date", json!("2026-04-01"), json!("2026-04-15"), true, |r| {
+                r.start_date.map(|v| value(v.map(|d| d.to_string())))
+            }),
+            field("due_date", json!("2026-05-01"), json!("2026-06-15"), true, |r| {
+                r.due_date.map(|v| value(v.map(|d| d.to_string())))
+            }),
+            field("project_key", json!("VPC"), json!("NET"), true, |r| r.project_key.clone().map(value)),
+            field(
+                "jira_url",
+                json!("https://jira.example.com/browse/VPC-1"),
+                json!("https://jira.example.com/browse/NET-1"),
+                true,
+                |r| r.jira_url.clone().map(value),
+            ),
+            field("parent_task_id", json!(1), json!(2), true, |r| r.parent_task_id.map(value)),
+            field("comment", json!("old note"), json!("new note"), false, |r| r.comment.clone().map(|v| Some(v.into()))),
+            field("version", json!(1), json!(2), false, |r| r.version.map(|v| Some(v.into()))),
+        ]
+    }
+
+    /// 없으면 None, 값이면 Some(Some(값)). 지울 수 있는 필드는 null이면 Some(None), 아니면 null도 없는 것과 같다.
+    #[test]
+    fn update_request_distinguishes_absent_null_and_value() {
+        let parse = |body: Value| serde_json::from_value::<UpdateTaskRequest>(body).unwrap();
+        for field in update_fields() {
+            let name = field.name;
+            assert_eq!((field.get)(&parse(json!({}))), None, "{} absent", name);
+            let null = if field.nullable { Some(None) } else { None };
+            assert_eq!((field.get)(&parse(json!({ name: null }))), null, "{} null", name);
+            assert_eq!((field.get)(&parse(json!({ name: field.new.clone() }))), Some(Some(field.new)), "{} value", name);
+        }
+    }
+
+    /// 모든 필드가 표에 있는지 (필드를 추가하면 표에도 넣어야 한다)
+    #[test]
+    fn update_fields_cover_the_request() {
+        let all: serde_json::Map<String, Value> = update_fields().into_iter().map(|f| (f.name.to_string(), f.new)).collect();
+        let request = serde_json::to_value(serde_json::from_value::<UpdateTaskRequest>(Value::Object(all)).unwrap()).unwrap();
+        let mut names: Vec<&str> = request.as_object().unwrap().keys().map(String::as_str).collect();
+        let mut listed: Vec<&str> = update_fields().iter().map(|f| f.name).collect();
+        names.sort();
+        listed.sort();
+        assert_eq!(names, listed);
+    }
+
+    #[test]
+    fn update_request_rejects_wrong_types() {
+        assert!(serde_json::from_value::<UpdateTaskRequest>(json!({ "due_date": "tomorrow" })).is_err());
+        assert!(serde_json::from_value::<UpdateTaskRequest>(json!({ "parent_task_id": "1" })).is_err());
+    }
+
+    #[test]
+    fn task_list_query_keeps_the_flat_view_json() {
+        let json = json!({ "assignee": "@me", "parent": 3, "due_within": 7, "sort": "-due_date", "limit": 20 });
+        let query: TaskListQuery = serde_json::from_value(json).unwrap();
+        assert_eq!(query.filter.assignee.as_deref(), Some("@me"));
+        assert_eq!(query.filter.parent_task_id, Some(3));
+        assert_eq!(query.filter.due_within, Some(7));
+        assert_eq!(query.page.sort.as_deref(), Some("-due_date"));
+        assert_eq!(query.page.limit, Some(20));
+        assert!(!query.page.include_ancestors);
+
+        let saved = serde_json::to_value(&query).unwrap();
+        assert_eq!(saved["parent_task_id"], 3);
+        assert_eq!(saved["sort"], "-due_date");
+        assert!(saved.get("filter").is_none() && saved.get("page").is_none());
+    }
 }
@@ -0,0 +1,227 @@
+use std::collections::HashSet;
+
+use chrono::{NaiveDate, Utc};
+use sqlx::{FromRow, SqliteConnection};
+
+use crate::models::{RollupViolation, Task};
+use crate::schedule;
+
+// ─────────────────────────────────────────────
+// 상위 task roll-up: 하위 task의 상태/일정을 상위 task에 반영
+// ─────────────────────────────────────────────
+
+pub const DONE_WHEN_CHILDREN_DONE: &str = "done_when_children_done";
+pub const DUE_COVERS_CHILDREN: &str = "due_covers_children";
+pub const START_COVERS_CHILDREN: &str = "start_covers_children";
+pub const PROGRESS_FROM_CHILDREN: &str = "progress_from_children";
+
+pub const RULES: [&str; 4] = [
+    DONE_WHEN_CHILDREN_DONE,
+    DUE_COVERS_CHILDREN,
+    START_COVERS_CHILDREN,
+    PROGRESS_FROM_CHILDREN,
+];
+
+// parent 사이클이 있는 데이터에서도 끝나도록 하는 상한
+const MAX_ROLLUP_DEPTH: usize = 64;
+
+pub async fn enabled_rules(conn: &mut SqliteConnection) -> Result<HashSet<String>, sqlx::Error> {
+    let rows: Vec<(String,)> = sqlx::query_as("SELECT rule FROM rollup_rules WHERE enabled = 1")
+        .fetch_all(&mut *conn)
+        .await?;
+    Ok(rows.into_iter().map(|(r,)| r).collect())
+}
+
+#[derive(FromRow)]
+struct RollupRow {
+    id: i64,
+    title: String,
+    status: String,
+    parent_task_id: Option<i64>,
+    start_date: Option<NaiveDate>,
+    due_date: Option<NaiveDate>,
+}
+
+async fn load_row(conn: &mut SqliteConnection, id: i64) -> Result<Option<RollupRow>, sqlx::Error> {
+    sqlx::query_as::<_, RollupRow>(
+        "SELECT id, title, status, parent_task_id, start_date, due_date FROM tasks WHERE id = ?"
+    )
+    .bind(id)
+    .fetch_optional(&mut *conn)
+    .await
+}
+
+async fn load_children(conn: &mut SqliteConnection, id: i64) -> Result<Vec<RollupRow>, sqlx::Error> {
+    sqlx::query_as::<_, RollupRow>(
+        "SELECT id, title, status, parent_task_id, start_date, due_date FROM tasks WHERE parent_task_id = ?"
+    )
+    .bind(id)
+    .fetch_all(&mut *conn)
+    .await
+}
+
+async fn record(
+    conn: &mut SqliteConnection,
+    task_id: i64,
+    detail: serde_json::Value,
+) -> Result<(), sqlx::Error> {
+    sqlx::query("INSERT INTO task_history (task_id, action, detail, actor) VALUES (?, 'rollup', ?, 'rollup')")
+        .bind(task_id)
+        .bind(detail.to_string())
+        .execute(&mut *conn)
+        .await?;
+    Ok(())
+}
+
+/// 사용자가 Done에서 직접 되돌린 뒤로 Done이 된 하위 task가 없으면 true.
+/// 이때는 DONE_WHEN_CHILDREN_DONE으로 다시 Done으로 만들지 않는다 (전이 기록 id 순서로 비교).
+async fn reopened_by_user(conn: &mut SqliteConnection, id: i64) -> Result<bool, sqlx::Error> {
+    let (reopened,): (bool,) = sqlx::query_as(
+        "SELECT EXISTS (
+            SELECT 1 FROM (
+                SELECT id FROM task_stage_transitions
+                 WHERE task_id = ?1 AND from_status = 'Done' AND to_status != 'Done' AND actor IS NOT 'rollup'
+                 ORDER BY id DESC LIMIT 1
+            ) r
+            WHERE NOT EXISTS (
+                SELECT 1 FROM task_stage_transitions c JOIN tasks t ON t.id = c.task_id
+                 WHERE t.parent_task_id = ?1 AND c.to_status = 'Done' AND c.id > r.id
+            )
+        )"
+    )
+    .bind(id)
+    .fetch_one(&mut *conn)
+    .await?;
+    Ok(reopened)
+}
+
+/// task 자신과 그 상위 task들에 roll-up 규칙을 적용하고, 규칙으로 바뀐 task id를 돌려준다
+/// (호출한 쪽에서 commit 후 변경 알림용).
+/// 호출하는 쪽의 transaction 안에서 실행해야 변경과 roll-up이 함께 반영된다.
+pub async fn apply_from(conn: &mut SqliteConnection, task_id: i64) -> Result<Vec<i64>, sqlx::Error> {
+    let rules = enabled_rules(conn).await?;
+    let mut next = Some(task_id);
+    let mut depth = 0;
+    let mut changed = Vec::new();
+
+    while let Some(id) = next {
+        if depth >= MAX_ROLLUP_DEPTH {
+            break;
+        }
+        depth += 1;
+
+        let Some(task) = load_row(conn, id).await? else { break };
+        next = task.parent_task_id;
+
+        let children = load_children(conn, id).await?;
+        if children.is_empty() {
+            continue;
+        }
+
+        if rules.contains(DONE_WHEN_CHILDREN_DONE)
+            && task.status != "Done"
+            && children.iter().all(|c| c.status == "Done")
+            && !reopened_by_user(conn, id).await?
+        {
+            let before = sqlx::query_as::<_, Task>("SELECT * FROM tasks WHERE id = ?")
+                .bind(id)
+                .fetch_one(&mut *conn)
+                .await?;
+            sqlx::query("UPDATE tasks SET status = 'Done', stage = NULL, updated_at = ?, version = version + 1 WHERE id = ?")
+                .bind(Utc::now())
+                .bind(id)
+                .execute(&mut *conn)
+                .await?;
+            schedule::record_transition(conn, &before, "Done", None, Some("rollup")).await?;
+            record(conn, id, serde_json::json!({ "rule": DONE_WHEN_CHILDREN_DONE, "status": "Done" })).await?;
+            changed.push(id);
+        }
+
+        let latest_due = children.iter().filter_map(|c| c.due_date).max();
+        if rules.contains(DUE_COVERS_CHILDREN)
+            && let (Some(due), Some(latest)) = (task.due_date, latest_due)
+            && latest > due
+        {
+            sqlx::query("UPDATE tasks SET due_date = ?, updated_at = ?, version = version + 1 WHERE id = ?")
+                .bind(latest.to_string())
+                .bind(Utc::now())
+                .bind(id)
+                .execute(&mut *conn)
+                .await?;
+            schedule::refresh_stage_deadlines(conn, id).await?;
+            record(conn, id, serde_json::json!({ "rule": DUE_COVERS_CHILDREN, "from": due, "to": latest })).await?;
+            changed.push(id);
+        }
+
+        let earliest_start = children.iter().filter_map(|c| c.start_date).min();
+        if rules.contains(START_COVERS_CHILDREN)
+            && let (Some(start), Some(earliest)) = (task.start_date, earliest_start)
+            && earliest < start
+        {
+            sqlx::query("UPDATE tasks SET start_date = ?, updated_at = ?, version = version + 1 WHERE id = ?")
+                .bind(earliest.to_string())
+                .bind(Utc::now())
+                .bind(id)
+                .execute(&mut *conn)
+                .await?;
+            record(conn, id, serde_json::json!({ "rule": START_COVERS_CHILDREN, "from": start, "to": earliest })).await?;
+            changed.push(id);
+        }
+    }
+
+    changed.dedup();
+    Ok(changed)
+}
+
+/// 규칙과 맞지 않는 상위 task 목록 (규칙 활성화 여부와 관계없이 검사)
+pub async fn violations(conn: &mut SqliteConnection, boards: Option<&[String]>) -> Result<Vec<RollupViolation>, sqlx::Error> {
+    // boards가 있으면 그 board의 상위 task만
+    let boards = boards.map(|keys| serde_json::to_string(keys).unwrap_or_default());
+    let parents: Vec<(i64,)> = sqlx::query_as(
+        "SELECT DISTINCT t.parent_task_id FROM tasks t
+           JOIN tasks p ON p.id = t.parent_task_id
+           JOIN boards b ON b.id = p.board_id
+          WHERE ?1 IS NULL OR b.key IN (SELECT value FROM json_each(?1))
+          ORDER BY t.parent_task_id"
+    )
+    .bind(boards)
+    .fetch_all(&mut *conn)
+    .await?;
+    let enabled = enabled_rules(conn).await?;
+
+    let mut result = Vec::new();
+    for (id,) in parents {
+        let Some(task) = load_row(conn, id).await? else { continue };
+        let children = load_children(conn, id).await?;
+        let mut push = |rule: &str, message: String| {
+            result.push(RollupViolation {
+                task_id: task.id,
+                title: task.title.clone(),
+                rule: rule.to_string(),
+                enabled: enabled.contains(rule),
+                message,
+            });
+        };
+
+        let open_children = children.iter().filter(|c| c.status != "Done").count();
+        if open_children == 0 && task.status != "Done" {
+            push(DONE_WHEN_CHILDREN_DONE, format!("all {} children are Done but the parent is {}", children.len(), task.status));
+        } else if open_children > 0 && task.status == "Done" {
+            push(DONE_WHEN_CHILDREN_DONE, format!("the parent is Done but {} children are not", open_children));
+        }
+
+        if let (Some(due), Some(latest)) = (task.due_date, children.iter().filter_map(|c| c.due_date).max())
+            && latest > due
+        {
+            push(DUE_COVERS_CHILDREN, format!("due date {} is before the latest child due date {}", due, latest));
+        }
+
+        if let (Some(start), Some(earliest)) = (task.start_date, children.iter().filter_map(|c| c.start_date).min())
+            && earliest < start
+        {
+            push(START_COVERS_CHILDREN, format!("start date {} is after the earliest child start date {}", start, earliest));
+        }
+    }
+
+    Ok(result)
+}
@@ -0,0 +1,578 @@
+// ─────────────────────────────────────────────
+// route × 역할 테스트: 모든 /api route를 viewer, member, board admin, 멤버가 아닌 사용자,
+// 전체 admin으로 호출해 권한이 없으면 403, 있으면 403/401이 아닌지 확인한다.
+//
+// 요청마다 새 in-memory DB를 만들어 앞의 요청이 바꾼 상태에 영향을 받지 않게 한다.
+// ─────────────────────────────────────────────
+
+use axum::body::Body;
+use axum::http::{header, Method, Request, StatusCode};
+use sqlx::SqlitePool;
+use tower::ServiceExt;
+
+use crate::auth::BoardRole;
+use crate::models::tests::{update_fields, UpdateField};
+use crate::models::Task;
+use crate::test_support::{self, PASSWORD};
+use crate::{auth, backup, boards, rollup};
+
+const TEAM: &str = "team";
+
+// setup()이 만드는 행의 id (새 DB이므로 항상 같다)
+const TEAM_PARENT: i64 = 1;
+const TEAM_CHILD: i64 = 2;
+const TEAM_TASK: i64 = 3;
+const DEFAULT_TASK: i64 = 4;
+const COMMENT: i64 = 1;
+const ATTACHMENT: i64 = 1;
+const LINK: i64 = 1;
+const VIEW: i64 = 1;
+const API_TOKEN: i64 = 1;
+const OUTSIDER_ID: i64 = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Subject {
+    Root,       // users.role = admin
+    Viewer,
+    Member,
+    BoardAdmin,
+    Outsider,   // board_members에 없음
+}
+
+const SUBJECTS: [Subject; 5] = [Subject::Root, Subject::Viewer, Subject::Member, Subject::BoardAdmin, Subject::Outsider];
+
+impl Subject {
+    fn username(self) -> &'static str {
+        match self {
+            Subject::Root => "root",
+            Subject::Viewer => "viewer",
+            Subject::Member => "member",
+            Subject::BoardAdmin => "boardadmin",
+            Subject::Outsider => "outsider",
+        }
+    }
+
+    /// default, team board 모두 같은 역할 (멤버가 아니면 기본 board에서만 viewer)
+    fn role_on(self, board: &str) -> Option<BoardRole> {
+        match self {
+            Subject::Root | Subject::BoardAdmin => Some(BoardRole::Admin),
+            Subject::Member => Some(BoardRole::Member),
+            Subject::Viewer => Some(BoardRole::Viewer),
+            Subject::Outsider if board == auth::DEFAULT_BOARD => Some(BoardRole::Viewer),
+            Subject::Outsider => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Need {
+    LoggedIn,
+    Board(&'static str, BoardRole),
+    GlobalAdmin,
+}
+
+impl Need {
+    fn allows(self, subject: Subject) -> bool {
+        match self {
+            Need::LoggedIn => true,
+            Need::Board(board, min) => subject.role_on(board).is_some_and(|role| role >= min),
+            Need::GlobalAdmin => subject == Subject::Root,
+        }
+    }
+}
+
+struct Route {
+    method: Method,
+    uri: String,
+    body: Option<String>,
+    need: Need,
+}
+
+fn route(method: Method, uri: impl Into<String>, body: Option<serde_json::Value>, need: Need) -> Route {
+    Route { method, uri: uri.into(), body: body.map(|b| b.to_string()), need }
+}
+
+fn viewer(board: &'static str) -> Need {
+    Need::Board(board, BoardRole::Viewer)
+}
+
+fn member(board: &'static str) -> Need {
+    Need::Board(board, BoardRole::Member)
+}
+
+fn admin(board: &'static str) -> Need {
+    Need::Board(board, BoardRole::Admin)
+}
+
+fn routes(archive: &str) -> Vec<Route> {
+    use serde_json::json;
+    use Method as M;
+    const D: &str = auth::DEFAULT_BOARD;
+    let t = |path: &str| format!("/api/boards/{}{}", TEAM, path);
+    let task = |id: i64, path: &str| format!("/api/tasks/{}{}", id, path);
+    let mut routes = vec![
+        // 기본 board 경로 (멤버가 아니어도 viewer)
+        route(M::GET, "/api/tasks", None, viewer(D)),
+        route(M::POST, "/api/tasks", Some(json!({ "title": "new" })), member(D)),
+        route(M::GET, "/api/tasks/tree", None, viewer(D)),
+        route(M::GET, "/api/tasks/export", None, viewer(D)),
+        route(
+            M::POST,
+            "/api/tasks/bulk",
+            Some(json!({ "task_ids": [DEFAULT_TASK], "operation": { "op": "set_assignee", "assignee": "kim" } })),
+            member(D),
+        ),
+        route(M::POST, "/api/tasks/from-backlog", Some(json!({ "ticket_keys": [], "include_subtasks": false })), member(D)),
+        route(M::POST, "/api/backlog/query", Some(json!({})), viewer(D)),
+        route(M::GET, "/api/board/summary", None, viewer(D)),
+        route(M::GET, "/api/board/wip-limits", None, viewer(D)),
+        route(M::PUT, "/api/board/wip-limits", Some(json!({ "scope": "InProgress", "wip_limit": 3 })), admin(D)),
+        route(M::GET, "/api/board/members", None, viewer(D)),
+        route(M::PUT, format!("/api/board/members/{}", OUTSIDER_ID), Some(json!({ "role": "viewer" })), admin(D)),
+        route(M::DELETE, "/api/board/members/2", None, admin(D)),
+        route(M::GET, "/api/board/backup", None, admin(D)),
+        route(M::GET, "/api/board/deleted-tasks", None, viewer(D)),
+        route(M::GET, "/api/views", None, viewer(D)),
+        route(M::POST, "/api/views", Some(json!({ "name": "mine" })), viewer(D)),
+        route(M::GET, "/api/schedule/analysis", None, viewer(D)),
+        route(M::GET, task(DEFAULT_TASK, "/comments"), None, viewer(D)),
+        route(M::DELETE, task(DEFAULT_TASK, ""), None, admin(D)),
+        // team board
+        route(M::GET, t(""), None, viewer(TEAM)),
+        route(M::PUT, t(""), Some(json!({ "name": "Team 2" })), admin(TEAM)),
+        route(M::GET, t("/backup"), None, admin(TEAM)),
+        route(M::GET, t("/deleted-tasks"), None, viewer(TEAM)),
+        route(M::GET, t("/tasks"), None, viewer(TEAM)),
+        route(M::POST, t("/tasks"), Some(json!({ "title": "new" })), member(TEAM)),
+        route(M::GET, t("/tasks/tree"), None, viewer(TEAM)),
+        route(
+            M::POST,
+            t("/tasks/bulk"),
+            Some(json!({ "task_ids": [TEAM_TASK], "operation": { "op": "set_assignee", "assignee": "kim" } })),
+            member(TEAM),
+        ),
+        route(M::GET, t("/tasks/export"), None, viewer(TEAM)),
+        route(M::POST, t("/tasks/from-backlog"), Some(json!({ "ticket_keys": [], "include_subtasks": false })), member(TEAM)),
+        route(M::POST, t("/backlog/query"), Some(json!({})), viewer(TEAM)),
+        route(M::GET, t("/summary"), None, viewer(TEAM)),
+        route(M::GET, t("/wip-limits"), None, viewer(TEAM)),
+        route(M::PUT, t("/wip-limits"), Some(json!({ "scope": "InProgress", "wip_limit": 3 })), admin(TEAM)),
+        route(M::GET, t("/members"), None, viewer(TEAM)),
+        route(M::PUT, t(&format!("/members/{}", OUTSIDER_ID)), Some(json!({ "role": "viewer" })), admin(TEAM)),
+        route(M::DELETE, t("/members/2"), None, admin(TEAM)),
+        route(M::GET, t("/views"), None, viewer(TEAM)),
+        route(M::POST, t("/views"), Some(json!({ "name": "mine" })), viewer(TEAM)),
+        route(M::GET, t("/schedule/analysis"), None, viewer(TEAM)),
+        // team board의 task
+        route(M::PUT, task(TEAM_TASK, ""), Some(json!({ "title": "renamed", "version": 1 })), member(TEAM)),
+        route(M::DELETE, task(TEAM_TASK, ""), None, admin(TEAM)),
+        route(
+            M::POST,
+            task(TEAM_TASK, "/status"),
+            Some(json!({ "status": "InProgress", "stage": "사양확인", "version": 1 })),
+            member(TEAM),
+        ),
+        route(M::POST, task(TEAM_TASK, "/move"), Some(json!({ "parent_task_id": TEAM_PARENT })), member(TEAM)),
+        route(M::POST, task(TEAM_TASK, "/board"), Some(json!({ "board": D })), member(TEAM)),
+        route(M::GET, task(TEAM_PARENT, "/comments"), None, viewer(TEAM)),
+        route(M::POST, task(TEAM_PARENT, "/comments"), Some(json!({ "content": "hi" })), member(TEAM)),
+        // comment는 root가 쓴 것이므로 고치거나 지우려면 board admin
+        route(M::PUT, task(TEAM_PARENT, &format!("/comments/{}", COMMENT)), Some(json!({ "content": "edited" })), admin(TEAM)),
+        route(M::DELETE, task(TEAM_PARENT, &format!("/comments/{}", COMMENT)), None, admin(TEAM)),
+        route(M::GET, task(TEAM_PARENT, &format!("/comments/{}/revisions", COMMENT)), None, viewer(TEAM)),
+        route(M::POST, task(TEAM_PARENT, "/attachments"), Some(json!({})), member(TEAM)),
+        route(M::GET, format!("/api/attachments/{}", ATTACHMENT), None, viewer(TEAM)),
+        route(M::GET, task(TEAM_PARENT, "/history"), None, viewer(TEAM)),
+        route(M::GET, task(TEAM_PARENT, "/transitions"), None, viewer(TEAM)),
+        route(M::GET, task(TEAM_PARENT, "/links"), None, viewer(TEAM)),
+        route(
+            M::POST,
+            task(TEAM_CHILD, "/links"),
+            Some(json!({ "target_task_id": TEAM_TASK, "link_type": "relates_to" })),
+            member(TEAM),
+        ),
+        route(M::DELETE, task(TEAM_PARENT, &format!("/links/{}", LINK)), None, member(TEAM)),
+        route(M::GET, task(TEAM_TASK, "/blockers"), None, viewer(TEAM)),
+        // 공유 view (root 소유): 보기는 viewer, 고치기는 board admin
+        route(M::GET, format!("/api/views/{}", VIEW), None, viewer(TEAM)),
+        route(M::PUT, format!("/api/views/{}", VIEW), Some(json!({ "name": "renamed" })), admin(TEAM)),
+        route(M::DELETE, format!("/api/views/{}", VIEW), None, admin(TEAM)),
+        route(M::GET, format!("/api/views/{}/tasks", VIEW), None, viewer(TEAM)),
+        route(M::GET, format!("/api/events?board={}", TEAM), None, viewer(TEAM)),
+        // 로그인만 필요 (결과는 볼 수 있는 board로 제한)
+        route(M::GET, "/api/events", None, Need::LoggedIn),
+        route(M::GET, "/api/mentions", None, Need::LoggedIn),
+        route(M::GET, "/api/boards", None, Need::LoggedIn),
+        route(M::GET, "/api/holidays", None, Need::LoggedIn),
+        route(M::GET, "/api/rollup/rules", None, Need::LoggedIn),
+        route(M::GET, "/api/rollup/violations", None, Need::LoggedIn),
+        route(M::GET, "/api/workflows", None, Need::LoggedIn),
+        route(M::GET, "/api/workflows/default/transitions", None, Need::LoggedIn),
+        route(M::GET, "/api/auth/me", None, Need::LoggedIn),
+        route(M::POST, "/api/auth/logout", None, Need::LoggedIn),
+        route(
+            M::PUT,
+            "/api/auth/password",
+            Some(json!({ "current_password": PASSWORD, "new_password": "password456" })),
+            Need::LoggedIn,
+        ),
+        route(M::GET, "/api/users", None, Need::LoggedIn),
+        route(M::GET, "/api/tokens", None, Need::LoggedIn),
+        route(M::POST, "/api/tokens", Some(json!({ "name": "ci", "scopes": ["read"] })), Need::LoggedIn),
+        route(M::DELETE, format!("/api/tokens/{}", API_TOKEN), None, Need::LoggedIn),
+        // 모든 board가 같이 쓰는 설정, 계정, 서버 관리
+        route(M::POST, "/api/boards", Some(json!({ "key": "new", "name": "New" })), Need::GlobalAdmin),
+        route(M::PUT, "/api/holidays/2026-12-25", Some(json!({ "name": "성탄절" })), Need::GlobalAdmin),
+        route(M::DELETE, "/api/holidays/2026-12-25", None, Need::GlobalAdmin),
+        route(M::PUT, format!("/api/rollup/rules/{}", rollup::RULES[0]), Some(json!({ "enabled": true })), Need::GlobalAdmin),
+        route(M::PUT, "/api/workflows/default/transitions", Some(json!([])), Need::GlobalAdmin),
+        route(
+            M::POST,
+            "/api/users",
+            Some(json!({ "username": "new", "password": PASSWORD, "role": "member" })),
+            Need::GlobalAdmin,
+        ),
+        route(M::PUT, format!("/api/users/{}", OUTSIDER_ID), Some(json!({ "display_name": "Out" })), Need::GlobalAdmin),
+        route(M::GET, "/api/snapshots", None, Need::GlobalAdmin),
+        route(M::POST, "/api/snapshots", None, Need::GlobalAdmin),
+    ];
+    routes.push(Route {
+        method: M::POST,
+        uri: "/api/boards/restore?board=restored".to_string(),
+        body: Some(archive.to_string()),
+        need: Need::GlobalAdmin,
+    });
+    for (uri, board) in [("/api/tasks/import".to_string(), D), (t("/tasks/import"), TEAM)] {
+        routes.push(Route { method: M::POST, uri, body: Some("title\nimported\n".to_string()), need: member(board) });
+    }
+    routes
+}
+
+/// board 두 개 (default, team)와 사용자, task, comment, 첨부, 링크, view를 만든다.
+/// 돌려주는 값은 사용자별 session token.
+async fn setup(pool: &SqlitePool) -> Vec<(Subject, String)> {
+    let mut conn = pool.acquire().await.unwrap();
+    sqlx::query("INSERT INTO boards (key, name) VALUES (?, 'Team')")
+        .bind(TEAM)
+        .execute(&mut *conn)
+        .await
+        .unwrap();
+
+    let mut tokens = Vec::new();
+    for subject in SUBJECTS {
+        let role = if subject == Subject::Root { auth::ADMIN_ROLE } else { auth::MEMBER_ROLE };
+        let (id, token) = test_support::add_user(&mut conn, subject.username(), role).await;
+        let board_role = match subject {
+            Subject::Viewer => Some("viewer"),
+            Subject::Member => Some("member"),
+            Subject::BoardAdmin => Some("admin"),
+            Subject::Root | Subject::Outsider => None,
+        };
+        for board in [auth::DEFAULT_BOARD, TEAM] {
+            if let Some(board_role) = board_role {
+                sqlx::query("INSERT INTO board_members (board, user_id, role) VALUES (?, ?, ?)")
+                    .bind(board)
+                    .bind(id)
+                    .bind(board_role)
+                    .execute(&mut *conn)
+                    .await
+                    .unwrap();
+            }
+        }
+        tokens.push((subject, token));
+    }
+
+    for statement in [
+        "INSERT INTO tasks (title, board_id, parent_task_id) VALUES ('parent', 2, NULL)",
+        "INSERT INTO tasks (title, board_id, parent_task_id) VALUES ('child', 2, 1)",
+        "INSERT INTO tasks (title, board_id, parent_task_id) VALUES ('blocked', 2, NULL)",
+        "INSERT INTO tasks (title, board_id, parent_task_id) VALUES ('default', 1, NULL)",
+        "INSERT INTO task_comments (task_id, author, content) VALUES (1, 'root', 'hello @viewer')",
+        "INSERT INTO comment_mentions (comment_id, task_id, username) VALUES (1, 1, 'viewer')",
+        "INSERT INTO attachments (task_id, comment_id, sha256, file_name, content_type, size)
+         VALUES (1, 1, '0000000000000000000000000000000000000000000000000000000000000000', 'a.txt', 'text/plain', 1)",
+        "INSERT INTO task_links (source_task_id, target_task_id, link_type) VALUES (1, 3, 'blocks')",
+        "INSERT INTO saved_views (board_id, owner_id, name, shared) VALUES (2, 1, 'shared', 1)",
+        "INSERT INTO api_tokens (user_id, name, token_hash, token_prefix, scopes) VALUES (1, 'ci', 'hash', 'prefix', 'read')",
+    ] {
+        sqlx::query(statement).execute(&mut *conn).await.unwrap();
+    }
+    tokens
+}
+
+async fn test_app() -> (axum::Router, Vec<(Subject, String)>, String) {
+    let pool = test_support::memory_db().await;
+    let tokens = setup(&pool).await;
+    let archive = {
+        let mut conn = pool.acquire().await.unwrap();
+        let board = boards::fetch_board(&mut conn, TEAM).await.unwrap();
+        serde_json::to_string(&backup::export_board(&mut conn, &board).await.unwrap()).unwrap()
+    };
+    (test_support::app(pool), tokens, archive)
+}
+
+async fn call(app: axum::Router, route: &Route, token: &str) -> StatusCode {
+    let mut request = Request::builder()
+        .method(route.method.clone())
+        .uri(&route.uri)
+        .header(header::AUTHORIZATION, format!("Bearer {}", token));
+    if route.body.is_some() {
+        let content_type = if route.uri.ends_with("/import") { "text/csv" } else { "application/json" };
+        request = request.header(header::CONTENT_TYPE, content_type);
+    }
+    let body = route.body.clone().map(Body::from).unwrap_or_else(Body::empty);
+    app.oneshot(request.body(body).unwrap()).await.unwrap().status()
+}
+
+#[tokio::test]
+async fn every_route_enforces_roles() {
+    let (_, _, archive) = test_app().await;
+    let mut failures = Vec::new();
+    for route in routes(&archive) {
+        for subject in SUBJECTS {
+            // 요청마다 새 DB (앞에서 지우거나 바꾼 것의 영향을 받지 않도록)
+            let (app, tokens, _) = test_app().await;
+            let token = &tokens.iter().find(|(s, _)| *s == subject).unwrap().1;
+            let status = call(app, &route, token).await;
+            let allowed = route.need.allows(subject);
+            // 허용된 요청은 extractor를 지나 handler까지 가야 한다 (handler가 죽으면 500)
+            let ok = if allowed {
+                status != StatusCode::FORBIDDEN && status != StatusCode::UNAUTHORIZED && !status.is_server_error()
+            } else {
+                status == StatusCode::FORBIDDEN
+            };
+            if !ok {
+                failures.push(format!(
+                    "{} {} as {:?}: expected {}, got {}",
+                    route.method,
+                    route.uri,
+                    subject,
+                    if allowed { "access without 401/403/5xx" } else { "403" },
+                    status
+                ));
+            }
+        }
+    }
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
+
+#[tokio::test]
+async fn routes_require_login() {
+    let (_, _, archive) = test_app().await;
+    for route in routes(&archive) {
+        let (app, _, _) = test_app().await;
+        let status = call(app, &route, "not-a-session").await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED, "{} {}", route.method, route.uri);
+    }
+}
+
+
+async fn get_json(app: axum::Router, uri: &str, token: &str) -> serde_json::Value {
+    let request = Request::builder()
+        .uri(uri)
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .body(Body::empty())
+        .unwrap();
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK, "GET {}", uri);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    serde_json::from_slice(&body).unwrap()
+}
+
+#[tokio::test]
+async fn task_refs_resolve_only_on_viewable_boards() {
+    let (app, tokens, _) = test_app().await;
+    let token = |subject: Subject| tokens.iter().find(|(s, _)| *s == subject).unwrap().1.as_str();
+    let request = Request::builder()
+        .method(Method::PUT)
+        .uri(format!("/api/tasks/{}", DEFAULT_TASK))
+        .header(header::AUTHORIZATION, format!("Bearer {}", token(Subject::Root)))
+        .header(header::CONTENT_TYPE, "application/json")
+        .header(header::IF_MATCH, "*")
+        .body(Body::from(format!(r#"{{"description": "see #{} and #{}"}}"#, TEAM_PARENT, DEFAULT_TASK)))
+        .unwrap();
+    assert_eq!(app.clone().oneshot(request).await.unwrap().status(), StatusCode::OK);
+
+    let html = |tasks: serde_json::Value| {
+        tasks.as_array().unwrap().iter().find(|t| t["id"] == DEFAULT_TASK).unwrap()["description_html"]
+            .as_str()
+            .unwrap()
+            .to_string()
+    };
+    // team board의 task 제목은 team을 볼 수 없는 사용자에게 보이지 않는다
+    let outsider = html(get_json(app.clone(), "/api/tasks", token(Subject::Outsider)).await);
+    assert!(!outsider.contains("parent"), "{}", outsider);
+    assert!(outsider.contains(&format!("data-task-id=\"{}\"", DEFAULT_TASK)), "{}", outsider);
+    assert!(outsider.contains(&format!("#{}", TEAM_PARENT)), "{}", outsider);
+
+    let root = html(get_json(app, "/api/tasks", token(Subject::Root)).await);
+    assert!(root.contains("title=\"parent\""), "{}", root);
+}
+
+#[tokio::test]
+async fn put_to_done_clears_the_stage_like_the_status_api() {
+    let pool = test_support::memory_db().await;
+    let mut conn = pool.acquire().await.unwrap();
+    let (_, token) = test_support::add_user(&mut conn, "root", auth::ADMIN_ROLE).await;
+    sqlx::query("INSERT INTO tasks (title, board_id, status, stage) VALUES ('task', 1, 'InProgress', '검증')")
+        .execute(&mut *conn)
+        .await
+        .unwrap();
+    drop(conn);
+
+    let request = Request::builder()
+        .method(Method::PUT)
+        .uri("/api/tasks/1")
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .header(header::CONTENT_TYPE, "application/json")
+        .header(header::IF_MATCH, "*")
+        .body(Body::from(r#"{"status": "Done"}"#))
+        .unwrap();
+    let status = test_support::app(pool.clone()).oneshot(request).await.unwrap().status();
+    assert_eq!(status, StatusCode::OK);
+
+    let (stage,): (Option<String>,) = sqlx::query_as("SELECT stage FROM tasks WHERE id = 1").fetch_one(&pool).await.unwrap();
+    assert_eq!(stage, None);
+    let (to_status, to_stage): (String, Option<String>) =
+        sqlx::query_as("SELECT to_status, to_stage FROM task_stage_transitions WHERE task_id = 1 ORDER BY id DESC")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!((to_status.as_str(), to_stage), ("Done", None));
+}
+
+/// 상위 task 두 개와, 지울 수 있는 필드가 모두 채워진 task 하나를 만들고 PUT body를 보낸 뒤의 task (JSON)
+async fn put_update(body: &serde_json::Value) -> serde_json::Value {
+    let pool = test_support::memory_db().await;
+    let mut conn = pool.acquire().await.unwrap();
+    let (_, token) = test_support::add_user(&mut conn, "root", auth::ADMIN_ROLE).await;
+    for title in ["old parent", "new parent"] {
+        sqlx::query("INSERT INTO tasks (title, board_id) VALUES (?, 1)").bind(title).execute(&mut *conn).await.unwrap();
+    }
+    let fields: Vec<UpdateField> = update_fields().into_iter().filter(|f| f.nullable).collect();
+    let columns: Vec<&str> = fields.iter().map(|f| f.name).collect();
+    let sql = format!(
+        "INSERT INTO tasks (title, board_id, {}) VALUES ('task', 1, {})",
+        columns.join(", "),
+        vec!["?"; columns.len()].join(", ")
+    );
+    let mut insert = sqlx::query(&sql);
+    for field in &fields {
+        insert = match &field.old {
+            serde_json::Value::Number(n) => insert.bind(n.as_i64()),
+            v => insert.bind(v.as_str().unwrap().to_string()),
+        };
+    }
+    insert.execute(&mut *conn).await.unwrap();
+    drop(conn);
+
+    let request = Request::builder()
+        .method(Method::PUT)
+        .uri("/api/tasks/3")
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .header(header::CONTENT_TYPE, "application/json")
+        .header(header::IF_MATCH, "*")
+        .body(Body::from(body.to_string()))
+        .unwrap();
+    let status = test_support::app(pool.clone()).oneshot(request).await.unwrap().status();
+    assert_eq!(status, StatusCode::OK, "PUT {}", body);
+
+    let task = sqlx::query_as::<_, Task>("SELECT * FROM tasks WHERE id = 3").fetch_one(&pool).await.unwrap();
+    serde_json::to_value(task).unwrap()
+}
+
+/// PUT /api/tasks/:id: 지울 수 있는 필드마다 없음(그대로) / null(지움) / 값(바꿈)
+#[tokio::test]
+async fn put_task_applies_absent_null_and_value() {
+    use serde_json::{json, Value};
+    let nullable = || update_fields().into_iter().filter(|f| f.nullable);
+
+    let task = put_update(&json!({ "title": "renamed" })).await;
+    assert_eq!(task["title"], "renamed");
+    for field in nullable() {
+        assert_eq!(task[field.name], field.old, "{} should be unchanged", field.name);
+    }
+
+    for field in nullable() {
+        let task = put_update(&json!({ field.name: null })).await;
+        assert_eq!(task[field.name], Value::Null, "{} should be cleared", field.name);
+        for other in nullable().filter(|o| o.name != field.name) {
+            assert_eq!(task[other.name], other.old, "{} should be unchanged when clearing {}", other.name, field.name);
+        }
+
+        let task = put_update(&json!({ field.name: field.new.clone() })).await;
+        assert_eq!(task[field.name], field.new, "{} should be replaced", field.name);
+    }
+}
+
+#[tokio::test]
+async fn deleting_a_comment_removes_its_attachments() {
+    let pool = test_support::memory_db().await;
+    let tokens = setup(&pool).await;
+    let app = test_support::app(pool.clone());
+    let token = &tokens.iter().find(|(s, _)| *s == Subject::Root).unwrap().1;
+    let attachments = |pool: sqlx::SqlitePool| async move {
+        sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM attachments WHERE id = ?")
+            .bind(ATTACHMENT)
+            .fetch_one(&pool)
+            .await
+            .unwrap()
+    };
+
+    assert_eq!(attachments(pool.clone()).await, 1);
+    let delete = route(Method::DELETE, format!("/api/tasks/{}/comments/{}", TEAM_PARENT, COMMENT), None, Need::LoggedIn);
+    assert_eq!(call(app.clone(), &delete, token).await, StatusCode::OK);
+    // 지운 comment의 첨부는 내려받을 수도, 목록에 보일 수도 없어야 한다
+    assert_eq!(attachments(pool).await, 0);
+    let comments = get_json(app, &format!("/api/tasks/{}/comments", TEAM_PARENT), token).await;
+    let comment = comments.as_array().unwrap().iter().find(|c| c["id"] == COMMENT).unwrap();
+    assert_eq!(comment["files"], serde_json::json!([]));
+}
+
+#[tokio::test]
+async fn last_used_at_is_written_only_when_stale() {
+    let pool = test_support::memory_db().await;
+    let mut conn = pool.acquire().await.unwrap();
+    let (_, token) = test_support::add_user(&mut conn, "alice", auth::MEMBER_ROLE).await;
+    drop(conn);
+    let app = test_support::app(pool.clone());
+    let me = route(Method::GET, "/api/auth/me".to_string(), None, Need::LoggedIn);
+    let set = |at: chrono::DateTime<chrono::Utc>| {
+        let pool = pool.clone();
+        async move { sqlx::query("UPDATE sessions SET last_used_at = ?").bind(at).execute(&pool).await.unwrap() }
+    };
+    let last_used = || {
+        let pool = pool.clone();
+        async move {
+            sqlx::query_scalar::<_, chrono::DateTime<chrono::Utc>>("SELECT last_used_at FROM sessions")
+                .fetch_one(&pool)
+                .await
+                .unwrap()
+        }
+    };
+
+    // 방금 쓴 값은 요청이 와도 그대로
+    let recent = chrono::Utc::now() - chrono::Duration::minutes(1);
+    set(recent).await;
+    assert_eq!(call(app.clone(), &me, &token).await, StatusCode::OK);
+    assert_eq!(last_used().await, recent);
+
+    let stale = chrono::Utc::now() - chrono::Duration::minutes(30);
+    set(stale).await;
+    assert_eq!(call(app, &me, &token).await, StatusCode::OK);
+    assert!(last_used().await > recent);
+}
+
+#[tokio::test]
+async fn task_list_reads_filter_and_page_params_together() {
+    let (app, tokens, _) = test_app().await;
+    let token = &tokens.iter().find(|(s, _)| *s == Subject::Root).unwrap().1;
+    let ids = |tasks: serde_json::Value| -> Vec<i64> {
+        tasks.as_array().unwrap().iter().map(|t| t["id"].as_i64().unwrap()).collect()
+    };
+
+    let uri = format!("/api/boards/{}/tasks?parent={}&overdue=false&include_ancestors=true", TEAM, TEAM_PARENT);
+    assert_eq!(ids(get_json(app.clone(), &uri, token).await), vec![TEAM_PARENT, TEAM_CHILD]);
+    let uri = format!("/api/boards/{}/tasks?has_jira=false&sort=-id&limit=2", TEAM);
+    assert_eq!(ids(get_json(app, &uri, token).await), vec![TEAM_TASK, TEAM_CHILD]);
+}
@@ -0,0 +1,370 @@
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, Datelike, Days, NaiveDate, Utc, Weekday};
+use sqlx::{SqliteConnection, SqlitePool};
+
+use crate::models::{StageDeadline, Task};
+use crate::workflow::STAGES;
+
+// 단계별 due offset (due_date보다 몇 영업일 앞서 끝나야 하는지)
+// 리뷰: due-1일, 검증: 리뷰-1일(=due-2일), 개발: 검증-2일(=due-4일),
+// CCB: 개발-1일(=due-5일), 사양확인: CCB-1일(=due-6일)
+pub const STAGE_DUE_OFFSETS: [(&str, u64); 6] = [
+    ("사양확인", 6),
+    ("CCB", 5),
+    ("개발", 4),
+    ("검증", 2),
+    ("리뷰", 1),
+    ("Done", 0),
+];
+
+// ─────────────────────────────────────────────
+// 영업일 달력: 주말과 holidays 테이블의 휴일을 제외
+// ─────────────────────────────────────────────
+
+pub fn is_working_day(date: NaiveDate, holidays: &HashSet<NaiveDate>) -> bool {
+    !matches!(date.weekday(), Weekday::Sat | Weekday::Sun) && !holidays.contains(&date)
+}
+
+/// date로부터 영업일 n일 전. n = 0이면 date 그대로
+pub fn sub_working_days(date: NaiveDate, n: u64, holidays: &HashSet<NaiveDate>) -> NaiveDate {
+    let mut d = date;
+    let mut left = n;
+    while left > 0 {
+        d = d - Days::new(1);
+        if is_working_day(d, holidays) {
+            left -= 1;
+        }
+    }
+    d
+}
+
+/// due_date 기준 단계별 계획 완료일
+pub fn planned_stage_deadlines(due: NaiveDate, holidays: &HashSet<NaiveDate>) -> Vec<StageDeadline> {
+    STAGE_DUE_OFFSETS
+        .iter()
+        .map(|&(stage, offset)| StageDeadline {
+            stage: stage.to_string(),
+            planned_date: sub_working_days(due, offset, holidays),
+        })
+        .collect()
+}
+
+async fn load_holidays(conn: &mut SqliteConnection) -> Result<HashSet<NaiveDate>, sqlx::Error> {
+    let rows: Vec<(NaiveDate,)> = sqlx::query_as("SELECT date FROM holidays")
+        .fetch_all(&mut *conn)
+        .await?;
+    Ok(rows.into_iter().map(|(d,)| d).collect())
+}
+
+async fn store_stage_deadlines(
+    conn: &mut SqliteConnection,
+    task_id: i64,
+    due: Option<NaiveDate>,
+    holidays: &HashSet<NaiveDate>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query("DELETE FROM task_stage_deadlines WHERE task_id = ?")
+        .bind(task_id)
+        .execute(&mut *conn)
+        .await?;
+    let Some(due) = due else { return Ok(()) };
+    for d in planned_stage_deadlines(due, holidays) {
+        sqlx::query("INSERT INTO task_stage_deadlines (task_id, stage, planned_date) VALUES (?, ?, ?)")
+            .bind(task_id)
+            .bind(&d.stage)
+            .bind(d.planned_date.to_string())
+            .execute(&mut *conn)
+            .await?;
+    }
+    Ok(())
+}
+
+/// task의 due_date(또는 상위 task)가 바뀌었을 때 단계별 계획 완료일을 다시 계산.
+/// due가 없는 하위 task는 상위 task의 due를 따르므로 (effective_dates) subtree 전체를 다시 계산한다.
+/// (함께 읽은 상위 task도 다시 저장하지만 값은 그대로)
+pub async fn refresh_stage_deadlines(conn: &mut SqliteConnection, task_id: i64) -> Result<(), sqlx::Error> {
+    let holidays = load_holidays(conn).await?;
+    let rows = load_date_rows(conn, &[task_id], true).await?;
+    for &id in rows.keys() {
+        let (_, due) = effective_dates(id, &rows, 0);
+        store_stage_deadlines(conn, id, due, &holidays).await?;
+    }
+    Ok(())
+}
+
+/// 모든 task의 단계별 계획 완료일을 다시 계산 (휴일 변경, 서버 시작 시)
+pub async fn refresh_all_stage_deadlines(conn: &mut SqliteConnection) -> Result<(), sqlx::Error> {
+    let holidays = load_holidays(conn).await?;
+    let rows: HashMap<i64, DateRow> = sqlx::query_as::<_, DateRow>(
+        "SELECT id, status, stage, parent_task_id, start_date, due_date, created_at FROM tasks"
+    )
+    .fetch_all(&mut *conn)
+    .await?
+    .into_iter()
+    .map(|r| (r.id, r))
+    .collect();
+    for &id in rows.keys() {
+        let (_, due) = effective_dates(id, &rows, 0);
+        store_stage_deadlines(conn, id, due, &holidays).await?;
+    }
+    Ok(())
+}
+
+// ─────────────────────────────────────────────
+// 진행도 계산: 모든 클라이언트(UI, CLI, 리포트)가 같은 값을 쓰도록 서버에서 계산
+// ─────────────────────────────────────────────
+
+/// 이상적 진행도 (0~100): start ~ due 사이에서 오늘의 위치.
+/// due가 없으면 0 (무한)
+pub fn ideal_progress(start: NaiveDate, due: Option<NaiveDate>, today: NaiveDate) -> i64 {
+    let Some(due) = due else { return 0 };
+    if due <= start {
+        return 100;
+    }
+    let total = (due - start).num_days() as f64;
+    let elapsed = (today - start).num_days() as f64;
+    ((elapsed / total * 100.0).round() as i64).clamp(0, 100)
+}
+
+/// 실제 진행도 (0~100, 단계 기반): 5단계를 균등하게 나누어 현재 단계의 시작 비율 + 5% 최소값
+pub fn actual_progress(status: &str, stage: Option<&str>) -> i64 {
+    match status {
+        "Done" => 100,
+        "Pending" => 0,
+        _ => match stage.and_then(|s| STAGES.iter().position(|&x| x == s)) {
+            Some(idx) => (idx * 100 / STAGES.len()) as i64 + 5,
+            None => 5,
+        },
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct DateRow {
+    id: i64,
+    status: String,
+    stage: Option<String>,
+    parent_task_id: Option<i64>,
+    start_date: Option<NaiveDate>,
+    due_date: Option<NaiveDate>,
+    created_at: DateTime<Utc>,
+}
+
+/// 비어 있는 start/due는 상위 task의 값을 따른다.
+/// 최상위이고 start가 없으면 created_at. (frontend flattenTree와 같은 규칙)
+fn effective_dates(
+    id: i64,
+    rows: &HashMap<i64, DateRow>,
+    depth: usize,
+) -> (Option<NaiveDate>, Option<NaiveDate>) {
+    let Some(row) = rows.get(&id) else { return (None, None) };
+    let parent = match row.parent_task_id {
+        Some(pid) if depth < rows.len() => Some(effective_dates(pid, rows, depth + 1)),
+        _ => None,
+    };
+    let start = row
+        .start_date
+        .or(parent.and_then(|p| p.0))
+        .or(Some(row.created_at.date_naive()));
+    let due = row.due_date.or(parent.and_then(|p| p.1));
+    (start, due)
+}
+
+/// board의 모든 task의 실효 일정 (start, due). 상위 task 날짜 상속 규칙은 effective_dates와 같다.
+/// due_override = (task id, due)이면 그 task의 due를 바꾼 것으로 계산한다 (what-if 분석, DB는 그대로).
+/// (상위/하위 task는 항상 같은 board에 있다)
+pub async fn effective_schedule(
+    db: &SqlitePool,
+    board_id: i64,
+    due_override: Option<(i64, NaiveDate)>,
+) -> Result<HashMap<i64, (NaiveDate, Option<NaiveDate>)>, sqlx::Error> {
+    let mut rows: HashMap<i64, DateRow> = sqlx::query_as::<_, DateRow>(
+        "SELECT id, status, stage, parent_task_id, start_date, due_date, created_at FROM tasks WHERE board_id = ?"
+    )
+    .bind(board_id)
+    .fetch_all(db)
+    .await?
+    .into_iter()
+    .map(|r| (r.id, r))
+    .collect();
+    if let Some((id, due)) = due_override
+        && let Some(row) = rows.get_mut(&id)
+    {
+        row.due_date = Some(due);
+    }
+
+    Ok(rows
+        .iter()
+        .map(|(&id, row)| {
+            let (start, due) = effective_dates(id, &rows, 0);
+            (id, (start.unwrap_or(row.created_at.date_naive()), due))
+        })
+        .collect())
+}
+
+/// 하위 task가 있으면 하위 task 진행도의 평균 (rollup 규칙 progress_from_children)
+fn rolled_up_progress(
+    id: i64,
+    rows: &HashMap<i64, DateRow>,
+    children: &HashMap<i64, Vec<i64>>,
+    depth: usize,
+) -> i64 {
+    let Some(row) = rows.get(&id) else { return 0 };
+    let kids = children.get(&id).map(Vec::as_slice).unwrap_or_default();
+    if row.status == "Done" || kids.is_empty() || depth >= rows.len() {
+        return actual_progress(&row.status, row.stage.as_deref());
+    }
+    let sum: i64 = kids.iter().map(|&c| rolled_up_progress(c, rows, children, depth + 1)).sum();
+    (sum as f64 / kids.len() as f64).round() as i64
+}
+
+/// ids의 task와 그 상위 task들 (상속 일정 계산용), descendants면 하위 task들도 (진행도 roll-up용)
+async fn load_date_rows(
+    conn: &mut SqliteConnection,
+    ids: &[i64],
+    descendants: bool,
+) -> Result<HashMap<i64, DateRow>, sqlx::Error> {
+    // UNION이므로 parent 사이클이 있어도 끝난다
+    let rows = sqlx::query_as::<_, DateRow>(
+        "WITH RECURSIVE
+            up(id) AS (
+                SELECT value FROM json_each(?1)
+                UNION
+                SELECT t.parent_task_id FROM tasks t JOIN up ON t.id = up.id WHERE t.parent_task_id IS NOT NULL
+            ),
+            down(id) AS (
+                SELECT value FROM json_each(?1) WHERE ?2
+                UNION
+                SELECT t.id FROM tasks t JOIN down ON t.parent_task_id = down.id
+            )
+         SELECT id, status, stage, parent_task_id, start_date, due_date, created_at FROM tasks
+         WHERE id IN (SELECT id FROM up UNION SELECT id FROM down)"
+    )
+    .bind(serde_json::to_string(ids).unwrap_or_default())
+    .bind(descendants)
+    .fetch_all(&mut *conn)
+    .await?;
+    Ok(rows.into_iter().map(|r| (r.id, r)).collect())
+}
+
+/// Task 응답에 ideal_progress, actual_progress, days_in_stage, is_late,
+/// stage_deadlines, stage_overdue를 채운다. 응답에 들어가는 task와 그 상위/하위 task만 읽는다.
+pub async fn annotate(db: &SqlitePool, tasks: &mut [Task]) -> Result<(), sqlx::Error> {
+    if tasks.is_empty() {
+        return Ok(());
+    }
+    let mut conn = db.acquire().await?;
+    let ids: Vec<i64> = tasks.iter().map(|t| t.id).collect();
+    let ids_json = serde_json::to_string(&ids).unwrap_or_default();
+
+    let (progress_rollup,): (bool,) = sqlx::query_as(
+        "SELECT EXISTS (SELECT 1 FROM rollup_rules WHERE rule = ? AND enabled = 1)"
+    )
+    .bind(crate::rollup::PROGRESS_FROM_CHILDREN)
+    .fetch_one(&mut *conn)
+    .await?;
+    let rows = load_date_rows(&mut conn, &ids, progress_rollup).await?;
+    let mut children: HashMap<i64, Vec<i64>> = HashMap::new();
+    if progress_rollup {
+        for row in rows.values() {
+            if let Some(pid) = row.parent_task_id {
+                children.entry(pid).or_default().push(row.id);
+            }
+        }
+    }
+
+    let entered: Vec<(i64, DateTime<Utc>)> = sqlx::query_as(
+        "SELECT task_id, MAX(entered_at) FROM task_stage_transitions
+         WHERE exited_at IS NULL AND task_id IN (SELECT value FROM json_each(?)) GROUP BY task_id"
+    )
+    .bind(&ids_json)
+    .fetch_all(&mut *conn)
+    .await?;
+    let entered: HashMap<i64, DateTime<Utc>> = entered.into_iter().collect();
+
+    let deadlines: Vec<(i64, String, NaiveDate)> = sqlx::query_as(
+        "SELECT task_id, stage, planned_date FROM task_stage_deadlines
+         WHERE task_id IN (SELECT value FROM json_each(?)) ORDER BY task_id, planned_date"
+    )
+    .bind(&ids_json)
+    .fetch_all(&mut *conn)
+    .await?;
+    let mut deadlines_by_task: HashMap<i64, Vec<StageDeadline>> = HashMap::new();
+    for (task_id, stage, planned_date) in deadlines {
+        deadlines_by_task
+            .entry(task_id)
+            .or_default()
+            .push(StageDeadline { stage, planned_date });
+    }
+
+    let now = Utc::now();
+    let today = now.date_naive();
+    for task in tasks.iter_mut() {
+        let (start, due) = effective_dates(task.id, &rows, 0);
+        let start = start.unwrap_or(task.created_at.date_naive());
+
+        task.ideal_progress = ideal_progress(start, due, today);
+        task.actual_progress = if children.contains_key(&task.id) {
+            rolled_up_progress(task.id, &rows, &children, 0)
+        } else {
+            actual_progress(&task.status, task.stage.as_deref())
+        };
+
+        // 전이 기록이 없는 예전 task: Pending이면 생성 시각, 아니면 마지막 수정 시각
+        let since = entered.get(&task.id).copied().unwrap_or(if task.status == "Pending" {
+            task.created_at
+        } else {
+            task.updated_at
+        });
+        task.days_in_stage = Some((now - since).num_days().max(0));
+
+        task.is_late = task.status != "Done"
+            && due.is_some_and(|d| today > d || task.actual_progress < task.ideal_progress);
+
+        task.stage_deadlines = deadlines_by_task.remove(&task.id).unwrap_or_default();
+        task.stage_overdue = task.status == "InProgress"
+            && task.stage_deadlines.iter().any(|d| {
+                Some(d.stage.as_str()) == task.stage.as_deref() && today > d.planned_date
+            });
+    }
+
+    Ok(())
+}
+
+/// 상태/단계 전이 기록: 현재 머물던 기록을 닫고 새 상태의 기록을 연다
+pub async fn record_transition(
+    conn: &mut SqliteConnection,
+    task: &Task,
+    to_status: &str,
+    to_stage: Option<&str>,
+    actor: Option<&str>,
+) -> Result<(), sqlx::Error> {
+    if task.status == to_status && task.stage.as_deref() == to_stage {
+        return Ok(());
+    }
+
+    let now = Utc::now();
+    sqlx::query(
+        "UPDATE task_stage_transitions SET exited_at = ? WHERE task_id = ? AND exited_at IS NULL"
+    )
+    .bind(now)
+    .bind(task.id)
+    .execute(&mut *conn)
+    .await?;
+
+    sqlx::query(
+        "INSERT INTO task_stage_transitions
+            (task_id, from_status, from_stage, to_status, to_stage, actor, entered_at)
+         VALUES (?, ?, ?, ?, ?, ?, ?)"
+    )
+    .bind(task.id)
+    .bind(&task.status)
+    .bind(&task.stage)
+    .bind(to_status)
+    .bind(to_stage)
+    .bind(actor)
+    .bind(now)
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(())
+}
@@ -0,0 +1,213 @@
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::SqlitePool;
+
+// ─────────────────────────────────────────────
+// DB snapshot: 서버가 돌아가는 중에 `VACUUM INTO`로 일관된 DB 사본을 만든다
+//
+// - VACUUM INTO는 pool의 connection 하나에서 읽기 transaction으로 실행된다.
+//   DB가 WAL 모드이므로 (db::init_db) 그동안 다른 handler의 읽기와 쓰기는 막히지 않고,
+//   사본에는 시작 시점의 내용만 들어간다. 쓰기끼리 겹치면 busy_timeout만큼 기다린다.
+// - 파일 작업(디렉터리 생성, 이름 바꾸기, 목록, 지우기)은 spawn_blocking에서
+// - 한 번에 하나만 (예약 실행과 수동 실행이 겹치면 뒤의 것이 기다린다)
+// - <dir>/kanban-<시각>.db.tmp에 쓰고 끝나면 이름을 바꾼다 (덜 쓴 파일은 목록에 안 보임)
+// - 가장 최근 keep개만 남기고 지운다
+//
+// 설정 (환경 변수)
+//   KANBAN_SNAPSHOT_DIR               저장 디렉터리 (기본 "backups")
+//   KANBAN_SNAPSHOT_INTERVAL_MINUTES  주기, 0이면 예약 실행 안 함 (기본 1440 = 하루)
+//   KANBAN_SNAPSHOT_KEEP              남길 개수 (기본 7)
+// ─────────────────────────────────────────────
+
+const FILE_PREFIX: &str = "kanban-";
+const FILE_SUFFIX: &str = ".db";
+const DEFAULT_DIR: &str = "backups";
+const DEFAULT_INTERVAL_MINUTES: u64 = 24 * 60;
+const DEFAULT_KEEP: usize = 7;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SnapshotInfo {
+    pub file: String,
+    pub size: u64,                   // bytes
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LastSnapshot {
+    #[serde(flatten)]
+    pub snapshot: SnapshotInfo,
+    pub duration_ms: u64,
+}
+
+// GET /api/snapshots
+#[derive(Debug, Serialize)]
+pub struct SnapshotStatus {
+    pub dir: String,
+    pub interval_minutes: u64,       // 0 = 예약 실행 안 함
+    pub keep: usize,
+    pub last: Option<LastSnapshot>,  // 이 프로세스에서 마지막으로 만든 것
+    pub last_error: Option<String>,
+    pub snapshots: Vec<SnapshotInfo>, // 디렉터리에 남아 있는 것 (최근 것부터)
+}
+
+pub struct Snapshots {
+    dir: PathBuf,
+    interval_minutes: u64,
+    keep: usize,
+    running: tokio::sync::Mutex<()>,
+    last: Mutex<(Option<LastSnapshot>, Option<String>)>,
+}
+
+impl Snapshots {
+    pub fn from_env() -> Self {
+        fn var<T: std::str::FromStr>(name: &str, default: T) -> T {
+            match std::env::var(name) {
+                Ok(v) => v.trim().parse().unwrap_or_else(|_| {
+                    eprintln!("Ignoring invalid {}={:?}", name, v);
+                    default
+                }),
+                Err(_) => default,
+            }
+        }
+        Self::new(
+            PathBuf::from(std::env::var("KANBAN_SNAPSHOT_DIR").unwrap_or_else(|_| DEFAULT_DIR.to_string())),
+            var("KANBAN_SNAPSHOT_INTERVAL_MINUTES", DEFAULT_INTERVAL_MINUTES),
+            var("KANBAN_SNAPSHOT_KEEP", DEFAULT_KEEP),
+        )
+    }
+
+    pub fn new(dir: PathBuf, interval_minutes: u64, keep: usize) -> Self {
+        Self {
+            dir,
+            interval_minutes,
+            keep: keep.max(1),
+            running: tokio::sync::Mutex::new(()),
+            last: Mutex::new((None, None)),
+        }
+    }
+
+    /// 주기마다 snapshot을 만드는 task를 띄운다 (서버 시작 직후에는 만들지 않음)
+    pub fn spawn_schedule(self: &Arc<Self>, db: SqlitePool) {
+        if self.interval_minutes == 0 {
+            return;
+        }
+        let snapshots = Arc::clone(self);
+        let period = Duration::from_secs(self.interval_minutes * 60);
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval_at(tokio::time::Instant::now() + period, period);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                interval.tick().await;
+                match snapshots.take(&db).await {
+                    Ok(last) => println!("Saved database snapshot {} ({} bytes)", last.snapshot.file, last.snapshot.size),
+                    Err(e) => eprintln!("Database snapshot failed: {}", e),
+                }
+            }
+        });
+    }
+
+    /// 지금 snapshot을 하나 만들고 오래된 것을 지운다
+    pub async fn take(&self, db: &SqlitePool) -> Result<LastSnapshot, String> {
+        let _running = self.running.lock().await;
+        let result = self.write_snapshot(db).await;
+        let mut last = self.last.lock().unwrap();
+        match &result {
+            Ok(snapshot) => *last = (Some(snapshot.clone()), None),
+            Err(e) => last.1 = Some(e.clone()),
+        }
+        result
+    }
+
+    async fn write_snapshot(&self, db: &SqlitePool) -> Result<LastSnapshot, String> {
+        let started = Instant::now();
+        let created_at = Utc::now();
+        let file = format!("{}{}{}", FILE_PREFIX, created_at.format("%Y%m%d-%H%M%S-%3f"), FILE_SUFFIX);
+        let path = self.dir.join(&file);
+        let tmp = self.dir.join(format!("{}.tmp", file));
+
+        let (dir, stale) = (self.dir.clone(), tmp.clone());
+        blocking(move || {
+            std::fs::create_dir_all(&dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+            // VACUUM INTO는 이미 있는 파일에는 쓰지 않는다 (지난번 실패로 남은 것)
+            let _ = std::fs::remove_file(&stale);
+            Ok::<_, String>(())
+        })
+        .await??;
+
+        let mut conn = db.acquire().await.map_err(|e| e.to_string())?;
+        let vacuum = sqlx::query("VACUUM INTO ?")
+            .bind(tmp.to_string_lossy().into_owned())
+            .execute(&mut *conn)
+            .await;
+        drop(conn);
+        if let Err(e) = vacuum {
+            let message = format!("VACUUM INTO {}: {}", tmp.display(), e);
+            let _ = blocking(move || std::fs::remove_file(&tmp)).await;
+            return Err(message);
+        }
+
+        let (dir, keep) = (self.dir.clone(), self.keep);
+        let size = blocking(move || {
+            std::fs::rename(&tmp, &path).map_err(|e| format!("{}: {}", path.display(), e))?;
+            let size = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+            prune(&dir, keep);
+            Ok::<_, String>(size)
+        })
+        .await??;
+        Ok(LastSnapshot {
+            snapshot: SnapshotInfo { file, size, created_at },
+            duration_ms: started.elapsed().as_millis() as u64,
+        })
+    }
+
+    pub async fn status(&self) -> SnapshotStatus {
+        let dir = self.dir.clone();
+        let snapshots = blocking(move || list(&dir)).await.unwrap_or_default();
+        let (last, last_error) = self.last.lock().unwrap().clone();
+        SnapshotStatus {
+            dir: self.dir.to_string_lossy().into_owned(),
+            interval_minutes: self.interval_minutes,
+            keep: self.keep,
+            last,
+            last_error,
+            snapshots,
+        }
+    }
+}
+
+/// 파일 작업은 tokio worker를 막지 않도록 blocking thread에서
+async fn blocking<T: Send + 'static>(f: impl FnOnce() -> T + Send + 'static) -> Result<T, String> {
+    tokio::task::spawn_blocking(f).await.map_err(|e| e.to_string())
+}
+
+/// 최근 keep개만 남긴다. 지우지 못한 파일은 다음에 다시 시도.
+fn prune(dir: &Path, keep: usize) {
+    for old in list(dir).into_iter().skip(keep) {
+        if let Err(e) = std::fs::remove_file(dir.join(&old.file)) {
+            eprintln!("Failed to remove old snapshot {}: {}", old.file, e);
+        }
+    }
+}
+
+/// 디렉터리의 snapshot 파일, 최근 것부터 (파일 이름의 시각 순)
+fn list(dir: &Path) -> Vec<SnapshotInfo> {
+    let Ok(entries) = std::fs::read_dir(dir) else { return Vec::new() };
+    let mut snapshots: Vec<SnapshotInfo> = entries
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let file = entry.file_name().into_string().ok()?;
+            if !file.starts_with(FILE_PREFIX) || !file.ends_with(FILE_SUFFIX) {
+                return None;
+            }
+            let meta = entry.metadata().ok()?;
+            let created_at = meta.modified().ok().map(DateTime::<Utc>::from)?;
+            Some(SnapshotInfo { file, size: meta.len(), created_at })
+        })
+        .collect();
+    snapshots.sort_by(|a, b| b.file.cmp(&a.file));
+    snapshots
+}
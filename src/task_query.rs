@@ -0,0 +1,229 @@
+use chrono::{NaiveDate, Utc};
+use sqlx::{QueryBuilder, Sqlite, SqliteConnection};
+
+use crate::error::ApiError;
+use crate::models::{Task, TaskFilter, TaskListQuery, TaskPageQuery};
+
+// ─────────────────────────────────────────────
+// Task 목록 조회: 필터 / 정렬 / cursor 페이지
+//
+// - 필터는 GET /api/tasks, bulk, 저장된 view가 같이 쓴다
+// - cursor는 이전 페이지 마지막 task의 id. 그 task의 정렬 값 다음부터 이어서 읽는다 (keyset)
+// ─────────────────────────────────────────────
+
+pub const MAX_LIMIT: i64 = 1000;
+
+// 정렬 키 → ORDER BY 식 (id가 항상 마지막 tie-breaker).
+// keyset 비교를 위해 NULL이 없는 식으로 바꾼다 (날짜가 없으면 맨 뒤).
+const SORT_KEYS: &[(&str, &[&str])] = &[
+    ("tree", &["COALESCE(parent_task_id, 0)", "sort_order"]),
+    ("id", &[]),
+    ("title", &["title"]),
+    ("status", &["status"]),
+    ("assignee", &["COALESCE(assignee, '')"]),
+    ("start_date", &["COALESCE(start_date, '9999-12-31')"]),
+    ("due_date", &["COALESCE(due_date, '9999-12-31')"]),
+    ("created_at", &["created_at"]),
+    ("updated_at", &["updated_at"]),
+];
+
+pub struct TaskPage {
+    pub tasks: Vec<Task>,
+    pub next_cursor: Option<i64>,    // 다음 페이지가 있으면 이 페이지 마지막 task id
+}
+
+pub fn is_empty(filter: &TaskFilter) -> bool {
+    filter.status.is_none()
+        && filter.stage.is_none()
+        && filter.assignee.is_none()
+        && filter.project_key.is_none()
+        && filter.tag.is_none()
+        && filter.due_before.is_none()
+        && filter.due_after.is_none()
+        && filter.due_within.is_none()
+        && filter.overdue.is_none()
+        && filter.has_jira.is_none()
+        && filter.parent_task_id.is_none()
+        && filter.q.is_none()
+}
+
+/// WHERE 절 뒤에 filter 조건을 " AND ..."로 붙인다
+pub fn push_filter(qb: &mut QueryBuilder<'_, Sqlite>, filter: &TaskFilter, today: NaiveDate) {
+    let columns = [
+        ("status", &filter.status),
+        ("stage", &filter.stage),
+        ("assignee", &filter.assignee),
+        ("project_key", &filter.project_key),
+    ];
+    for (column, value) in columns {
+        if let Some(value) = value {
+            qb.push(format!(" AND {} = ", column)).push_bind(value.clone());
+        }
+    }
+    if let Some(tag) = filter.tag.as_deref().map(str::trim) {
+        // "a, b,c" → ",a,b,c," 에서 ",tag," 찾기
+        qb.push(" AND instr(',' || REPLACE(REPLACE(TRIM(COALESCE(tags, '')), ', ', ','), ' ,', ',') || ',', ")
+            .push_bind(format!(",{},", tag))
+            .push(") > 0");
+    }
+    if let Some(date) = filter.due_before {
+        qb.push(" AND due_date <= ").push_bind(date.to_string());
+    }
+    if let Some(date) = filter.due_after {
+        qb.push(" AND due_date >= ").push_bind(date.to_string());
+    }
+    if let Some(days) = filter.due_within {
+        qb.push(" AND due_date >= ")
+            .push_bind(today.to_string())
+            .push(" AND due_date <= ")
+            .push_bind(today.checked_add_signed(chrono::Duration::days(days.clamp(0, 36500))).unwrap_or(NaiveDate::MAX).to_string());
+    }
+    if let Some(overdue) = filter.overdue {
+        qb.push(if overdue { " AND " } else { " AND NOT " })
+            .push("(due_date IS NOT NULL AND status != 'Done' AND due_date < ")
+            .push_bind(today.to_string())
+            .push(")");
+    }
+    if let Some(has_jira) = filter.has_jira {
+        qb.push(if has_jira { " AND jira_ticket_key IS NOT NULL" } else { " AND jira_ticket_key IS NULL" });
+    }
+    if let Some(parent) = filter.parent_task_id {
+        qb.push(" AND parent_task_id = ").push_bind(parent);
+    }
+    if let Some(text) = filter.q.as_deref().map(str::trim).filter(|q| !q.is_empty()) {
+        let pattern = format!("%{}%", text.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_"));
+        qb.push(" AND (");
+        let mut fields = qb.separated(" OR ");
+        for column in ["title", "description", "alias", "keywords", "jira_ticket_key"] {
+            fields.push(format!("{} LIKE ", column)).push_bind_unseparated(pattern.clone()).push_unseparated(" ESCAPE '\\'");
+        }
+        qb.push(")");
+    }
+}
+
+/// filter에 맞는 board의 task id (id 순)
+pub async fn filter_ids(
+    conn: &mut SqliteConnection,
+    board_id: i64,
+    filter: &TaskFilter,
+) -> Result<Vec<i64>, sqlx::Error> {
+    let mut qb = QueryBuilder::new("SELECT id FROM tasks WHERE board_id = ");
+    qb.push_bind(board_id);
+    push_filter(&mut qb, filter, Utc::now().date_naive());
+    qb.push(" ORDER BY id");
+    let rows: Vec<(i64,)> = qb.build_query_as().fetch_all(&mut *conn).await?;
+    Ok(rows.into_iter().map(|(id,)| id).collect())
+}
+
+/// 정렬 키와 limit 검사 (view를 저장할 때도 미리 확인)
+pub fn validate(page: &TaskPageQuery) -> Result<(), ApiError> {
+    sort_exprs(page.sort.as_deref())?;
+    if let Some(limit) = page.limit
+        && !(1..=MAX_LIMIT).contains(&limit)
+    {
+        return Err(ApiError::bad_request(format!("limit must be between 1 and {}", MAX_LIMIT)));
+    }
+    Ok(())
+}
+
+/// "-due_date" → (["COALESCE(due_date, ...)", "id"], 내림차순)
+fn sort_exprs(sort: Option<&str>) -> Result<(Vec<&'static str>, bool), ApiError> {
+    let sort = sort.unwrap_or("tree");
+    let (key, desc) = match sort.strip_prefix('-') {
+        Some(key) => (key, true),
+        None => (sort, false),
+    };
+    let Some((_, exprs)) = SORT_KEYS.iter().find(|(k, _)| *k == key) else {
+        let keys: Vec<&str> = SORT_KEYS.iter().map(|(k, _)| *k).collect();
+        return Err(ApiError::bad_request(format!("unknown sort key: {}", key)).with("sort_keys", keys));
+    };
+    Ok((exprs.iter().copied().chain(["id"]).collect(), desc))
+}
+
+/// board의 task 목록 한 페이지
+pub async fn list(
+    conn: &mut SqliteConnection,
+    board_id: i64,
+    query: &TaskListQuery,
+) -> Result<TaskPage, ApiError> {
+    let page = &query.page;
+    validate(page)?;
+    let (exprs, desc) = sort_exprs(page.sort.as_deref())?;
+    let limit = page.limit;
+
+    let mut qb = QueryBuilder::new("SELECT * FROM tasks WHERE board_id = ");
+    qb.push_bind(board_id);
+    push_filter(&mut qb, &query.filter, Utc::now().date_naive());
+    if let Some(cursor) = page.cursor {
+        let exists: Option<(i64,)> = sqlx::query_as("SELECT id FROM tasks WHERE id = ? AND board_id = ?")
+            .bind(cursor)
+            .bind(board_id)
+            .fetch_optional(&mut *conn)
+            .await?;
+        if exists.is_none() {
+            return Err(ApiError::bad_request(format!(
+                "cursor task {} no longer exists; start again without a cursor",
+                cursor
+            )));
+        }
+        let tuple = exprs.join(", ");
+        qb.push(format!(
+            " AND ({}) {} (SELECT {} FROM tasks WHERE id = ",
+            tuple,
+            if desc { "<" } else { ">" },
+            tuple
+        ))
+        .push_bind(cursor)
+        .push(")");
+    }
+    let order: Vec<String> = exprs.iter().map(|e| format!("{}{}", e, if desc { " DESC" } else { "" })).collect();
+    qb.push(format!(" ORDER BY {}", order.join(", ")));
+    if let Some(limit) = limit {
+        // 다음 페이지가 있는지 보려고 하나 더 읽는다
+        qb.push(" LIMIT ").push_bind(limit + 1);
+    }
+
+    let mut tasks: Vec<Task> = qb.build_query_as().fetch_all(&mut *conn).await?;
+    let next_cursor = match limit {
+        Some(limit) if tasks.len() as i64 > limit => {
+            tasks.truncate(limit as usize);
+            tasks.last().map(|t| t.id)
+        }
+        _ => None,
+    };
+
+    if page.include_ancestors {
+        let mut ancestors = ancestors_of(conn, &tasks).await?;
+        ancestors.append(&mut tasks);
+        tasks = ancestors;
+    }
+    Ok(TaskPage { tasks, next_cursor })
+}
+
+/// tasks의 상위 task 중 tasks에 없는 것 (트리 순서, context_only 표시)
+async fn ancestors_of(conn: &mut SqliteConnection, tasks: &[Task]) -> Result<Vec<Task>, sqlx::Error> {
+    let ids: Vec<i64> = tasks.iter().map(|t| t.id).collect();
+    let parents: Vec<i64> = tasks.iter().filter_map(|t| t.parent_task_id).collect();
+    if parents.is_empty() {
+        return Ok(Vec::new());
+    }
+    let mut ancestors = sqlx::query_as::<_, Task>(
+        "WITH RECURSIVE ancestors(id) AS (
+            SELECT value FROM json_each(?1)
+            UNION
+            SELECT t.parent_task_id FROM tasks t JOIN ancestors a ON t.id = a.id
+             WHERE t.parent_task_id IS NOT NULL
+         )
+         SELECT * FROM tasks
+         WHERE id IN (SELECT id FROM ancestors) AND id NOT IN (SELECT value FROM json_each(?2))
+         ORDER BY parent_task_id NULLS FIRST, sort_order ASC, id ASC"
+    )
+    .bind(serde_json::to_string(&parents).unwrap_or_default())
+    .bind(serde_json::to_string(&ids).unwrap_or_default())
+    .fetch_all(&mut *conn)
+    .await?;
+    for task in &mut ancestors {
+        task.context_only = true;
+    }
+    Ok(ancestors)
+}
@@ -0,0 +1,59 @@
+// ─────────────────────────────────────────────
+// 테스트 공통: in-memory DB, 사용자/세션, router
+// ─────────────────────────────────────────────
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, OnceLock};
+
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+use sqlx::{SqliteConnection, SqlitePool};
+
+use crate::{auth, db, snapshot, AppState, EventHub, JiraClient};
+
+pub const PASSWORD: &str = "password123";
+
+/// 스키마만 만든 새 in-memory DB (기본 board와 workflow 포함)
+pub async fn memory_db() -> SqlitePool {
+    static NEXT: AtomicUsize = AtomicUsize::new(0);
+    // 같은 이름의 shared-cache in-memory DB는 pool의 connection끼리 공유된다
+    let options = SqliteConnectOptions::new()
+        .filename(format!("file:kanban-test-{}?mode=memory&cache=shared", NEXT.fetch_add(1, Ordering::Relaxed)));
+    // connection이 모두 닫히면 in-memory DB가 사라지므로 하나는 계속 열어 둔다
+    let pool = SqlitePoolOptions::new()
+        .min_connections(1)
+        .idle_timeout(None)
+        .max_lifetime(None)
+        .connect_with(options)
+        .await
+        .unwrap();
+    db::create_schema(&pool).await.unwrap();
+    pool
+}
+
+/// PASSWORD의 hash (argon2가 느리므로 한 번만)
+fn password_hash() -> &'static str {
+    static HASH: OnceLock<String> = OnceLock::new();
+    HASH.get_or_init(|| auth::hash_password(PASSWORD).unwrap())
+}
+
+/// 사용자를 만들고 (id, session token)을 돌려준다
+pub async fn add_user(conn: &mut SqliteConnection, username: &str, role: &str) -> (i64, String) {
+    let (id,): (i64,) = sqlx::query_as("INSERT INTO users (username, password_hash, role) VALUES (?, ?, ?) RETURNING id")
+        .bind(username)
+        .bind(password_hash())
+        .bind(role)
+        .fetch_one(&mut *conn)
+        .await
+        .unwrap();
+    let (token, _) = auth::create_session(conn, id).await.unwrap();
+    (id, token)
+}
+
+pub fn app(db: SqlitePool) -> axum::Router {
+    crate::app(AppState {
+        db,
+        jira: Arc::new(JiraClient::new()),
+        events: Arc::new(EventHub::new()),
+        snapshots: Arc::new(snapshot::Snapshots::new(std::env::temp_dir().join("vpc-kanban-tests"), 0, 1)),
+    })
+}
@@ -0,0 +1,173 @@
+use std::collections::HashMap;
+
+use chrono::Utc;
+use sqlx::{FromRow, SqliteConnection, SqlitePool};
+
+use crate::auth::CurrentUser;
+use crate::error::ApiError;
+use crate::models::{Task, TaskNode};
+use crate::{markdown, schedule};
+
+// ─────────────────────────────────────────────
+// Task 트리: parent_task_id 기반 계층 조회 / 이동
+// ─────────────────────────────────────────────
+
+// 잘못된 데이터로 parent 사이클이 생겨도 재귀 CTE가 끝나도록 하는 상한
+const MAX_TREE_DEPTH: i64 = 64;
+
+#[derive(FromRow)]
+struct TreeRow {
+    #[sqlx(flatten)]
+    task: Task,
+    depth: i64,
+}
+
+/// root(없으면 board의 최상위 task 전체)부터 max_depth까지의 subtree
+pub async fn load_tree(
+    db: &SqlitePool,
+    user: &CurrentUser,
+    board_id: i64,
+    root: Option<i64>,
+    max_depth: Option<i64>,
+) -> Result<Vec<TaskNode>, sqlx::Error> {
+    let max_depth = max_depth.unwrap_or(MAX_TREE_DEPTH).clamp(0, MAX_TREE_DEPTH);
+    let rows = sqlx::query_as::<_, TreeRow>(
+        "WITH RECURSIVE tree(id, depth) AS (
+            SELECT id, 0 FROM tasks
+             WHERE CASE WHEN ?1 IS NULL THEN parent_task_id IS NULL AND board_id = ?3 ELSE id = ?1 END
+            UNION ALL
+            SELECT t.id, tree.depth + 1 FROM tasks t
+              JOIN tree ON t.parent_task_id = tree.id
+             WHERE tree.depth < ?2
+         )
+         SELECT tasks.*, tree.depth FROM tasks JOIN tree ON tasks.id = tree.id
+         ORDER BY tree.depth, tasks.sort_order, tasks.id"
+    )
+    .bind(root)
+    .bind(max_depth)
+    .bind(board_id)
+    .fetch_all(db)
+    .await?;
+
+    let (mut tasks, depths): (Vec<Task>, Vec<i64>) = rows.into_iter().map(|r| (r.task, r.depth)).unzip();
+    schedule::annotate(db, &mut tasks).await?;
+    markdown::annotate_tasks(db, user, &mut tasks).await?;
+
+    // 깊은 것부터 부모에 붙여 올라간다 (rows는 depth 오름차순)
+    let mut nodes: Vec<Option<TaskNode>> = tasks
+        .into_iter()
+        .zip(depths)
+        .map(|(task, depth)| Some(TaskNode { task, depth, children: vec![] }))
+        .collect();
+    let index: HashMap<i64, usize> = nodes
+        .iter()
+        .enumerate()
+        .map(|(i, n)| (n.as_ref().unwrap().task.id, i))
+        .collect();
+
+    for i in (0..nodes.len()).rev() {
+        let node = nodes[i].as_ref().unwrap();
+        let parent_idx = match node.task.parent_task_id.and_then(|p| index.get(&p)) {
+            Some(&p) if node.depth > 0 => p,
+            _ => continue,
+        };
+        let node = nodes[i].take().unwrap();
+        if let Some(parent) = nodes[parent_idx].as_mut() {
+            parent.children.insert(0, node);
+        }
+    }
+
+    Ok(nodes.into_iter().flatten().collect())
+}
+
+/// candidate가 ancestor 자신이거나 그 하위 task인지
+pub async fn is_in_subtree(
+    conn: &mut SqliteConnection,
+    ancestor: i64,
+    candidate: i64,
+) -> Result<bool, sqlx::Error> {
+    let (found,): (bool,) = sqlx::query_as(
+        "WITH RECURSIVE sub(id, depth) AS (
+            SELECT ?1, 0
+            UNION ALL
+            SELECT t.id, sub.depth + 1 FROM tasks t JOIN sub ON t.parent_task_id = sub.id
+             WHERE sub.depth < ?3
+         )
+         SELECT EXISTS (SELECT 1 FROM sub WHERE id = ?2)"
+    )
+    .bind(ancestor)
+    .bind(candidate)
+    .bind(MAX_TREE_DEPTH)
+    .fetch_one(&mut *conn)
+    .await?;
+    Ok(found)
+}
+
+/// task(와 그 subtree)를 new_parent 아래 position 위치로 옮긴다.
+/// 자기 자신이나 하위 task 아래로 옮기는 것은 사이클이므로 거부.
+pub async fn move_task(
+    conn: &mut SqliteConnection,
+    task: &Task,
+    new_parent: Option<i64>,
+    position: Option<usize>,
+) -> Result<(), ApiError> {
+    let id = task.id;
+    if let Some(parent) = new_parent {
+        let parent_board: Option<(i64,)> = sqlx::query_as("SELECT board_id FROM tasks WHERE id = ?")
+            .bind(parent)
+            .fetch_optional(&mut *conn)
+            .await?;
+        match parent_board {
+            None => return Err(ApiError::not_found(format!("parent task {} not found", parent))),
+            // 다른 board로는 POST /api/tasks/:id/board로 옮긴다
+            Some((board_id,)) if board_id != task.board_id => {
+                return Err(ApiError::bad_request(format!(
+                    "parent task {} is on a different board; move the task to that board first",
+                    parent
+                )));
+            }
+            Some(_) => {}
+        }
+        if is_in_subtree(conn, id, parent).await? {
+            return Err(ApiError::conflict(
+                "cycle",
+                format!("task {} cannot be moved under itself or its descendant {}", id, parent),
+            ));
+        }
+    }
+
+    // 새 형제 목록에서 position 위치에 끼워 넣고 sort_order를 다시 매긴다
+    let mut siblings: Vec<i64> = sqlx::query_as::<_, (i64,)>(
+        "SELECT id FROM tasks WHERE parent_task_id IS ? AND board_id = ? AND id != ? ORDER BY sort_order, id"
+    )
+    .bind(new_parent)
+    .bind(task.board_id)
+    .bind(id)
+    .fetch_all(&mut *conn)
+    .await?
+    .into_iter()
+    .map(|(sid,)| sid)
+    .collect();
+    let position = position.unwrap_or(siblings.len()).min(siblings.len());
+    siblings.insert(position, id);
+
+    sqlx::query("UPDATE tasks SET parent_task_id = ?, updated_at = ?, version = version + 1 WHERE id = ?")
+        .bind(new_parent)
+        .bind(Utc::now())
+        .bind(id)
+        .execute(&mut *conn)
+        .await?;
+    for (order, sid) in siblings.iter().enumerate() {
+        sqlx::query("UPDATE tasks SET sort_order = ? WHERE id = ?")
+            .bind(order as i64)
+            .bind(sid)
+            .execute(&mut *conn)
+            .await?;
+    }
+    // due가 없는 subtree는 새 상위 task의 due를 따른다
+    if new_parent != task.parent_task_id {
+        schedule::refresh_stage_deadlines(conn, id).await?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,104 @@
+use sqlx::SqliteConnection;
+
+use crate::auth::{BoardRole, CurrentUser};
+use crate::error::ApiError;
+use crate::models::{Board, SavedView, TaskListQuery};
+use crate::{boards, task_query};
+
+// ─────────────────────────────────────────────
+// 저장된 view: task 목록 조회 조건에 이름을 붙여 둔 것
+//
+// - 기본은 만든 사람만 보고, shared면 그 board를 볼 수 있는 모든 사용자에게 보인다
+// - 공유는 board member 이상만, 남의 공유 view 수정/삭제는 board admin만
+// - assignee가 "@me"면 실행하는 사용자로 바꿔서 조회 (공유 view "내 task")
+// ─────────────────────────────────────────────
+
+pub const ME: &str = "@me";
+const MAX_NAME_LEN: usize = 100;
+
+const SELECT_VIEW: &str = "SELECT v.*, b.key AS board, u.username AS owner
+     FROM saved_views v
+     JOIN boards b ON b.id = v.board_id
+     JOIN users u ON u.id = v.owner_id";
+
+/// 볼 수 없는 (남의 비공개) view는 없는 것처럼 404
+pub async fn fetch_view(conn: &mut SqliteConnection, id: i64, user: &CurrentUser) -> Result<SavedView, ApiError> {
+    sqlx::query_as::<_, SavedView>(&format!("{} WHERE v.id = ?", SELECT_VIEW))
+        .bind(id)
+        .fetch_optional(&mut *conn)
+        .await?
+        .filter(|v| v.shared || v.owner_id == user.id || user.is_admin())
+        .ok_or_else(|| ApiError::not_found(format!("view {} not found", id)))
+}
+
+/// board의 view 중 user가 볼 수 있는 것 (본인 것 + 공유된 것)
+pub async fn list_views(
+    conn: &mut SqliteConnection,
+    board: &Board,
+    user: &CurrentUser,
+) -> Result<Vec<SavedView>, sqlx::Error> {
+    sqlx::query_as::<_, SavedView>(&format!(
+        "{} WHERE v.board_id = ? AND (v.shared = 1 OR v.owner_id = ?) ORDER BY v.shared, v.name COLLATE NOCASE, v.id",
+        SELECT_VIEW
+    ))
+    .bind(board.id)
+    .bind(user.id)
+    .fetch_all(&mut *conn)
+    .await
+}
+
+pub async fn board_of_view(conn: &mut SqliteConnection, id: i64) -> Result<Board, ApiError> {
+    let key: Option<(String,)> = sqlx::query_as(
+        "SELECT b.key FROM boards b JOIN saved_views v ON v.board_id = b.id WHERE v.id = ?"
+    )
+    .bind(id)
+    .fetch_optional(&mut *conn)
+    .await?;
+    match key {
+        Some((key,)) => boards::fetch_board(conn, &key).await,
+        None => Err(ApiError::not_found(format!("view {} not found", id))),
+    }
+}
+
+/// 본인 view이거나, 공유 view면 board admin
+pub fn check_editable(view: &SavedView, user: &CurrentUser, role: BoardRole) -> Result<(), ApiError> {
+    if view.owner_id == user.id || (view.shared && role >= BoardRole::Admin) {
+        return Ok(());
+    }
+    Err(ApiError::forbidden(format!("view {} belongs to {}", view.id, view.owner))
+        .with("owner", &view.owner))
+}
+
+pub fn check_share(shared: bool, role: BoardRole, board: &Board) -> Result<(), ApiError> {
+    if shared && role < BoardRole::Member {
+        return Err(ApiError::forbidden(format!("sharing a view requires the member role on board {}", board.key))
+            .with("board", &board.key)
+            .with("role", role)
+            .with("required", BoardRole::Member));
+    }
+    Ok(())
+}
+
+pub fn validate_name(name: &str) -> Result<String, ApiError> {
+    let name = name.trim();
+    if name.is_empty() || name.chars().count() > MAX_NAME_LEN {
+        return Err(ApiError::bad_request(format!("view name must be 1-{} characters", MAX_NAME_LEN)));
+    }
+    Ok(name.to_string())
+}
+
+/// 저장할 조건 검사. cursor는 페이지 위치라 저장하지 않는다.
+pub fn normalize_query(mut query: TaskListQuery) -> Result<TaskListQuery, ApiError> {
+    task_query::validate(&query.page)?;
+    query.page.cursor = None;
+    Ok(query)
+}
+
+/// 실행할 조건: "@me"를 user로 바꾼다
+pub fn resolve_query(view: &SavedView, user: &CurrentUser) -> TaskListQuery {
+    let mut query = view.query.clone();
+    if query.filter.assignee.as_deref() == Some(ME) {
+        query.filter.assignee = Some(user.username.clone());
+    }
+    query
+}
@@ -0,0 +1,329 @@
+use std::collections::HashMap;
+
+use sqlx::{SqliteConnection, SqlitePool};
+
+use crate::auth::BoardRole;
+use crate::error::ApiError;
+use crate::models::{BoardColumn, BoardSummary, Task, TransitionRule, TransitionRuleInput};
+
+// InProgress 세부 단계 (순서대로). frontend/app.js의 STAGES와 동일하게 유지.
+pub const STAGES: [&str; 5] = ["사양확인", "CCB", "개발", "검증", "리뷰"];
+
+pub const STATUSES: [&str; 3] = ["Pending", "InProgress", "Done"];
+
+pub const DEFAULT_WORKFLOW: &str = "default";
+
+/// 전이 규칙의 required_fields에 쓸 수 있는 task 필드
+pub const REQUIRABLE_FIELDS: [&str; 7] =
+    ["assignee", "description", "alias", "tags", "start_date", "due_date", "jira_ticket_key"];
+
+/// WIP 한도를 걸 수 있는 범위: InProgress 그룹 전체 또는 개별 stage
+pub fn is_wip_scope(scope: &str) -> bool {
+    scope == "InProgress" || STAGES.contains(&scope)
+}
+
+pub async fn load_wip_limits(db: &SqlitePool, board_id: i64) -> Result<HashMap<String, i64>, sqlx::Error> {
+    let rows: Vec<(String, i64)> = sqlx::query_as("SELECT scope, wip_limit FROM wip_limits WHERE board_id = ?")
+        .bind(board_id)
+        .fetch_all(db)
+        .await?;
+    Ok(rows.into_iter().collect())
+}
+
+/// 상태/단계별 현재 카드 수와 WIP 한도
+pub async fn board_summary(db: &SqlitePool, board_id: i64) -> Result<BoardSummary, sqlx::Error> {
+    let limits = load_wip_limits(db, board_id).await?;
+    let counts: Vec<(String, Option<String>, i64)> = sqlx::query_as(
+        "SELECT status, stage, COUNT(*) FROM tasks WHERE board_id = ? GROUP BY status, stage"
+    )
+    .bind(board_id)
+    .fetch_all(db)
+    .await?;
+
+    let count_of = |scope: &str| -> i64 {
+        counts
+            .iter()
+            .filter(|(status, stage, _)| {
+                status == scope || (status == "InProgress" && stage.as_deref() == Some(scope))
+            })
+            .map(|(_, _, n)| n)
+            .sum()
+    };
+
+    let scopes = ["Pending", "InProgress"]
+        .into_iter()
+        .chain(STAGES)
+        .chain(["Done"]);
+    let columns = scopes
+        .map(|scope| {
+            let count = count_of(scope);
+            let wip_limit = limits.get(scope).copied();
+            BoardColumn {
+                scope: scope.to_string(),
+                count,
+                wip_limit,
+                over_limit: wip_limit.is_some_and(|l| count > l),
+            }
+        })
+        .collect();
+
+    Ok(BoardSummary { columns })
+}
+
+/// WIP 한도 위반 내용
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct WipViolation {
+    pub scope: String,
+    pub wip_limit: i64,
+    pub count: i64,
+}
+
+impl WipViolation {
+    pub fn into_error(self) -> ApiError {
+        let message = format!(
+            "WIP limit for {} is {} and it already holds {} task(s); pass \"override\": true with a \"reason\" to move anyway",
+            self.scope, self.wip_limit, self.count
+        );
+        ApiError::conflict("wip_limit_exceeded", message)
+            .with("scope", &self.scope)
+            .with("wip_limit", self.wip_limit)
+            .with("count", self.count)
+    }
+}
+
+/// task를 (status, stage)로 옮겼을 때 넘치게 되는 WIP 한도 목록.
+/// 이미 해당 범위에 있던 task는 다시 세지 않는다.
+pub async fn check_wip(
+    conn: &mut SqliteConnection,
+    task: &Task,
+    status: &str,
+    stage: Option<&str>,
+) -> Result<Vec<WipViolation>, sqlx::Error> {
+    let mut violations = Vec::new();
+    if status != "InProgress" {
+        return Ok(violations);
+    }
+
+    let limits: Vec<(String, i64)> = sqlx::query_as("SELECT scope, wip_limit FROM wip_limits WHERE board_id = ?")
+        .bind(task.board_id)
+        .fetch_all(&mut *conn)
+        .await?;
+    let limits: HashMap<String, i64> = limits.into_iter().collect();
+
+    if task.status != "InProgress" && let Some(&limit) = limits.get("InProgress") {
+        let (count,): (i64,) = sqlx::query_as(
+            "SELECT COUNT(*) FROM tasks WHERE board_id = ? AND status = 'InProgress' AND id != ?"
+        )
+        .bind(task.board_id)
+        .bind(task.id)
+        .fetch_one(&mut *conn)
+        .await?;
+        if count >= limit {
+            violations.push(WipViolation { scope: "InProgress".to_string(), wip_limit: limit, count });
+        }
+    }
+
+    if let Some(stage) = stage {
+        let same_stage = task.status == "InProgress" && task.stage.as_deref() == Some(stage);
+        if !same_stage && let Some(&limit) = limits.get(stage) {
+            let (count,): (i64,) = sqlx::query_as(
+                "SELECT COUNT(*) FROM tasks WHERE board_id = ? AND status = 'InProgress' AND stage = ? AND id != ?"
+            )
+            .bind(task.board_id)
+            .bind(stage)
+            .bind(task.id)
+            .fetch_one(&mut *conn)
+            .await?;
+            if count >= limit {
+                violations.push(WipViolation { scope: stage.to_string(), wip_limit: limit, count });
+            }
+        }
+    }
+
+    Ok(violations)
+}
+
+// ─── Transition rules ────────────────────────────────────────────────────────
+
+/// 전이 규칙에서 쓰는 task의 상태 이름: InProgress면 stage, 아니면 status
+pub fn state_key(status: &str, stage: Option<&str>) -> String {
+    match (status, stage) {
+        ("InProgress", Some(stage)) => stage.to_string(),
+        _ => status.to_string(),
+    }
+}
+
+fn is_state(state: &str) -> bool {
+    STATUSES.contains(&state) || STAGES.contains(&state)
+}
+
+fn split_list(list: Option<&str>) -> Vec<&str> {
+    list.unwrap_or("")
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+fn field_present(task: &Task, field: &str) -> bool {
+    let non_empty = |v: &Option<String>| v.as_deref().is_some_and(|s| !s.trim().is_empty());
+    match field {
+        "assignee" => non_empty(&task.assignee),
+        "description" => non_empty(&task.description),
+        "alias" => non_empty(&task.alias),
+        "tags" => non_empty(&task.tags),
+        "jira_ticket_key" => non_empty(&task.jira_ticket_key),
+        "start_date" => task.start_date.is_some(),
+        "due_date" => task.due_date.is_some(),
+        _ => false,
+    }
+}
+
+pub async fn list_transitions(
+    conn: &mut SqliteConnection,
+    workflow: &str,
+) -> Result<Vec<TransitionRule>, sqlx::Error> {
+    sqlx::query_as::<_, TransitionRule>(
+        "SELECT * FROM workflow_transitions WHERE workflow = ? ORDER BY id ASC"
+    )
+    .bind(workflow)
+    .fetch_all(&mut *conn)
+    .await
+}
+
+/// 규칙 입력값 검증 (알 수 없는 상태, 필드, board 역할 이름 거부)
+pub fn validate_rule(rule: &TransitionRuleInput) -> Result<(), ApiError> {
+    if rule.from_state != "*" && !is_state(&rule.from_state) {
+        return Err(ApiError::bad_request(format!("unknown from_state: {}", rule.from_state)));
+    }
+    if !is_state(&rule.to_state) {
+        return Err(ApiError::bad_request(format!("unknown to_state: {}", rule.to_state)));
+    }
+    if let Some(role) = split_list(rule.allowed_roles.as_deref())
+        .into_iter()
+        .find(|r| BoardRole::parse(r).is_none())
+    {
+        return Err(ApiError::bad_request(format!(
+            "unknown role {} in allowed_roles; expected viewer, member or admin",
+            role
+        )));
+    }
+    if let Some(field) = split_list(rule.required_fields.as_deref())
+        .into_iter()
+        .find(|f| !REQUIRABLE_FIELDS.contains(f))
+    {
+        return Err(ApiError::bad_request(format!(
+            "unknown required field {}; expected one of {:?}",
+            field, REQUIRABLE_FIELDS
+        )));
+    }
+    Ok(())
+}
+
+/// before → after 전이가 workflow 규칙을 만족하는지 검사.
+/// workflow에 규칙이 없으면 모든 전이를 허용한다. 같은 상태 안에서의 변경은 검사하지 않는다.
+pub async fn check_transition(
+    conn: &mut SqliteConnection,
+    workflow: &str,
+    before: &Task,
+    after: &Task,
+    comment: Option<&str>,
+    role: Option<BoardRole>,
+) -> Result<(), ApiError> {
+    let from = state_key(&before.status, before.stage.as_deref());
+    let to = state_key(&after.status, after.stage.as_deref());
+    if from == to {
+        return Ok(());
+    }
+
+    let rules = list_transitions(conn, workflow).await?;
+    if rules.is_empty() {
+        return Ok(());
+    }
+
+    let rule = rules
+        .iter()
+        .find(|r| r.from_state == from && r.to_state == to)
+        .or_else(|| rules.iter().find(|r| r.from_state == "*" && r.to_state == to));
+    let Some(rule) = rule else {
+        let allowed: Vec<&str> = rules
+            .iter()
+            .filter(|r| r.from_state == from || r.from_state == "*")
+            .map(|r| r.to_state.as_str())
+            .collect();
+        return Err(ApiError::conflict(
+            "transition_not_allowed",
+            format!("workflow {} does not allow moving from {} to {}", workflow, from, to),
+        )
+        .with("from", &from)
+        .with("to", &to)
+        .with("allowed", allowed));
+    };
+
+    // 역할은 viewer < member < admin 순서: 나열된 것 중 가장 낮은 역할 이상이면 된다
+    // (allowed_roles는 규칙을 저장할 때 검사하므로 모두 board 역할)
+    let min_role = split_list(rule.allowed_roles.as_deref()).into_iter().filter_map(BoardRole::parse).min();
+    if let Some(min_role) = min_role
+        && role.is_none_or(|r| r < min_role)
+    {
+        return Err(ApiError::new(
+            axum::http::StatusCode::FORBIDDEN,
+            "transition_role_forbidden",
+            format!("moving from {} to {} requires the {} role or higher", from, to, min_role.as_str()),
+        )
+        .with("rule", rule)
+        .with("role", role)
+        .with("required", min_role));
+    }
+
+    let mut missing: Vec<&str> = split_list(rule.required_fields.as_deref())
+        .into_iter()
+        .filter(|f| !field_present(after, f))
+        .collect();
+    if rule.require_comment && comment.is_none_or(|c| c.trim().is_empty()) {
+        missing.push("comment");
+    }
+    if !missing.is_empty() {
+        return Err(ApiError::conflict(
+            "transition_requirements_missing",
+            format!("moving from {} to {} requires: {}", from, to, missing.join(", ")),
+        )
+        .with("rule", rule)
+        .with("missing", missing));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support;
+
+    #[tokio::test]
+    async fn allowed_roles_admit_higher_board_roles() {
+        let pool = test_support::memory_db().await;
+        let mut conn = pool.acquire().await.unwrap();
+        sqlx::query("UPDATE workflow_transitions SET allowed_roles = 'member' WHERE workflow = 'staged' AND to_state = 'Done'")
+            .execute(&mut *conn)
+            .await
+            .unwrap();
+        let before = sqlx::query_as::<_, Task>(
+            "INSERT INTO tasks (title, board_id, status, stage) VALUES ('task', 1, 'InProgress', '리뷰') RETURNING *"
+        )
+        .fetch_one(&mut *conn)
+        .await
+        .unwrap();
+        let after = Task { status: "Done".to_string(), stage: None, ..before.clone() };
+
+        for (role, allowed) in [
+            (Some(BoardRole::Admin), true),
+            (Some(BoardRole::Member), true),
+            (Some(BoardRole::Viewer), false),
+            (None, false),
+        ] {
+            let result = check_transition(&mut conn, "staged", &before, &after, Some("done"), role).await;
+            assert_eq!(result.is_ok(), allowed, "{:?}", role);
+        }
+    }
+}